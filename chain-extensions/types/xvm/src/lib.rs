@@ -27,11 +27,59 @@ use sp_std::vec::Vec;
 pub enum XvmExecutionResult {
     /// Success
     Success = 0,
-    // TODO: expand this with concrete XVM errors
+    /// Target VM isn't known
+    VmNotRecognized = 1,
+    /// Call destination or arguments couldn't be decoded
+    EncodingFailure = 2,
+    /// Caller couldn't be represented in the target VM
+    ContextConversionFailed = 3,
+    /// Call ran out of gas
+    OutOfGas = 4,
+    /// VM specific failure, output holds the exit reason
+    ExecutionError = 5,
+    /// Callee reverted, output holds the revert data
+    Reverted = 6,
+    /// Call destination doesn't exist or isn't a contract
+    InvalidTarget = 7,
+    /// Recipient inbox is full
+    InboxFull = 8,
+    /// Message is too large
+    MessageTooLarge = 9,
+    /// No more deliveries can be scheduled
+    DeliveryQueueFull = 10,
+    /// Too many nested XVM calls
+    CallDepthExceeded = 11,
+    /// Call re-enters a target protected against reentrancy
+    ReentrantCall = 12,
+    /// Target VM is disabled, or the call target isn't allowed
+    CallBlocked = 13,
     /// Error not (yet) covered by a dedidacted code
     UnknownError = 255,
 }
 
+impl From<u8> for XvmExecutionResult {
+    /// Maps an XVM error code onto the matching result, unknown codes become `UnknownError`.
+    fn from(code: u8) -> Self {
+        match code {
+            0 => XvmExecutionResult::Success,
+            1 => XvmExecutionResult::VmNotRecognized,
+            2 => XvmExecutionResult::EncodingFailure,
+            3 => XvmExecutionResult::ContextConversionFailed,
+            4 => XvmExecutionResult::OutOfGas,
+            5 => XvmExecutionResult::ExecutionError,
+            6 => XvmExecutionResult::Reverted,
+            7 => XvmExecutionResult::InvalidTarget,
+            8 => XvmExecutionResult::InboxFull,
+            9 => XvmExecutionResult::MessageTooLarge,
+            10 => XvmExecutionResult::DeliveryQueueFull,
+            11 => XvmExecutionResult::CallDepthExceeded,
+            12 => XvmExecutionResult::ReentrantCall,
+            13 => XvmExecutionResult::CallBlocked,
+            _ => XvmExecutionResult::UnknownError,
+        }
+    }
+}
+
 impl TryFrom<DispatchError> for XvmExecutionResult {
     type Error = DispatchError;
 
@@ -56,5 +104,31 @@ pub struct XvmCallArgs {
     pub input: Vec<u8>,
 }
 
+#[derive(Clone, PartialEq, Eq, Encode, Decode, Debug)]
+pub struct XvmCallWithValueArgs {
+    /// virtual machine identifier
+    pub vm_id: u8,
+    /// Call destination (e.g. address)
+    pub to: Vec<u8>,
+    /// Encoded call params
+    pub input: Vec<u8>,
+    /// Native value transferred to the callee from the calling contract balance
+    pub value: u128,
+}
+
+#[derive(Clone, PartialEq, Eq, Encode, Decode, Debug)]
+pub struct XvmInstantiateArgs {
+    /// virtual machine identifier
+    pub vm_id: u8,
+    /// Contract code
+    pub code: Vec<u8>,
+    /// Encoded constructor params
+    pub input: Vec<u8>,
+    /// Salt making the contract address deterministic
+    pub salt: Vec<u8>,
+    /// Native value transferred to the new contract from the calling contract balance
+    pub value: u128,
+}
+
 pub const FRONTIER_VM_ID: u8 = 0x0F;
 pub const PARITY_WASM_VM_ID: u8 = 0x1F;
@@ -18,17 +18,28 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
-use frame_support::dispatch::Encode;
-use frame_support::weights::Weight;
+use frame_support::{dispatch::Encode, traits::Get};
 use pallet_contracts::chain_extension::{ChainExtension, Environment, Ext, InitState, RetVal};
-use pallet_xvm::XvmContext;
+use pallet_unified_accounts::UnifiedAddressMapper;
+use pallet_xvm::{WeightInfo, XvmContext};
 use sp_runtime::DispatchError;
 use sp_std::marker::PhantomData;
-use xvm_chain_extension_types::{XvmCallArgs, XvmExecutionResult};
+use xvm_chain_extension_types::{
+    XvmCallArgs, XvmCallWithValueArgs, XvmExecutionResult, XvmInstantiateArgs, PARITY_WASM_VM_ID,
+};
 
+/// XVM calls and instantiations are made by the calling contract, value is transferred
+/// from its own balance.
 enum XvmFuncId {
     XvmCall,
-    // TODO: expand with other calls too
+    /// Read-only call, state changes of the callee are discarded.
+    XvmQueryCall,
+    /// Contract instantiation, the output is the encoded address of the new contract.
+    XvmInstantiate,
+    /// Call transferring native value to the callee.
+    XvmCallWithValue,
+    /// EVM address of the calling contract, the caller seen by the EVM contracts it calls.
+    XvmEvmAddress,
 }
 
 impl TryFrom<u16> for XvmFuncId {
@@ -37,6 +48,10 @@ impl TryFrom<u16> for XvmFuncId {
     fn try_from(value: u16) -> Result<Self, Self::Error> {
         match value {
             1 => Ok(XvmFuncId::XvmCall),
+            2 => Ok(XvmFuncId::XvmQueryCall),
+            3 => Ok(XvmFuncId::XvmInstantiate),
+            4 => Ok(XvmFuncId::XvmCallWithValue),
+            5 => Ok(XvmFuncId::XvmEvmAddress),
             _ => Err(DispatchError::Other(
                 "Unsupported func id in Xvm chain extension",
             )),
@@ -45,17 +60,20 @@ impl TryFrom<u16> for XvmFuncId {
 }
 
 /// XVM chain extension.
-pub struct XvmExtension<T>(PhantomData<T>);
+///
+/// Contracts are mapped to their EVM address by `M`, like the EVM backend of XVM does.
+pub struct XvmExtension<T, M>(PhantomData<(T, M)>);
 
-impl<T> Default for XvmExtension<T> {
+impl<T, M> Default for XvmExtension<T, M> {
     fn default() -> Self {
         XvmExtension(PhantomData)
     }
 }
 
-impl<T> ChainExtension<T> for XvmExtension<T>
+impl<T, M> ChainExtension<T> for XvmExtension<T, M>
 where
     T: pallet_contracts::Config + pallet_xvm::Config,
+    M: UnifiedAddressMapper<T::AccountId>,
 {
     fn call<E: Ext>(&mut self, env: Environment<E, InitState>) -> Result<RetVal, DispatchError>
     where
@@ -65,32 +83,54 @@ where
         let mut env = env.buf_in_buf_out();
 
         match func_id {
-            XvmFuncId::XvmCall => {
+            XvmFuncId::XvmCall | XvmFuncId::XvmQueryCall | XvmFuncId::XvmCallWithValue => {
+                let query = matches!(func_id, XvmFuncId::XvmQueryCall);
+
+                // XVM dispatch overhead is charged upfront, the remaining gas is the call budget.
+                env.charge_weight(<T as pallet_xvm::Config>::WeightInfo::xvm_call())?;
                 // We need to immediately charge for the worst case scenario. Gas equals Weight in pallet-contracts context.
                 let remaining_weight = env.ext().gas_meter().gas_left();
-                // We don't track used proof size, so we can't refund after.
-                // So we will charge a 32KB dummy value as a temporary replacement.
-                let charged_weight =
-                    env.charge_weight(remaining_weight.set_proof_size(32 * 1024))?;
-
-                let caller = env.ext().caller().clone();
-
-                let XvmCallArgs { vm_id, to, input } = env.read_as_unbounded(env.in_len())?;
+                let charged_weight = env.charge_weight(remaining_weight)?;
+
+                let source = env.ext().address().clone();
+
+                let XvmCallWithValueArgs {
+                    vm_id,
+                    to,
+                    input,
+                    value,
+                } = match func_id {
+                    XvmFuncId::XvmCallWithValue => env.read_as_unbounded(env.in_len())?,
+                    _ => {
+                        let XvmCallArgs { vm_id, to, input } =
+                            env.read_as_unbounded(env.in_len())?;
+                        XvmCallWithValueArgs {
+                            vm_id,
+                            to,
+                            input,
+                            value: 0,
+                        }
+                    }
+                };
 
-                let _origin_address = env.ext().address().clone();
-                let _value = env.ext().value_transferred();
                 let xvm_context = XvmContext {
                     id: vm_id,
                     max_weight: remaining_weight,
                     env: None,
+                    value,
+                    query,
+                    ..Default::default()
                 };
 
-                let call_result =
-                    pallet_xvm::Pallet::<T>::xvm_bare_call(xvm_context, caller, to, input);
+                let call_result = pallet_xvm::Pallet::<T>::in_origin_frame(
+                    PARITY_WASM_VM_ID,
+                    source.encode(),
+                    || pallet_xvm::Pallet::<T>::xvm_bare_call(xvm_context, source, to, input),
+                );
 
+                // Refunds both unused execution time and proof size.
                 let actual_weight = pallet_xvm::consumed_weight(&call_result);
-                // TODO: implement proof of size refund.
-                env.adjust_weight(charged_weight, Weight::from_ref_time(actual_weight));
+                env.adjust_weight(charged_weight, actual_weight);
 
                 match call_result {
                     Ok(success) => {
@@ -110,11 +150,92 @@ where
                             "failure: {:?}", failure
                         );
 
-                        // TODO Propagate error
-                        Ok(RetVal::Converging(XvmExecutionResult::UnknownError as u32))
+                        // Revert data or exit reason is handed back to the contract.
+                        let buffer: sp_std::vec::Vec<_> = failure.error().data().encode();
+                        env.write(&buffer, false, None)?;
+                        Ok(RetVal::Converging(
+                            XvmExecutionResult::from(failure.error().code()) as u32,
+                        ))
                     }
                 }
             }
+
+            XvmFuncId::XvmInstantiate => {
+                // XVM dispatch overhead is charged upfront, the remaining gas is the deployment budget.
+                env.charge_weight(<T as pallet_xvm::Config>::WeightInfo::xvm_instantiate())?;
+                let remaining_weight = env.ext().gas_meter().gas_left();
+                let charged_weight = env.charge_weight(remaining_weight)?;
+
+                let source = env.ext().address().clone();
+
+                let XvmInstantiateArgs {
+                    vm_id,
+                    code,
+                    input,
+                    salt,
+                    value,
+                } = env.read_as_unbounded(env.in_len())?;
+
+                let xvm_context = XvmContext {
+                    id: vm_id,
+                    max_weight: remaining_weight,
+                    env: None,
+                    value,
+                    ..Default::default()
+                };
+
+                let instantiate_result = pallet_xvm::Pallet::<T>::in_origin_frame(
+                    PARITY_WASM_VM_ID,
+                    source.encode(),
+                    || {
+                        pallet_xvm::Pallet::<T>::xvm_bare_instantiate(
+                            xvm_context,
+                            source,
+                            code,
+                            input,
+                            salt,
+                        )
+                    },
+                );
+
+                let actual_weight = pallet_xvm::consumed_weight(&instantiate_result);
+                env.adjust_weight(charged_weight, actual_weight);
+
+                match instantiate_result {
+                    Ok(success) => {
+                        log::trace!(
+                            target: "xvm-extension::xvm_instantiate",
+                            "success: {:?}", success
+                        );
+
+                        let buffer: sp_std::vec::Vec<_> = success.output().encode();
+                        env.write(&buffer, false, None)?;
+                        Ok(RetVal::Converging(XvmExecutionResult::Success as u32))
+                    }
+
+                    Err(failure) => {
+                        log::trace!(
+                            target: "xvm-extension::xvm_instantiate",
+                            "failure: {:?}", failure
+                        );
+
+                        let buffer: sp_std::vec::Vec<_> = failure.error().data().encode();
+                        env.write(&buffer, false, None)?;
+                        Ok(RetVal::Converging(
+                            XvmExecutionResult::from(failure.error().code()) as u32,
+                        ))
+                    }
+                }
+            }
+
+            XvmFuncId::XvmEvmAddress => {
+                // Lookup of the claimed address.
+                env.charge_weight(T::DbWeight::get().reads(1))?;
+
+                let address = M::to_h160_or_default(env.ext().address());
+                env.write(&address.encode(), false, None)?;
+                Ok(RetVal::Converging(XvmExecutionResult::Success as u32))
+            }
         }
     }
 }
@@ -0,0 +1,49 @@
+// This file is part of Astar.
+
+// Copyright (C) 2019-2023 Stake Technologies Pte.Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Runtime API definition for the collator selection pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+// These clippy lints are disabled because the macro-generated code triggers them.
+#![allow(clippy::unnecessary_mut_passed)]
+#![allow(clippy::too_many_arguments)]
+
+pub use pallet_collator_selection::CandidateStats;
+use parity_scale_codec::Codec;
+use sp_std::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+    /// Collator set and candidate standing queries.
+    pub trait CollatorSelectionApi<AccountId, Balance, BlockNumber>
+    where
+        AccountId: Codec,
+        Balance: Codec,
+        BlockNumber: Codec,
+    {
+        /// The invulnerable, fixed collators.
+        fn invulnerables() -> Vec<AccountId>;
+
+        /// Collation candidates with their deposits and authoring statistics.
+        fn candidates() -> Vec<CandidateStats<AccountId, Balance, BlockNumber>>;
+
+        /// Reward the next block author will receive from the pot.
+        fn pot_reward() -> Balance;
+
+        /// Collator set the next session would be assembled with if it started now.
+        fn projected_collators() -> Vec<AccountId>;
+    }
+}
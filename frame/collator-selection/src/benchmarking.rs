@@ -28,6 +28,7 @@ use frame_support::{
     assert_ok,
     codec::Decode,
     traits::{Currency, EnsureOrigin, Get},
+    BoundedVec,
 };
 use frame_system::{EventRecord, RawOrigin};
 use pallet_authorship::EventHandler;
@@ -129,6 +130,47 @@ benchmarks! {
         assert_last_event::<T>(Event::NewInvulnerables(new_invulnerables).into());
     }
 
+    // worst case is the new invulnerable being the last of `c` candidates.
+    add_invulnerable {
+        let b in 1 .. T::MaxInvulnerables::get() - 1;
+        let c in 1 .. T::MaxCandidates::get();
+        <CandidacyBond<T>>::put(T::Currency::minimum_balance());
+        <DesiredCandidates<T>>::put(c);
+
+        register_validators::<T>(c);
+        register_candidates::<T>(c);
+
+        let invulnerables = (0..b).map(|i| account("invulnerable", i, SEED)).collect::<Vec<_>>();
+        <Invulnerables<T>>::put(BoundedVec::truncate_from(invulnerables));
+
+        let new_invulnerable = <Candidates<T>>::get().last().unwrap().who.clone();
+        let origin = T::UpdateOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+    }: {
+        assert_ok!(
+            <CollatorSelection<T>>::add_invulnerable(origin, new_invulnerable.clone())
+        );
+    }
+    verify {
+        assert!(!<Candidates<T>>::get().iter().any(|candidate| candidate.who == new_invulnerable));
+        assert_last_event::<T>(Event::InvulnerableAdded(new_invulnerable).into());
+    }
+
+    remove_invulnerable {
+        let b in 1 .. T::MaxInvulnerables::get();
+        let invulnerables = register_validators::<T>(b);
+        <Invulnerables<T>>::put(BoundedVec::truncate_from(invulnerables.clone()));
+
+        let leaving = invulnerables.last().unwrap().clone();
+        let origin = T::UpdateOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+    }: {
+        assert_ok!(
+            <CollatorSelection<T>>::remove_invulnerable(origin, leaving.clone())
+        );
+    }
+    verify {
+        assert_last_event::<T>(Event::InvulnerableRemoved(leaving).into());
+    }
+
     set_desired_candidates {
         let max: u32 = 148;
         let origin = T::UpdateOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
@@ -46,6 +46,11 @@
 //! Candidates will not be allowed to get kicked or leave_intent if the total number of candidates
 //! fall below MinCandidates. This is for potential disaster recovery scenarios.
 //!
+//! Session keys of candidates are re-validated on every new session. Candidates without registered
+//! keys are left out of the next collator set, without being kicked or slashed, and are included
+//! again once their keys are set. As they can't author blocks meanwhile, their kick threshold is
+//! restarted on every session they miss.
+//!
 //! ### Rewards
 //!
 //! The Collator Selection pallet maintains an on-chain account (the "Pot"). In each block, the
@@ -57,6 +62,13 @@
 //!
 //! To initiate rewards an ED needs to be transferred to the pot address.
 //!
+//! ### Slashing
+//!
+//! Candidates which didn't author a block within `KickThreshold` are kicked on session change and
+//! lose `SlashRatio` of their bond. The slashed funds go to `SlashDestination` if it is set,
+//! otherwise they are handed over to `Config::OnSlash`, which can e.g. deposit them into the
+//! treasury, split them or burn them.
+//!
 //! Note: Eventually the Pot distribution may be modified as discussed in
 //! [this issue](https://github.com/paritytech/statemint/issues/21#issuecomment-810481073).
 
@@ -72,6 +84,7 @@ mod tests;
 
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
+pub mod migrations;
 pub mod weights;
 
 #[frame_support::pallet]
@@ -87,10 +100,10 @@ pub mod pallet {
             RuntimeDebug,
         },
         traits::{
-            Currency, EnsureOrigin, ExistenceRequirement::KeepAlive, ReservableCurrency,
-            ValidatorRegistration,
+            Currency, EnsureOrigin, ExistenceRequirement::KeepAlive, Imbalance, OnUnbalanced,
+            ReservableCurrency, ValidatorRegistration,
         },
-        PalletId,
+        BoundedVec, PalletId,
     };
     use frame_system::{pallet_prelude::*, Config as SystemConfig};
     use pallet_session::SessionManager;
@@ -99,6 +112,11 @@ pub mod pallet {
 
     type BalanceOf<T> =
         <<T as Config>::Currency as Currency<<T as SystemConfig>::AccountId>>::Balance;
+    type NegativeImbalanceOf<T> =
+        <<T as Config>::Currency as Currency<<T as SystemConfig>::AccountId>>::NegativeImbalance;
+
+    /// The current storage version.
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
 
     /// A convertor from collators id. Since this pallet does not have stash/controller, this is
     /// just identity.
@@ -124,8 +142,7 @@ pub mod pallet {
         /// Account Identifier from which the internal Pot is generated.
         type PotId: Get<PalletId>;
 
-        /// Maximum number of candidates that we should have. This is used for benchmarking and is not
-        /// enforced.
+        /// Maximum number of candidates that we should have.
         ///
         /// This does not take into account the invulnerables.
         type MaxCandidates: Get<u32>;
@@ -136,8 +153,6 @@ pub mod pallet {
         type MinCandidates: Get<u32>;
 
         /// Maximum number of invulnerables.
-        ///
-        /// Used only for benchmarking.
         type MaxInvulnerables: Get<u32>;
 
         // Will be kicked if block is not produced in threshold.
@@ -157,12 +172,18 @@ pub mod pallet {
         /// How many in perc kicked collators should be slashed (set 0 to disable)
         type SlashRatio: Get<Perbill>;
 
+        /// Handler for the slashed part of a kicked candidate's bond, used when no
+        /// `SlashDestination` is set. Use `()` to burn it.
+        type OnSlash: OnUnbalanced<NegativeImbalanceOf<Self>>;
+
         /// The weight information of this pallet.
         type WeightInfo: WeightInfo;
     }
 
     /// Basic information about a collation candidate.
-    #[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, scale_info::TypeInfo)]
+    #[derive(
+        PartialEq, Eq, Clone, Encode, Decode, MaxEncodedLen, RuntimeDebug, scale_info::TypeInfo,
+    )]
     pub struct CandidateInfo<AccountId, Balance> {
         /// Account identifier.
         pub who: AccountId,
@@ -170,21 +191,38 @@ pub mod pallet {
         pub deposit: Balance,
     }
 
+    /// Collation candidate standing, as exposed through the runtime API.
+    #[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, scale_info::TypeInfo)]
+    pub struct CandidateStats<AccountId, Balance, BlockNumber> {
+        /// Account identifier.
+        pub who: AccountId,
+        /// Reserved deposit.
+        pub deposit: Balance,
+        /// Last block authored by the candidate.
+        pub last_authored_block: BlockNumber,
+        /// Blocks left until the candidate reaches the kick threshold.
+        pub blocks_until_kick: BlockNumber,
+    }
+
     #[pallet::pallet]
     #[pallet::generate_store(pub(super) trait Store)]
-    #[pallet::without_storage_info]
+    #[pallet::storage_version(STORAGE_VERSION)]
     pub struct Pallet<T>(_);
 
     /// The invulnerable, fixed collators.
     #[pallet::storage]
     #[pallet::getter(fn invulnerables)]
-    pub type Invulnerables<T: Config> = StorageValue<_, Vec<T::AccountId>, ValueQuery>;
+    pub type Invulnerables<T: Config> =
+        StorageValue<_, BoundedVec<T::AccountId, T::MaxInvulnerables>, ValueQuery>;
 
     /// The (community, limited) collation candidates.
     #[pallet::storage]
     #[pallet::getter(fn candidates)]
-    pub type Candidates<T: Config> =
-        StorageValue<_, Vec<CandidateInfo<T::AccountId, BalanceOf<T>>>, ValueQuery>;
+    pub type Candidates<T: Config> = StorageValue<
+        _,
+        BoundedVec<CandidateInfo<T::AccountId, BalanceOf<T>>, T::MaxCandidates>,
+        ValueQuery,
+    >;
 
     /// Last block authored by collator.
     #[pallet::storage]
@@ -241,10 +279,9 @@ pub mod pallet {
                 "duplicate invulnerables in genesis."
             );
 
-            assert!(
-                T::MaxInvulnerables::get() >= (self.invulnerables.len() as u32),
-                "genesis invulnerables are more than T::MaxInvulnerables",
-            );
+            let bounded_invulnerables =
+                BoundedVec::<_, T::MaxInvulnerables>::try_from(self.invulnerables.clone())
+                    .expect("genesis invulnerables are more than T::MaxInvulnerables");
             assert!(
                 T::MaxCandidates::get() >= self.desired_candidates,
                 "genesis desired_candidates are more than T::MaxCandidates",
@@ -252,7 +289,7 @@ pub mod pallet {
 
             <DesiredCandidates<T>>::put(&self.desired_candidates);
             <CandidacyBond<T>>::put(&self.candidacy_bond);
-            <Invulnerables<T>>::put(&self.invulnerables);
+            <Invulnerables<T>>::put(bounded_invulnerables);
         }
     }
 
@@ -264,7 +301,13 @@ pub mod pallet {
         NewCandidacyBond(BalanceOf<T>),
         CandidateAdded(T::AccountId, BalanceOf<T>),
         CandidateRemoved(T::AccountId),
-        CandidateSlashed(T::AccountId),
+        /// A candidate was slashed by the given amount. Slashed funds were deposited into the
+        /// given account, or handed over to `Config::OnSlash` if it is `None`.
+        CandidateSlashed(T::AccountId, BalanceOf<T>, Option<T::AccountId>),
+        InvulnerableAdded(T::AccountId),
+        InvulnerableRemoved(T::AccountId),
+        /// Candidate has no registered session keys and was left out of the next collator set.
+        CandidateKeysMissing(T::AccountId),
     }
 
     // Errors inform users that something went wrong.
@@ -288,6 +331,10 @@ pub mod pallet {
         NoAssociatedValidatorId,
         /// Validator ID is not yet registered
         ValidatorNotRegistered,
+        /// Too many invulnerables
+        TooManyInvulnerables,
+        /// User is not an Invulnerable
+        NotInvulnerable,
     }
 
     #[pallet::hooks]
@@ -303,24 +350,15 @@ pub mod pallet {
             new: Vec<T::AccountId>,
         ) -> DispatchResultWithPostInfo {
             T::UpdateOrigin::ensure_origin(origin)?;
-            // we trust origin calls, this is just a for more accurate benchmarking
-            if (new.len() as u32) > T::MaxInvulnerables::get() {
-                log::warn!(
-                    "invulnerables > T::MaxInvulnerables; you might need to run benchmarks again"
-                );
-            }
+            let bounded_invulnerables = BoundedVec::<_, T::MaxInvulnerables>::try_from(new.clone())
+                .map_err(|_| Error::<T>::TooManyInvulnerables)?;
 
             // check if the invulnerables have associated validator keys before they are set
             for account_id in &new {
-                let validator_key = T::ValidatorIdOf::convert(account_id.clone())
-                    .ok_or(Error::<T>::NoAssociatedValidatorId)?;
-                ensure!(
-                    T::ValidatorRegistration::is_registered(&validator_key),
-                    Error::<T>::ValidatorNotRegistered
-                );
+                Self::ensure_keys_registered(account_id)?;
             }
 
-            <Invulnerables<T>>::put(&new);
+            <Invulnerables<T>>::put(bounded_invulnerables);
             Self::deposit_event(Event::NewInvulnerables(new));
             Ok(().into())
         }
@@ -377,12 +415,7 @@ pub mod pallet {
                 Error::<T>::AlreadyInvulnerable
             );
 
-            let validator_key = T::ValidatorIdOf::convert(who.clone())
-                .ok_or(Error::<T>::NoAssociatedValidatorId)?;
-            ensure!(
-                T::ValidatorRegistration::is_registered(&validator_key),
-                Error::<T>::ValidatorNotRegistered
-            );
+            Self::ensure_keys_registered(&who)?;
 
             let deposit = Self::candidacy_bond();
             // First authored block is current block plus kick threshold to handle session delay
@@ -397,7 +430,9 @@ pub mod pallet {
                         Err(Error::<T>::AlreadyCandidate)?
                     } else {
                         T::Currency::reserve(&who, deposit)?;
-                        candidates.push(incoming);
+                        candidates
+                            .try_push(incoming)
+                            .map_err(|_| Error::<T>::TooManyCandidates)?;
                         <LastAuthoredBlock<T>>::insert(
                             who.clone(),
                             frame_system::Pallet::<T>::block_number() + T::KickThreshold::get(),
@@ -428,6 +463,76 @@ pub mod pallet {
 
             Ok(Some(T::WeightInfo::leave_intent(current_count as u32)).into())
         }
+
+        /// Add a new account `who` to the list of `Invulnerables` collators.
+        ///
+        /// The account must have registered session keys. If the account is currently a
+        /// candidate, its candidacy is dropped and the `CandidacyBond` is unreserved.
+        ///
+        /// The origin for this call must be the `UpdateOrigin`.
+        #[pallet::call_index(5)]
+        #[pallet::weight(T::WeightInfo::add_invulnerable(
+            T::MaxInvulnerables::get().saturating_sub(1),
+            T::MaxCandidates::get()
+        ))]
+        pub fn add_invulnerable(
+            origin: OriginFor<T>,
+            who: T::AccountId,
+        ) -> DispatchResultWithPostInfo {
+            T::UpdateOrigin::ensure_origin(origin)?;
+            Self::ensure_keys_registered(&who)?;
+
+            let current_count =
+                <Invulnerables<T>>::try_mutate(|invulnerables| -> Result<usize, DispatchError> {
+                    ensure!(
+                        !invulnerables.contains(&who),
+                        Error::<T>::AlreadyInvulnerable
+                    );
+                    invulnerables
+                        .try_push(who.clone())
+                        .map_err(|_| Error::<T>::TooManyInvulnerables)?;
+                    Ok(invulnerables.len())
+                })?;
+
+            // an invulnerable must not hold a candidacy at the same time
+            let candidates = Self::candidates();
+            if candidates.iter().any(|c| c.who == who) {
+                Self::try_remove_candidate(&who, false)?;
+            }
+
+            Self::deposit_event(Event::InvulnerableAdded(who));
+            Ok(Some(T::WeightInfo::add_invulnerable(
+                (current_count as u32).saturating_sub(1),
+                candidates.len() as u32,
+            ))
+            .into())
+        }
+
+        /// Remove an account `who` from the list of `Invulnerables` collators.
+        ///
+        /// The origin for this call must be the `UpdateOrigin`.
+        #[pallet::call_index(6)]
+        #[pallet::weight(T::WeightInfo::remove_invulnerable(T::MaxInvulnerables::get()))]
+        pub fn remove_invulnerable(
+            origin: OriginFor<T>,
+            who: T::AccountId,
+        ) -> DispatchResultWithPostInfo {
+            T::UpdateOrigin::ensure_origin(origin)?;
+
+            let previous_count =
+                <Invulnerables<T>>::try_mutate(|invulnerables| -> Result<usize, DispatchError> {
+                    let previous_count = invulnerables.len();
+                    let index = invulnerables
+                        .iter()
+                        .position(|x| *x == who)
+                        .ok_or(Error::<T>::NotInvulnerable)?;
+                    invulnerables.remove(index);
+                    Ok(previous_count)
+                })?;
+
+            Self::deposit_event(Event::InvulnerableRemoved(who));
+            Ok(Some(T::WeightInfo::remove_invulnerable(previous_count as u32)).into())
+        }
     }
 
     impl<T: Config> Pallet<T> {
@@ -435,6 +540,81 @@ pub mod pallet {
         pub fn account_id() -> T::AccountId {
             T::PotId::get().into_account_truncating()
         }
+
+        /// Reward the next block author will receive from the pot.
+        ///
+        /// This is half of the pot balance above the existential deposit.
+        pub fn pot_reward() -> BalanceOf<T> {
+            T::Currency::free_balance(&Self::account_id())
+                .checked_sub(&T::Currency::minimum_balance())
+                .unwrap_or_else(Zero::zero)
+                .div(2u32.into())
+        }
+
+        /// Current candidates together with their authoring statistics.
+        pub fn candidate_stats() -> Vec<CandidateStats<T::AccountId, BalanceOf<T>, T::BlockNumber>>
+        {
+            let now = frame_system::Pallet::<T>::block_number();
+            let kick_threshold = T::KickThreshold::get();
+            Self::candidates()
+                .into_iter()
+                .map(|c| {
+                    let last_authored_block = <LastAuthoredBlock<T>>::get(&c.who);
+                    CandidateStats {
+                        who: c.who,
+                        deposit: c.deposit,
+                        last_authored_block,
+                        blocks_until_kick: last_authored_block
+                            .saturating_add(kick_threshold)
+                            .saturating_sub(now),
+                    }
+                })
+                .collect()
+        }
+
+        /// Collator set the next session would be assembled with if it started now.
+        ///
+        /// Mirrors [`Self::kick_stale_candidates`] without touching storage.
+        pub fn projected_collators() -> Vec<T::AccountId> {
+            let now = frame_system::Pallet::<T>::block_number();
+            let kick_threshold = T::KickThreshold::get();
+            let candidates = Self::candidates();
+            let mut remaining = candidates.len() as u32;
+            let active_candidates = candidates
+                .into_iter()
+                .filter_map(|c| {
+                    let since_last = now.saturating_sub(<LastAuthoredBlock<T>>::get(&c.who));
+                    let is_stale = since_last >= kick_threshold;
+                    if !Self::keys_registered(&c.who) {
+                        None
+                    } else if !is_stale || remaining <= T::MinCandidates::get() {
+                        Some(c.who)
+                    } else {
+                        remaining.saturating_dec();
+                        None
+                    }
+                })
+                .collect::<Vec<_>>();
+            Self::assemble_collators(active_candidates)
+        }
+
+        /// Ensures that `who` has an associated validator ID with registered session keys.
+        fn ensure_keys_registered(who: &T::AccountId) -> DispatchResult {
+            let validator_key = T::ValidatorIdOf::convert(who.clone())
+                .ok_or(Error::<T>::NoAssociatedValidatorId)?;
+            ensure!(
+                T::ValidatorRegistration::is_registered(&validator_key),
+                Error::<T>::ValidatorNotRegistered
+            );
+            Ok(())
+        }
+
+        /// Whether `who` has an associated validator ID with registered session keys.
+        fn keys_registered(who: &T::AccountId) -> bool {
+            T::ValidatorIdOf::convert(who.clone())
+                .map_or(false, |key| T::ValidatorRegistration::is_registered(&key))
+        }
+
         /// Removes a candidate if they exist and sends them back their deposit
         /// If second argument is `true` then a candidate will be slashed
         fn try_remove_candidate(who: &T::AccountId, slash: bool) -> Result<usize, DispatchError> {
@@ -453,11 +633,19 @@ pub mod pallet {
                         let (imbalance, _) = T::Currency::slash_reserved(who, slash);
                         T::Currency::unreserve(who, remain);
 
-                        if let Some(dest) = Self::slash_destination() {
-                            T::Currency::resolve_creating(&dest, imbalance);
+                        let slashed = imbalance.peek();
+                        let destination = Self::slash_destination();
+                        if let Some(dest) = &destination {
+                            T::Currency::resolve_creating(dest, imbalance);
+                        } else {
+                            T::OnSlash::on_unbalanced(imbalance);
                         }
 
-                        Self::deposit_event(Event::CandidateSlashed(who.clone()));
+                        Self::deposit_event(Event::CandidateSlashed(
+                            who.clone(),
+                            slashed,
+                            destination,
+                        ));
                     } else {
                         T::Currency::unreserve(who, deposit);
                     }
@@ -473,11 +661,14 @@ pub mod pallet {
         ///
         /// This is done on the fly, as frequent as we are told to do so, as the session manager.
         pub fn assemble_collators(candidates: Vec<T::AccountId>) -> Vec<T::AccountId> {
-            let mut collators = Self::invulnerables();
+            let mut collators = Self::invulnerables().into_inner();
             collators.extend(candidates.into_iter().collect::<Vec<_>>());
             collators
         }
         /// Kicks out and candidates that did not produce a block in the kick threshold.
+        ///
+        /// Candidates without registered session keys are left out of the collator set. They
+        /// can't author blocks, so their kick threshold is restarted instead of kicking them.
         pub fn kick_stale_candidates(
             candidates: Vec<CandidateInfo<T::AccountId, BalanceOf<T>>>,
         ) -> Vec<T::AccountId> {
@@ -486,11 +677,16 @@ pub mod pallet {
             candidates
                 .into_iter()
                 .filter_map(|c| {
+                    if !Self::keys_registered(&c.who) {
+                        <LastAuthoredBlock<T>>::insert(c.who.clone(), now + kick_threshold);
+                        Self::deposit_event(Event::CandidateKeysMissing(c.who));
+                        return None;
+                    }
+
                     let last_block = <LastAuthoredBlock<T>>::get(c.who.clone());
                     let since_last = now.saturating_sub(last_block);
-                    if since_last < kick_threshold
-                        || Self::candidates().len() as u32 <= T::MinCandidates::get()
-                    {
+                    let is_stale = since_last >= kick_threshold;
+                    if !is_stale || Self::candidates().len() as u32 <= T::MinCandidates::get() {
                         Some(c.who)
                     } else {
                         let outcome = Self::try_remove_candidate(&c.who, true);
@@ -513,10 +709,7 @@ pub mod pallet {
         fn note_author(author: T::AccountId) {
             let pot = Self::account_id();
             // assumes an ED will be sent to pot.
-            let reward = T::Currency::free_balance(&pot)
-                .checked_sub(&T::Currency::minimum_balance())
-                .unwrap_or_else(Zero::zero)
-                .div(2u32.into());
+            let reward = Self::pot_reward();
             // `reward` is half of pot account minus ED, this should never fail.
             let _success = T::Currency::transfer(&pot, &author, reward, KeepAlive);
             debug_assert!(_success.is_ok());
@@ -538,7 +731,7 @@ pub mod pallet {
                 <frame_system::Pallet<T>>::block_number(),
             );
 
-            let candidates = Self::candidates();
+            let candidates = Self::candidates().into_inner();
             let candidates_len_before = candidates.len();
             let active_candidates = Self::kick_stale_candidates(candidates);
             let active_candidates_len = active_candidates.len();
@@ -0,0 +1,116 @@
+// This file is part of Astar.
+
+// Copyright (C) 2019-2023 Stake Technologies Pte.Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Storage migrations for the collator selection pallet.
+
+use super::*;
+use frame_support::{
+    dispatch::GetStorageVersion,
+    pallet_prelude::*,
+    traits::{Currency, OnRuntimeUpgrade, ReservableCurrency},
+};
+use sp_std::{marker::PhantomData, vec::Vec};
+
+type BalanceOf<T> =
+    <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+/// Migrates `Invulnerables` and `Candidates` from unbounded `Vec` into `BoundedVec`.
+///
+/// Entries exceeding `MaxInvulnerables` or `MaxCandidates` are dropped. Dropped candidates
+/// get their deposit unreserved.
+pub struct MigrateToBoundedStorage<T: Config>(PhantomData<T>);
+impl<T: Config> OnRuntimeUpgrade for MigrateToBoundedStorage<T> {
+    fn on_runtime_upgrade() -> Weight {
+        let version = Pallet::<T>::on_chain_storage_version();
+        let mut consumed_weight = T::DbWeight::get().reads(1);
+        if version >= 1 {
+            return consumed_weight;
+        }
+
+        // 1st value //
+        let _ = Invulnerables::<T>::translate::<Vec<T::AccountId>, _>(|maybe_invulnerables| {
+            let mut invulnerables = maybe_invulnerables.unwrap_or_default();
+            let max = T::MaxInvulnerables::get() as usize;
+            if invulnerables.len() > max {
+                log::warn!(
+                    "Dropping {} invulnerables exceeding T::MaxInvulnerables",
+                    invulnerables.len() - max
+                );
+                invulnerables.truncate(max);
+            }
+
+            Some(BoundedVec::truncate_from(invulnerables))
+        });
+        consumed_weight.saturating_accrue(T::DbWeight::get().reads_writes(1, 1));
+
+        // 2nd value //
+        let _ = Candidates::<T>::translate::<Vec<CandidateInfo<T::AccountId, BalanceOf<T>>>, _>(
+            |maybe_candidates| {
+                let mut candidates = maybe_candidates.unwrap_or_default();
+                let max = T::MaxCandidates::get() as usize;
+                if candidates.len() > max {
+                    log::warn!(
+                        "Dropping {} candidates exceeding T::MaxCandidates",
+                        candidates.len() - max
+                    );
+                    for dropped in candidates.drain(max..) {
+                        T::Currency::unreserve(&dropped.who, dropped.deposit);
+                        LastAuthoredBlock::<T>::remove(&dropped.who);
+                        consumed_weight.saturating_accrue(T::DbWeight::get().reads_writes(1, 2));
+                    }
+                }
+
+                Some(BoundedVec::truncate_from(candidates))
+            },
+        );
+        consumed_weight.saturating_accrue(T::DbWeight::get().reads_writes(1, 1));
+
+        StorageVersion::new(1).put::<Pallet<T>>();
+        consumed_weight.saturating_accrue(T::DbWeight::get().writes(1));
+
+        consumed_weight
+    }
+
+    #[cfg(feature = "try-runtime")]
+    fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+        assert!(Pallet::<T>::on_chain_storage_version() < 1);
+        let invulnerables_count = Invulnerables::<T>::decode_len().unwrap_or_default() as u32;
+        let candidates_count = Candidates::<T>::decode_len().unwrap_or_default() as u32;
+
+        Ok((invulnerables_count, candidates_count).encode())
+    }
+
+    #[cfg(feature = "try-runtime")]
+    fn post_upgrade(state: Vec<u8>) -> Result<(), &'static str> {
+        assert_eq!(Pallet::<T>::on_chain_storage_version(), 1);
+
+        let (invulnerables_count, candidates_count): (u32, u32) =
+            Decode::decode(&mut state.as_ref())
+                .map_err(|_| "Cannot decode data from pre_upgrade")?;
+
+        assert_eq!(
+            Invulnerables::<T>::get().len() as u32,
+            invulnerables_count.min(T::MaxInvulnerables::get())
+        );
+        assert_eq!(
+            Candidates::<T>::get().len() as u32,
+            candidates_count.min(T::MaxCandidates::get())
+        );
+
+        Ok(())
+    }
+}
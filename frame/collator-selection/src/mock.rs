@@ -19,7 +19,7 @@ use super::*;
 use crate as collator_selection;
 use frame_support::{
     ord_parameter_types, parameter_types,
-    traits::{FindAuthor, GenesisBuild, ValidatorRegistration},
+    traits::{FindAuthor, GenesisBuild, Imbalance, OnUnbalanced, ValidatorRegistration},
     PalletId,
 };
 use frame_system as system;
@@ -197,14 +197,26 @@ parameter_types! {
     pub const SlashRatio: Perbill = Perbill::from_percent(10);
 }
 
+parameter_types! {
+    pub static UnregisteredKeys: Vec<u64> = vec![7];
+}
+
 pub struct IsRegistered;
 impl ValidatorRegistration<u64> for IsRegistered {
     fn is_registered(id: &u64) -> bool {
-        if *id == 7u64 {
-            false
-        } else {
-            true
-        }
+        !UnregisteredKeys::get().contains(id)
+    }
+}
+
+parameter_types! {
+    pub static SlashedToHandler: u64 = 0;
+}
+
+/// Records slashed amounts and burns them.
+pub struct RecordSlash;
+impl OnUnbalanced<pallet_balances::NegativeImbalance<Test>> for RecordSlash {
+    fn on_nonzero_unbalanced(amount: pallet_balances::NegativeImbalance<Test>) {
+        SlashedToHandler::mutate(|slashed| *slashed += amount.peek());
     }
 }
 
@@ -221,6 +233,7 @@ impl Config for Test {
     type ValidatorIdOf = IdentityCollator;
     type ValidatorRegistration = IsRegistered;
     type SlashRatio = SlashRatio;
+    type OnSlash = RecordSlash;
     type WeightInfo = ();
 }
 
@@ -16,10 +16,14 @@
 // limitations under the License.
 
 use crate as collator_selection;
-use crate::{mock::*, CandidateInfo, Error};
+use crate::{mock::*, CandidateInfo, CandidateStats, Error};
 use frame_support::{
     assert_noop, assert_ok,
-    traits::{Currency, GenesisBuild, OnInitialize},
+    dispatch::GetStorageVersion,
+    storage::unhashed,
+    traits::{
+        Currency, GenesisBuild, OnInitialize, OnRuntimeUpgrade, ReservableCurrency, StorageVersion,
+    },
 };
 use pallet_balances::Error as BalancesError;
 use sp_runtime::traits::BadOrigin;
@@ -63,6 +67,174 @@ fn it_should_set_invulnerables() {
     });
 }
 
+#[test]
+fn set_invulnerables_respects_max_invulnerables() {
+    new_test_ext().execute_with(|| {
+        let too_many = (1..=MaxInvulnerables::get() as u64 + 1).collect::<Vec<_>>();
+        assert_noop!(
+            CollatorSelection::set_invulnerables(
+                RuntimeOrigin::signed(RootAccount::get()),
+                too_many
+            ),
+            Error::<Test>::TooManyInvulnerables
+        );
+    });
+}
+
+#[test]
+fn add_invulnerable_works() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_eq!(CollatorSelection::invulnerables(), vec![1, 2]);
+
+        assert_ok!(CollatorSelection::add_invulnerable(
+            RuntimeOrigin::signed(RootAccount::get()),
+            3
+        ));
+        System::assert_last_event(RuntimeEvent::CollatorSelection(
+            crate::Event::InvulnerableAdded(3),
+        ));
+        assert_eq!(CollatorSelection::invulnerables(), vec![1, 2, 3]);
+
+        // cannot add with non-root.
+        assert_noop!(
+            CollatorSelection::add_invulnerable(RuntimeOrigin::signed(1), 4),
+            BadOrigin
+        );
+
+        // cannot add twice
+        assert_noop!(
+            CollatorSelection::add_invulnerable(RuntimeOrigin::signed(RootAccount::get()), 3),
+            Error::<Test>::AlreadyInvulnerable
+        );
+
+        // cannot add invulnerable without associated validator keys
+        assert_noop!(
+            CollatorSelection::add_invulnerable(RuntimeOrigin::signed(RootAccount::get()), 7),
+            Error::<Test>::ValidatorNotRegistered
+        );
+    });
+}
+
+#[test]
+fn add_invulnerable_respects_max_invulnerables() {
+    new_test_ext().execute_with(|| {
+        // 7 has no registered keys, so use accounts above it
+        let full_set = (10..10 + MaxInvulnerables::get() as u64).collect::<Vec<_>>();
+        assert_ok!(CollatorSelection::set_invulnerables(
+            RuntimeOrigin::signed(RootAccount::get()),
+            full_set
+        ));
+
+        assert_noop!(
+            CollatorSelection::add_invulnerable(RuntimeOrigin::signed(RootAccount::get()), 3),
+            Error::<Test>::TooManyInvulnerables
+        );
+    });
+}
+
+#[test]
+fn add_invulnerable_drops_candidacy() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(CollatorSelection::register_as_candidate(
+            RuntimeOrigin::signed(3)
+        ));
+        assert_eq!(Balances::free_balance(3), 90);
+
+        assert_ok!(CollatorSelection::add_invulnerable(
+            RuntimeOrigin::signed(RootAccount::get()),
+            3
+        ));
+
+        // candidacy is dropped and the bond is returned
+        assert!(CollatorSelection::candidates().is_empty());
+        assert_eq!(Balances::free_balance(3), 100);
+        assert_eq!(CollatorSelection::invulnerables(), vec![1, 2, 3]);
+    });
+}
+
+#[test]
+fn remove_invulnerable_works() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_eq!(CollatorSelection::invulnerables(), vec![1, 2]);
+
+        // cannot remove with non-root.
+        assert_noop!(
+            CollatorSelection::remove_invulnerable(RuntimeOrigin::signed(1), 1),
+            BadOrigin
+        );
+
+        assert_ok!(CollatorSelection::remove_invulnerable(
+            RuntimeOrigin::signed(RootAccount::get()),
+            1
+        ));
+        System::assert_last_event(RuntimeEvent::CollatorSelection(
+            crate::Event::InvulnerableRemoved(1),
+        ));
+        assert_eq!(CollatorSelection::invulnerables(), vec![2]);
+
+        // cannot remove an account which isn't invulnerable
+        assert_noop!(
+            CollatorSelection::remove_invulnerable(RuntimeOrigin::signed(RootAccount::get()), 1),
+            Error::<Test>::NotInvulnerable
+        );
+    });
+}
+
+#[test]
+fn migrate_to_bounded_storage_works() {
+    new_test_ext().execute_with(|| {
+        StorageVersion::new(0).put::<CollatorSelection>();
+
+        // legacy storage exceeding the bounds can't be decoded as `BoundedVec`
+        let legacy_invulnerables =
+            (100..100 + MaxInvulnerables::get() as u64 + 2).collect::<Vec<_>>();
+        unhashed::put(
+            &<crate::Invulnerables<Test>>::hashed_key(),
+            &legacy_invulnerables,
+        );
+
+        let deposit = 10;
+        let legacy_candidates = (200..200 + MaxCandidates::get() as u64 + 2)
+            .map(|who| {
+                Balances::make_free_balance_be(&who, 100);
+                assert_ok!(Balances::reserve(&who, deposit));
+                <crate::LastAuthoredBlock<Test>>::insert(who, 10);
+                CandidateInfo { who, deposit }
+            })
+            .collect::<Vec<_>>();
+        unhashed::put(&<crate::Candidates<Test>>::hashed_key(), &legacy_candidates);
+        assert!(CollatorSelection::invulnerables().is_empty());
+        assert!(CollatorSelection::candidates().is_empty());
+
+        crate::migrations::MigrateToBoundedStorage::<Test>::on_runtime_upgrade();
+
+        assert_eq!(CollatorSelection::on_chain_storage_version(), 1);
+        assert_eq!(
+            CollatorSelection::invulnerables(),
+            legacy_invulnerables[..MaxInvulnerables::get() as usize].to_vec()
+        );
+
+        // candidates above the bound are dropped and their deposit unreserved
+        let (kept, dropped) = legacy_candidates.split_at(MaxCandidates::get() as usize);
+        assert_eq!(CollatorSelection::candidates(), kept.to_vec());
+        for candidate in kept {
+            assert_eq!(Balances::reserved_balance(candidate.who), deposit);
+            assert!(<crate::LastAuthoredBlock<Test>>::contains_key(
+                candidate.who
+            ));
+        }
+        for candidate in dropped {
+            assert_eq!(Balances::reserved_balance(candidate.who), 0);
+            assert_eq!(Balances::free_balance(candidate.who), 100);
+            assert!(!<crate::LastAuthoredBlock<Test>>::contains_key(
+                candidate.who
+            ));
+        }
+    });
+}
+
 #[test]
 fn set_desired_candidates_works() {
     new_test_ext().execute_with(|| {
@@ -298,6 +470,80 @@ fn authorship_event_handler() {
     });
 }
 
+#[test]
+fn pot_reward_matches_note_author_payout() {
+    new_test_ext().execute_with(|| {
+        // nothing in the pot, nothing to pay
+        assert_eq!(CollatorSelection::pot_reward(), 0);
+
+        // put 100 in the pot + 5 for ED
+        Balances::make_free_balance_be(&CollatorSelection::account_id(), 105);
+        assert_eq!(CollatorSelection::pot_reward(), 50);
+
+        // triggers `note_author`
+        Authorship::on_initialize(1);
+        assert_eq!(Balances::free_balance(4), 150);
+    });
+}
+
+#[test]
+fn candidate_stats_works() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(CollatorSelection::register_as_candidate(
+            RuntimeOrigin::signed(3)
+        ));
+        assert_ok!(CollatorSelection::register_as_candidate(
+            RuntimeOrigin::signed(4)
+        ));
+
+        initialize_to_block(4);
+        // 4 authored block 4, 3 only has the initial grace period
+        assert_eq!(
+            CollatorSelection::candidate_stats(),
+            vec![
+                CandidateStats {
+                    who: 3,
+                    deposit: 10,
+                    last_authored_block: 10,
+                    blocks_until_kick: 16,
+                },
+                CandidateStats {
+                    who: 4,
+                    deposit: 10,
+                    last_authored_block: 4,
+                    blocks_until_kick: 10,
+                },
+            ]
+        );
+    });
+}
+
+#[test]
+fn projected_collators_works() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(CollatorSelection::register_as_candidate(
+            RuntimeOrigin::signed(3)
+        ));
+        assert_ok!(CollatorSelection::register_as_candidate(
+            RuntimeOrigin::signed(4)
+        ));
+        assert_eq!(CollatorSelection::projected_collators(), vec![1, 2, 3, 4]);
+
+        // 3 never authors and goes stale; 4 keeps authoring
+        System::set_block_number(20);
+        Authorship::on_initialize(20);
+        assert_eq!(CollatorSelection::projected_collators(), vec![1, 2, 4]);
+        // nothing was removed from storage
+        assert_eq!(CollatorSelection::candidates().len(), 2);
+
+        // the projection matches what the next session assembles
+        assert_eq!(
+            <CollatorSelection as pallet_session::SessionManager<u64>>::new_session(2),
+            Some(vec![1, 2, 4])
+        );
+    });
+}
+
 #[test]
 fn fees_edgecases() {
     new_test_ext().execute_with(|| {
@@ -398,6 +644,107 @@ fn kick_and_slash_mechanism() {
         // kicked collator gets funds back except slashed 10% (of 10 bond)
         assert_eq!(Balances::free_balance(3), 99);
         assert_eq!(Balances::free_balance(5), 101);
+        assert!(System::events().iter().any(|record| record.event
+            == RuntimeEvent::CollatorSelection(crate::Event::CandidateSlashed(3, 1, Some(5)))));
+        assert_eq!(SlashedToHandler::get(), 0);
+    });
+}
+
+#[test]
+fn slash_goes_to_on_slash_without_destination() {
+    new_test_ext().execute_with(|| {
+        let total_issuance = Balances::total_issuance();
+        assert_ok!(CollatorSelection::register_as_candidate(
+            RuntimeOrigin::signed(3)
+        ));
+        assert_ok!(CollatorSelection::register_as_candidate(
+            RuntimeOrigin::signed(4)
+        ));
+        initialize_to_block(20);
+        // 4 authored this block, gets to stay 3 was kicked
+        assert_eq!(CollatorSelection::candidates().len(), 1);
+        assert!(System::events().iter().any(|record| record.event
+            == RuntimeEvent::CollatorSelection(crate::Event::CandidateSlashed(3, 1, None))));
+
+        // kicked collator gets funds back except slashed 10% (of 10 bond)
+        assert_eq!(Balances::free_balance(3), 99);
+        // slashed amount was handed over to `OnSlash` and burned there
+        assert_eq!(SlashedToHandler::get(), 1);
+        assert_eq!(Balances::total_issuance(), total_issuance - 1);
+    });
+}
+
+#[test]
+fn candidates_without_keys_are_skipped() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(CollatorSelection::register_as_candidate(
+            RuntimeOrigin::signed(3)
+        ));
+
+        // 3 purges the session keys
+        UnregisteredKeys::set(vec![7, 3]);
+        assert_eq!(CollatorSelection::projected_collators(), vec![1, 2]);
+
+        initialize_to_block(10);
+        assert!(System::events().iter().any(|record| record.event
+            == RuntimeEvent::CollatorSelection(crate::Event::CandidateKeysMissing(3))));
+        // 3 is left out of the next collator set, but remains a candidate and isn't slashed
+        assert_eq!(Session::queued_keys().len(), 2);
+        assert_eq!(CollatorSelection::candidates().len(), 1);
+        assert_eq!(Balances::reserved_balance(3), 10);
+        // the kick threshold is restarted while keys are missing
+        assert_eq!(CollatorSelection::last_authored_block(3), 20);
+
+        // 3 sets the keys again, and is kept as the last candidate
+        UnregisteredKeys::set(vec![7]);
+        initialize_to_block(20);
+        assert_eq!(Session::queued_keys().len(), 3);
+        assert_eq!(CollatorSelection::candidates().len(), 1);
+        assert_eq!(Balances::reserved_balance(3), 10);
+    });
+}
+
+#[test]
+fn candidates_without_keys_are_not_kicked() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(CollatorSelection::register_as_candidate(
+            RuntimeOrigin::signed(3)
+        ));
+        assert_ok!(CollatorSelection::register_as_candidate(
+            RuntimeOrigin::signed(4)
+        ));
+        UnregisteredKeys::set(vec![7, 3]);
+
+        // 3 can't author blocks without keys, it's kept for longer than `KickThreshold`
+        initialize_to_block(40);
+        assert_eq!(
+            CollatorSelection::candidates(),
+            vec![
+                CandidateInfo {
+                    who: 3,
+                    deposit: 10
+                },
+                CandidateInfo {
+                    who: 4,
+                    deposit: 10
+                },
+            ]
+        );
+        assert_eq!(SessionHandlerCollators::get(), vec![1, 2, 4]);
+        assert_eq!(Balances::reserved_balance(3), 10);
+        assert!(!System::events().iter().any(|record| matches!(
+            record.event,
+            RuntimeEvent::CollatorSelection(crate::Event::CandidateSlashed(..))
+        )));
+
+        // 3 sets the keys again, and gets a full kick threshold to author a block
+        UnregisteredKeys::set(vec![7]);
+        initialize_to_block(50);
+        assert_eq!(CollatorSelection::candidates().len(), 2);
+        assert_eq!(CollatorSelection::projected_collators(), vec![1, 2, 3, 4]);
+        initialize_to_block(60);
+        assert_eq!(CollatorSelection::candidates().len(), 1);
+        assert_eq!(Balances::free_balance(3), 99);
     });
 }
 
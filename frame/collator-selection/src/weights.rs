@@ -54,6 +54,8 @@ pub trait WeightInfo {
 	fn leave_intent(c: u32, ) -> Weight;
 	fn note_author() -> Weight;
 	fn new_session(r: u32, c: u32, ) -> Weight;
+	fn add_invulnerable(b: u32, c: u32, ) -> Weight;
+	fn remove_invulnerable(b: u32, ) -> Weight;
 }
 
 /// Weights for pallet_collator_selection using the Substrate node and recommended hardware.
@@ -149,8 +151,11 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 	// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
 	// Storage: CollatorSelection SlashDestination (r:1 w:0)
 	// Proof Skipped: CollatorSelection SlashDestination (max_values: Some(1), max_size: None, mode: Measured)
+	// Storage: Session NextKeys (r:148 w:0)
+	// Proof Skipped: Session NextKeys (max_values: None, max_size: None, mode: Measured)
 	/// The range of component `r` is `[1, 148]`.
 	/// The range of component `c` is `[1, 148]`.
+	/// Benchmarked before session keys were checked, the key reads are added on top.
 	fn new_session(r: u32, c: u32, ) -> Weight {
 		// Minimum execution time: 16_816 nanoseconds.
 		Weight::from_ref_time(17_132_000)
@@ -158,11 +163,38 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			// Standard Error: 475_405
 			.saturating_add(Weight::from_ref_time(15_758_567).saturating_mul(c.into()))
 			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(r.into())))
 			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(c.into())))
 			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(c.into())))
 			.saturating_add(Weight::from_proof_size(3131).saturating_mul(c.into()))
 			.saturating_add(Weight::from_proof_size(2751).saturating_mul(r.into()))
 	}
+	// Storage: Session NextKeys (r:1 w:0)
+	// Proof Skipped: Session NextKeys (max_values: None, max_size: None, mode: Measured)
+	// Storage: CollatorSelection Invulnerables (r:1 w:1)
+	// Proof Skipped: CollatorSelection Invulnerables (max_values: Some(1), max_size: None, mode: Measured)
+	// Storage: CollatorSelection Candidates (r:1 w:1)
+	// Proof Skipped: CollatorSelection Candidates (max_values: Some(1), max_size: None, mode: Measured)
+	// Storage: System Account (r:1 w:1)
+	// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	// Storage: CollatorSelection LastAuthoredBlock (r:0 w:1)
+	// Proof Skipped: CollatorSelection LastAuthoredBlock (max_values: None, max_size: None, mode: Measured)
+	/// The range of component `b` is `[1, 47]`.
+	/// The range of component `c` is `[1, 148]`.
+	/// Not benchmarked yet, bounded by the `set_invulnerables` and `leave_intent` results.
+	fn add_invulnerable(b: u32, c: u32, ) -> Weight {
+		Self::set_invulnerables(b)
+			.saturating_add(Self::leave_intent(c))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+	}
+	// Storage: CollatorSelection Invulnerables (r:1 w:1)
+	// Proof Skipped: CollatorSelection Invulnerables (max_values: Some(1), max_size: None, mode: Measured)
+	/// The range of component `b` is `[1, 48]`.
+	/// Not benchmarked yet, bounded by the `set_invulnerables` results.
+	fn remove_invulnerable(b: u32, ) -> Weight {
+		Self::set_invulnerables(b)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+	}
 }
 
 // For backwards compatibility and tests
@@ -257,8 +289,11 @@ impl WeightInfo for () {
 	// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
 	// Storage: CollatorSelection SlashDestination (r:1 w:0)
 	// Proof Skipped: CollatorSelection SlashDestination (max_values: Some(1), max_size: None, mode: Measured)
+	// Storage: Session NextKeys (r:148 w:0)
+	// Proof Skipped: Session NextKeys (max_values: None, max_size: None, mode: Measured)
 	/// The range of component `r` is `[1, 148]`.
 	/// The range of component `c` is `[1, 148]`.
+	/// Benchmarked before session keys were checked, the key reads are added on top.
 	fn new_session(r: u32, c: u32, ) -> Weight {
 		// Minimum execution time: 16_816 nanoseconds.
 		Weight::from_ref_time(17_132_000)
@@ -266,9 +301,36 @@ impl WeightInfo for () {
 			// Standard Error: 475_405
 			.saturating_add(Weight::from_ref_time(15_758_567).saturating_mul(c.into()))
 			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().reads((1_u64).saturating_mul(r.into())))
 			.saturating_add(RocksDbWeight::get().reads((1_u64).saturating_mul(c.into())))
 			.saturating_add(RocksDbWeight::get().writes((1_u64).saturating_mul(c.into())))
 			.saturating_add(Weight::from_proof_size(3131).saturating_mul(c.into()))
 			.saturating_add(Weight::from_proof_size(2751).saturating_mul(r.into()))
 	}
+	// Storage: Session NextKeys (r:1 w:0)
+	// Proof Skipped: Session NextKeys (max_values: None, max_size: None, mode: Measured)
+	// Storage: CollatorSelection Invulnerables (r:1 w:1)
+	// Proof Skipped: CollatorSelection Invulnerables (max_values: Some(1), max_size: None, mode: Measured)
+	// Storage: CollatorSelection Candidates (r:1 w:1)
+	// Proof Skipped: CollatorSelection Candidates (max_values: Some(1), max_size: None, mode: Measured)
+	// Storage: System Account (r:1 w:1)
+	// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	// Storage: CollatorSelection LastAuthoredBlock (r:0 w:1)
+	// Proof Skipped: CollatorSelection LastAuthoredBlock (max_values: None, max_size: None, mode: Measured)
+	/// The range of component `b` is `[1, 47]`.
+	/// The range of component `c` is `[1, 148]`.
+	/// Not benchmarked yet, bounded by the `set_invulnerables` and `leave_intent` results.
+	fn add_invulnerable(b: u32, c: u32, ) -> Weight {
+		Self::set_invulnerables(b)
+			.saturating_add(Self::leave_intent(c))
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+	}
+	// Storage: CollatorSelection Invulnerables (r:1 w:1)
+	// Proof Skipped: CollatorSelection Invulnerables (max_values: Some(1), max_size: None, mode: Measured)
+	/// The range of component `b` is `[1, 48]`.
+	/// Not benchmarked yet, bounded by the `set_invulnerables` results.
+	fn remove_invulnerable(b: u32, ) -> Weight {
+		Self::set_invulnerables(b)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+	}
 }
\ No newline at end of file
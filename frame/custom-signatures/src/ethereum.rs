@@ -17,10 +17,12 @@
 
 //! Ethereum prefixed signatures compatibility instances.
 
+use crate::{Config, PayloadKind, PayloadVersion, SignedPayload, SigningMessage};
+use frame_support::traits::Get;
 use parity_scale_codec::{Decode, Encode};
-use sp_core::ecdsa;
+use sp_core::{ecdsa, H256, U256};
 use sp_io::{crypto::secp256k1_ecdsa_recover_compressed, hashing::keccak_256};
-use sp_runtime::traits::{IdentifyAccount, Lazy, Verify};
+use sp_runtime::traits::{IdentifyAccount, Lazy, UniqueSaturatedInto, Verify, Zero};
 use sp_runtime::MultiSignature;
 use sp_std::prelude::*;
 
@@ -75,13 +77,227 @@ impl Verify for EthereumSignature {
         account: &<Self::Signer as IdentifyAccount>::AccountId,
     ) -> bool {
         let msg = keccak_256(&signable_message(msg.get()));
-        match secp256k1_ecdsa_recover_compressed(&self.0, &msg).ok() {
-            Some(public) => {
-                let signer = Self::Signer::from(ecdsa::Public::from_raw(public));
-                *account == signer.into_account()
-            }
-            None => false,
+        recover_matches(&self.0, &msg, account)
+    }
+}
+
+impl SigningMessage for EthereumSignature {
+    fn signing_message<T: Config>(&self, payload: &SignedPayload<T>) -> Vec<u8> {
+        payload.scale_encoded()
+    }
+}
+
+/// Checks that the signer recovered from `signature` over the `hash` is `account`.
+fn recover_matches(
+    signature: &[u8; 65],
+    hash: &[u8; 32],
+    account: &<<MultiSignature as Verify>::Signer as IdentifyAccount>::AccountId,
+) -> bool {
+    match secp256k1_ecdsa_recover_compressed(signature, hash).ok() {
+        Some(public) => {
+            let signer = <MultiSignature as Verify>::Signer::from(ecdsa::Public::from_raw(public));
+            *account == signer.into_account()
         }
+        None => false,
+    }
+}
+
+/// EIP-712 domain type of signed calls.
+pub const EIP712_DOMAIN_TYPE: &[u8] = b"EIP712Domain(string name,string version,uint256 chainId,bytes32 genesisHash,uint16 callMagicNumber)";
+
+/// EIP-712 type of a signed call.
+pub const EIP712_CALL_TYPE: &[u8] = b"Call(bytes call,uint256 nonce)";
+
+/// EIP-712 type of a signed call with expiry, `expiresAt` being zero for immortal calls.
+pub const EIP712_MORTAL_CALL_TYPE: &[u8] =
+    b"MortalCall(bytes call,uint256 nonce,uint256 expiresAt)";
+
+/// EIP-712 type of a signed batch of calls.
+pub const EIP712_BATCH_TYPE: &[u8] = b"Batch(bytes calls,uint256 nonce)";
+
+/// EIP-712 type of a signed batch of calls with expiry, `expiresAt` being zero for immortal
+/// batches.
+pub const EIP712_MORTAL_BATCH_TYPE: &[u8] =
+    b"MortalBatch(bytes calls,uint256 nonce,uint256 expiresAt)";
+
+/// EIP-712 type of a sponsored call, `call` being the SCALE encoded `(sponsor, call)` pair.
+pub const EIP712_SPONSORED_CALL_TYPE: &[u8] = b"SponsoredCall(bytes call,uint256 nonce)";
+
+/// EIP-712 type of a sponsored call with expiry, `expiresAt` being zero for immortal calls.
+pub const EIP712_MORTAL_SPONSORED_CALL_TYPE: &[u8] =
+    b"MortalSponsoredCall(bytes call,uint256 nonce,uint256 expiresAt)";
+
+/// EIP-712 domain name of signed calls.
+pub const EIP712_DOMAIN_NAME: &[u8] = b"Astar Custom Signatures";
+
+/// EIP-712 domain version of signed calls.
+pub const EIP712_DOMAIN_VERSION: &[u8] = b"1";
+
+/// EIP-712 domain binding signed calls to a chain.
+#[derive(Encode, Decode, PartialEq, Eq, Clone, Debug, scale_info::TypeInfo)]
+pub struct Eip712Domain {
+    /// Chain ID, as known to the wallet.
+    pub chain_id: u64,
+    /// Genesis block hash of the chain.
+    pub genesis_hash: H256,
+    /// The pallet's `CallMagicNumber`.
+    pub call_magic_number: u16,
+}
+
+impl Eip712Domain {
+    /// Domain of the runtime `T`.
+    pub fn of<T: Config>() -> Self {
+        let genesis_hash = frame_system::Pallet::<T>::block_hash(T::BlockNumber::zero());
+        Self {
+            chain_id: T::ChainId::get(),
+            genesis_hash: H256::from_slice(genesis_hash.as_ref()),
+            call_magic_number: T::CallMagicNumber::get(),
+        }
+    }
+
+    /// EIP-712 `domainSeparator`.
+    pub fn separator(&self) -> [u8; 32] {
+        let mut data = keccak_256(EIP712_DOMAIN_TYPE).to_vec();
+        data.extend_from_slice(&keccak_256(EIP712_DOMAIN_NAME));
+        data.extend_from_slice(&keccak_256(EIP712_DOMAIN_VERSION));
+        data.extend_from_slice(&abi_uint(self.chain_id.into()));
+        data.extend_from_slice(self.genesis_hash.as_bytes());
+        data.extend_from_slice(&abi_uint(self.call_magic_number.into()));
+        keccak_256(&data)
+    }
+}
+
+/// ABI encoded `uint256`.
+fn abi_uint(value: U256) -> [u8; 32] {
+    let mut out = [0u8; 32];
+    value.to_big_endian(&mut out);
+    out
+}
+
+/// EIP-712 `hashStruct` of a `(bytes, uint256[, uint256])` struct of type `type_`.
+fn struct_hash(type_: &[u8], call: &[u8], nonce: u64, expires_at: Option<u64>) -> [u8; 32] {
+    let mut data = keccak_256(type_).to_vec();
+    data.extend_from_slice(&keccak_256(call));
+    data.extend_from_slice(&abi_uint(nonce.into()));
+    if let Some(expires_at) = expires_at {
+        data.extend_from_slice(&abi_uint(expires_at.into()));
+    }
+    keccak_256(&data)
+}
+
+/// EIP-712 `hashStruct` of a signed call.
+pub fn eip712_call_hash(call: &[u8], nonce: u64) -> [u8; 32] {
+    struct_hash(EIP712_CALL_TYPE, call, nonce, None)
+}
+
+/// EIP-712 `hashStruct` of a signed call with expiry.
+pub fn eip712_mortal_call_hash(call: &[u8], nonce: u64, expires_at: u64) -> [u8; 32] {
+    struct_hash(EIP712_MORTAL_CALL_TYPE, call, nonce, Some(expires_at))
+}
+
+/// EIP-712 `hashStruct` of a signed batch, `calls` being the SCALE encoded vector of calls.
+pub fn eip712_batch_hash(calls: &[u8], nonce: u64) -> [u8; 32] {
+    struct_hash(EIP712_BATCH_TYPE, calls, nonce, None)
+}
+
+/// EIP-712 `hashStruct` of a signed batch with expiry.
+pub fn eip712_mortal_batch_hash(calls: &[u8], nonce: u64, expires_at: u64) -> [u8; 32] {
+    struct_hash(EIP712_MORTAL_BATCH_TYPE, calls, nonce, Some(expires_at))
+}
+
+/// EIP-712 `hashStruct` of a sponsored call, `call` being the SCALE encoded `(sponsor, call)`
+/// pair.
+pub fn eip712_sponsored_call_hash(call: &[u8], nonce: u64) -> [u8; 32] {
+    struct_hash(EIP712_SPONSORED_CALL_TYPE, call, nonce, None)
+}
+
+/// EIP-712 `hashStruct` of a sponsored call with expiry.
+pub fn eip712_mortal_sponsored_call_hash(call: &[u8], nonce: u64, expires_at: u64) -> [u8; 32] {
+    struct_hash(
+        EIP712_MORTAL_SPONSORED_CALL_TYPE,
+        call,
+        nonce,
+        Some(expires_at),
+    )
+}
+
+/// Constructs the message that Ethereum RPC's `eth_signTypedData_v4` would hash and sign,
+/// i.e. `"\x19\x01" ‖ domainSeparator ‖ hashStruct(message)`.
+pub fn eip712_signable_message(domain: &Eip712Domain, struct_hash: &[u8; 32]) -> Vec<u8> {
+    let mut v = b"\x19\x01".to_vec();
+    v.extend_from_slice(&domain.separator());
+    v.extend_from_slice(struct_hash);
+    v
+}
+
+/// Ethereum-compatible EIP-712 typed data signature type.
+#[derive(Encode, Decode, PartialEq, Eq, Clone, scale_info::TypeInfo)]
+pub struct Eip712Signature(pub [u8; 65]);
+
+impl sp_std::fmt::Debug for Eip712Signature {
+    fn fmt(&self, f: &mut sp_std::fmt::Formatter<'_>) -> sp_std::fmt::Result {
+        write!(f, "Eip712Signature({:?})", &self.0[..])
+    }
+}
+
+impl From<ecdsa::Signature> for Eip712Signature {
+    fn from(signature: ecdsa::Signature) -> Self {
+        Self(signature.into())
+    }
+}
+
+impl sp_std::convert::TryFrom<Vec<u8>> for Eip712Signature {
+    type Error = ();
+
+    fn try_from(data: Vec<u8>) -> Result<Self, Self::Error> {
+        EthereumSignature::try_from(data).map(|signature| Self(signature.0))
+    }
+}
+
+/// Attempts to recover the Ethereum public key from a typed data signature signed by using
+/// the Ethereum RPC's `eth_signTypedData_v4`. The message is expected to be constructed by
+/// [`eip712_signable_message`].
+impl Verify for Eip712Signature {
+    type Signer = <MultiSignature as Verify>::Signer;
+
+    fn verify<L: Lazy<[u8]>>(
+        &self,
+        mut msg: L,
+        account: &<Self::Signer as IdentifyAccount>::AccountId,
+    ) -> bool {
+        recover_matches(&self.0, &keccak_256(msg.get()), account)
+    }
+}
+
+/// The domain binds EIP-712 signatures to the chain for all payload versions.
+impl SigningMessage for Eip712Signature {
+    fn signing_message<T: Config>(&self, payload: &SignedPayload<T>) -> Vec<u8> {
+        let nonce = payload.nonce.unique_saturated_into();
+        let struct_hash = match (payload.kind, payload.version) {
+            (PayloadKind::Call, PayloadVersion::V1) => eip712_call_hash(payload.call, nonce),
+            (PayloadKind::Call, PayloadVersion::V2 { expires_at }) => eip712_mortal_call_hash(
+                payload.call,
+                nonce,
+                expires_at.map_or(0, |at| at.unique_saturated_into()),
+            ),
+            (PayloadKind::Batch, PayloadVersion::V1) => eip712_batch_hash(payload.call, nonce),
+            (PayloadKind::Batch, PayloadVersion::V2 { expires_at }) => eip712_mortal_batch_hash(
+                payload.call,
+                nonce,
+                expires_at.map_or(0, |at| at.unique_saturated_into()),
+            ),
+            (PayloadKind::Sponsored, PayloadVersion::V1) => {
+                eip712_sponsored_call_hash(payload.call, nonce)
+            }
+            (PayloadKind::Sponsored, PayloadVersion::V2 { expires_at }) => {
+                eip712_mortal_sponsored_call_hash(
+                    payload.call,
+                    nonce,
+                    expires_at.map_or(0, |at| at.unique_saturated_into()),
+                )
+            }
+        };
+        eip712_signable_message(&Eip712Domain::of::<T>(), &struct_hash)
     }
 }
 
@@ -98,3 +314,46 @@ fn verify_should_works() {
     let signature = EthereumSignature(hex!["f5d5cc953828e3fb0d81f3176d88fa5c73d3ad3dc4bc7a8061b03a6db2cd73337778df75a1443e8c642f6ceae0db39b90c321ac270ad7836695cae76f703f3031c"]);
     assert_eq!(signature.verify(msg.as_ref(), &account), true);
 }
+
+#[test]
+fn eip712_verify_should_work() {
+    use hex_literal::hex;
+    use sp_core::{ecdsa, Pair};
+
+    // `Mail` example of the EIP-712 specification, as signed by `eth_signTypedData_v4`
+    let domain_separator = hex!["f2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f"];
+    let struct_hash = hex!["c52c0ee5d84264471806290a3f2c4cecfc5490626bf912d01f240d7a274b371e"];
+    let pair = ecdsa::Pair::from_seed(&keccak_256(b"cow"));
+    let account = <MultiSignature as Verify>::Signer::from(pair.public()).into_account();
+    let signature = Eip712Signature(hex!["4355c47d63924e8a72e509b65029052eb6c299d53a04e167c5775fd466751c9d07299936d304c153f6443dfa05f40ff007d72911b6f72307f996231605b915621c"]);
+
+    let mut typed_data = b"\x19\x01".to_vec();
+    typed_data.extend_from_slice(&domain_separator);
+    typed_data.extend_from_slice(&struct_hash);
+    assert_eq!(
+        keccak_256(&typed_data),
+        hex!["be609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2"]
+    );
+    assert!(signature.verify(&typed_data[..], &account));
+    assert!(!signature.verify(&struct_hash[..], &account));
+    assert!(!EthereumSignature(signature.0).verify(&typed_data[..], &account));
+
+    // domain and structs of signed calls follow the EIP-712 `encodeData` rules
+    let domain = Eip712Domain {
+        chain_id: 592,
+        genesis_hash: H256::repeat_byte(1),
+        call_magic_number: 0xff50,
+    };
+    assert_eq!(
+        domain.separator(),
+        hex!["7dedbb92c5bccb07a23fcd3fc81695bce3b879e104cc32327ed95f4693bf5b16"]
+    );
+    assert_eq!(
+        eip712_call_hash(&[1, 2, 3, 4, 5], 7),
+        hex!["09f7dca569da262c40c18a511dcfe32dcb481dae1637f8a2ada1578aeb22c5c3"]
+    );
+    assert_eq!(
+        eip712_mortal_batch_hash(&[1, 2, 3, 4, 5], 7, 100),
+        hex!["1e438a21bd1be9d303bc487684843b46b0a40b287a91182c51eda02f7d8f9060"]
+    );
+}
@@ -19,29 +19,148 @@
 
 pub use pallet::*;
 
-/// Ethereum-compatible signatures (eth_sign API call).
+use frame_support::traits::Get;
+use parity_scale_codec::{Decode, Encode};
+use scale_info::TypeInfo;
+use sp_runtime::{traits::Zero, RuntimeDebug};
+use sp_std::prelude::*;
+
+/// Ethereum-compatible signatures (eth_sign API call and EIP-712 typed data).
 pub mod ethereum;
 
+/// Signatures accepting any of the supported schemes.
+pub mod multi;
+
+/// NIST P-256 signatures (secp256r1 and WebAuthn passkeys).
+pub mod secp256r1;
+
 #[cfg(test)]
 mod tests;
 
+/// Format of the payload signed for a call.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub enum PayloadVersion<BlockNumber> {
+    /// `(CallMagicNumber, nonce, call)`, valid until the signer's nonce changes.
+    V1,
+    /// `(CallMagicNumber, genesis_hash, expires_at, nonce, call)`, valid on this chain only and,
+    /// if `expires_at` is set, up to and including block `expires_at`.
+    V2 { expires_at: Option<BlockNumber> },
+}
+
+impl<BlockNumber: Copy> PayloadVersion<BlockNumber> {
+    /// Last block the payload is valid in, if it is mortal.
+    pub fn expires_at(&self) -> Option<BlockNumber> {
+        match self {
+            Self::V1 => None,
+            Self::V2 { expires_at } => *expires_at,
+        }
+    }
+}
+
+/// Tag following the magic number of batch payloads, keeps batch signatures from being
+/// replayed as single call signatures and vice versa.
+pub const BATCH_PAYLOAD_TAG: [u8; 4] = *b"btch";
+
+/// Tag following the magic number of sponsored call payloads, keeps sponsored call signatures
+/// from being submitted as signatures of calls paid by the signer and vice versa.
+pub const SPONSORED_PAYLOAD_TAG: [u8; 4] = *b"spsr";
+
+/// What a signed payload dispatches.
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum PayloadKind {
+    /// A single SCALE encoded call.
+    Call,
+    /// A SCALE encoded vector of calls, dispatched by `call_batch`.
+    Batch,
+    /// A SCALE encoded `(sponsor, call)` pair, dispatched by `sponsored_call` of `sponsor`.
+    Sponsored,
+}
+
+/// Content signed by a call signer.
+pub struct SignedPayload<'a, T: Config> {
+    /// Payload format.
+    pub version: &'a PayloadVersion<T::BlockNumber>,
+    /// Single call or batch of calls.
+    pub kind: PayloadKind,
+    /// Signer's account nonce.
+    pub nonce: T::Index,
+    /// SCALE encoded call.
+    pub call: &'a [u8],
+}
+
+impl<'a, T: Config> SignedPayload<'a, T> {
+    /// SCALE encoded payload, as described by its [`PayloadVersion`].
+    ///
+    /// Batch and sponsored call payloads carry [`BATCH_PAYLOAD_TAG`] and
+    /// [`SPONSORED_PAYLOAD_TAG`] respectively right after the magic number.
+    pub fn scale_encoded(&self) -> Vec<u8> {
+        let mut message = T::CallMagicNumber::get().encode();
+        match self.kind {
+            PayloadKind::Call => {}
+            PayloadKind::Batch => message.extend_from_slice(&BATCH_PAYLOAD_TAG),
+            PayloadKind::Sponsored => message.extend_from_slice(&SPONSORED_PAYLOAD_TAG),
+        }
+        match self.version {
+            PayloadVersion::V1 => self.nonce.encode_to(&mut message),
+            PayloadVersion::V2 { expires_at } => (
+                frame_system::Pallet::<T>::block_hash(T::BlockNumber::zero()),
+                expires_at,
+                self.nonce,
+            )
+                .encode_to(&mut message),
+        }
+        message.extend_from_slice(self.call);
+        message
+    }
+}
+
+/// Construction of the message a signature is verified against.
+///
+/// Signing schemes differ in how the signed call is presented to the user, so each signature
+/// type decides how the payload is wrapped before verification.
+pub trait SigningMessage {
+    /// Message signed for the given `payload`.
+    fn signing_message<T: Config>(&self, payload: &SignedPayload<T>) -> Vec<u8>;
+
+    /// Whether the signature is scoped to the runtime `T`.
+    ///
+    /// Only signatures carrying context besides the signed message (e.g. the WebAuthn relying
+    /// party) need to check it, others are bound to the runtime by the message itself.
+    fn is_scoped_to<T: Config>(&self) -> bool {
+        true
+    }
+}
+
+impl SigningMessage for sp_runtime::MultiSignature {
+    fn signing_message<T: Config>(&self, payload: &SignedPayload<T>) -> Vec<u8> {
+        payload.scale_encoded()
+    }
+}
+
 #[frame_support::pallet]
 pub mod pallet {
     use frame_support::{
-        dispatch::{Dispatchable, GetDispatchInfo},
+        dispatch::{DispatchClass, Dispatchable, GetDispatchInfo, PostDispatchInfo},
         pallet_prelude::*,
-        traits::{Currency, ExistenceRequirement, Get, OnUnbalanced, WithdrawReasons},
+        storage::{with_transaction, TransactionOutcome},
+        traits::{Currency, ExistenceRequirement, Get, Imbalance, OnUnbalanced, WithdrawReasons},
+        weights::WeightToFee,
     };
     use frame_system::{ensure_none, pallet_prelude::*};
-    use sp_runtime::traits::{IdentifyAccount, Verify};
+    use sp_runtime::traits::{IdentifyAccount, Saturating, UniqueSaturatedInto, Verify, Zero};
     use sp_std::{convert::TryFrom, prelude::*};
 
+    use crate::{PayloadKind, PayloadVersion, SignedPayload, SigningMessage};
+
     #[pallet::pallet]
     pub struct Pallet<T>(_);
 
     /// The balance type of this pallet.
     pub type BalanceOf<T> =
         <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+    type NegativeImbalanceOf<T> = <<T as Config>::Currency as Currency<
+        <T as frame_system::Config>::AccountId,
+    >>::NegativeImbalance;
 
     #[pallet::config]
     pub trait Config: frame_system::Config {
@@ -50,11 +169,14 @@ pub mod pallet {
 
         /// A signable call.
         type RuntimeCall: Parameter
-            + Dispatchable<RuntimeOrigin = Self::RuntimeOrigin>
+            + Dispatchable<RuntimeOrigin = Self::RuntimeOrigin, PostInfo = PostDispatchInfo>
             + GetDispatchInfo;
 
         /// User defined signature type.
-        type Signature: Parameter + Verify<Signer = Self::Signer> + TryFrom<Vec<u8>>;
+        type Signature: Parameter
+            + Verify<Signer = Self::Signer>
+            + SigningMessage
+            + TryFrom<Vec<u8>>;
 
         /// User defined signer type.
         type Signer: IdentifyAccount<AccountId = Self::AccountId>;
@@ -67,14 +189,31 @@ pub mod pallet {
             <Self::Currency as Currency<Self::AccountId>>::NegativeImbalance,
         >;
 
-        /// The call processing fee amount.
+        /// The call processing base fee amount, charged on top of the call weight fee.
         #[pallet::constant]
         type CallFee: Get<BalanceOf<Self>>;
 
+        /// Conversion of the dispatched call weight into a fee.
+        type WeightToFee: WeightToFee<Balance = BalanceOf<Self>>;
+
         /// The call magic number.
         #[pallet::constant]
         type CallMagicNumber: Get<u16>;
 
+        /// The chain ID, used by signing schemes binding signatures to a chain (e.g. EIP-712).
+        #[pallet::constant]
+        type ChainId: Get<u64>;
+
+        /// Maximum number of calls in a signed batch.
+        #[pallet::constant]
+        type MaxBatchCalls: Get<u32>;
+
+        /// WebAuthn relying party ID passkey assertions must be scoped to, e.g. `astar.network`.
+        type WebAuthnRpId: Get<&'static [u8]>;
+
+        /// Origin passkey assertions must be made from, e.g. `https://astar.network`.
+        type WebAuthnOrigin: Get<&'static [u8]>;
+
         /// A configuration for base priority of unsigned transactions.
         ///
         /// This is exposed so that it can be tuned for particular runtime, when
@@ -90,13 +229,41 @@ pub mod pallet {
         InvalidSignature,
         /// Bad nonce parameter.
         BadNonce,
+        /// Signed payload expired.
+        Expired,
+        /// Call fee exceeds the allowance given by the sponsor to the signer.
+        AllowanceExceeded,
     }
 
+    /// Amount of fees a sponsor is willing to pay for calls signed by a user.
+    ///
+    /// Double map: sponsor => user => remaining allowance.
+    #[pallet::storage]
+    #[pallet::getter(fn sponsor_allowance)]
+    pub type SponsorAllowance<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        Blake2_128Concat,
+        T::AccountId,
+        BalanceOf<T>,
+        ValueQuery,
+    >;
+
     #[pallet::event]
     #[pallet::generate_deposit(pub(crate) fn deposit_event)]
     pub enum Event<T: Config> {
         /// A call just executed. \[result\]
         Executed(T::AccountId, DispatchResult),
+        /// A batch of calls just executed. \[results\]
+        ///
+        /// Contains one result per executed call, in order. If the last one is an error,
+        /// the batch was interrupted and all its calls were reverted.
+        BatchExecuted(T::AccountId, Vec<DispatchResult>),
+        /// Sponsor allowance for a user was set. \[sponsor, user, allowance\]
+        SponsorAllowanceSet(T::AccountId, T::AccountId, BalanceOf<T>),
+        /// Sponsor paid the fee for a call signed by a user. \[sponsor, user, fee\]
+        FeeSponsored(T::AccountId, T::AccountId, BalanceOf<T>),
     }
 
     #[pallet::call]
@@ -107,12 +274,15 @@ pub mod pallet {
         /// - One DB write (event).
         /// - Weight of derivative `call` execution + read/write + 10_000.
         /// # </weight>
+        ///
+        /// The signer pays `CallFee` plus the fee for the weight of `call`. The weight fee
+        /// is refunded down to the actual weight used by `call`.
+        ///
+        /// `version` selects the format of the signed payload, see [`PayloadVersion`].
         #[pallet::call_index(0)]
         #[pallet::weight({
             let dispatch_info = call.get_dispatch_info();
-            (dispatch_info.weight.saturating_add(T::DbWeight::get().reads(1))
-                                 .saturating_add(T::DbWeight::get().writes(1))
-                                 .saturating_add(Weight::from_ref_time(10_000)),
+            (dispatch_info.weight.saturating_add(Pallet::<T>::call_overhead_weight()),
              dispatch_info.class)
         })]
         pub fn call(
@@ -121,90 +291,409 @@ pub mod pallet {
             signer: T::AccountId,
             signature: Vec<u8>,
             #[pallet::compact] nonce: T::Index,
+            version: PayloadVersion<T::BlockNumber>,
         ) -> DispatchResultWithPostInfo {
             ensure_none(origin)?;
+            Self::check_signed_payload(
+                &call.encode(),
+                PayloadKind::Call,
+                &signer,
+                signature,
+                nonce,
+                &version,
+            )?;
 
+            let (res, actual_weight, _) = Self::dispatch_with_fee(*call, signer.clone(), &signer)?;
+            Self::deposit_event(Event::Executed(signer, res));
+
+            // Fee already charged
+            Ok(PostDispatchInfo {
+                actual_weight: Some(actual_weight.saturating_add(Self::call_overhead_weight())),
+                pays_fee: Pays::No,
+            })
+        }
+
+        /// Dispatch a batch of calls signed with a single signature.
+        ///
+        /// The signed payload contains the SCALE encoded list of `calls` in place of the call,
+        /// and [`BATCH_PAYLOAD_TAG`](crate::BATCH_PAYLOAD_TAG) after the magic number. EIP-712
+        /// signers sign a `Batch` (or `MortalBatch`) struct instead of a `Call`.
+        /// Calls are dispatched in order and, same as `Utility::batch_all`, if any of them
+        /// fails the whole batch is reverted. Fee and nonce are charged regardless.
+        ///
+        /// The signer pays `CallFee` once, plus the fee for the weight of all `calls`. The
+        /// weight fee is refunded down to the actual weight used.
+        #[pallet::call_index(1)]
+        #[pallet::weight({
+            let (weight, class) = Pallet::<T>::batch_weight_and_class(calls);
+            (weight.saturating_add(Pallet::<T>::call_overhead_weight()), class)
+        })]
+        pub fn call_batch(
+            origin: OriginFor<T>,
+            calls: BoundedVec<<T as Config>::RuntimeCall, T::MaxBatchCalls>,
+            signer: T::AccountId,
+            signature: Vec<u8>,
+            #[pallet::compact] nonce: T::Index,
+            version: PayloadVersion<T::BlockNumber>,
+        ) -> DispatchResultWithPostInfo {
+            ensure_none(origin)?;
+            Self::check_signed_payload(
+                &calls.encode(),
+                PayloadKind::Batch,
+                &signer,
+                signature,
+                nonce,
+                &version,
+            )?;
+
+            // Processing fee, only for the weight of calls which pay fees
+            let paying_weight = calls
+                .iter()
+                .map(|call| call.get_dispatch_info())
+                .filter(|info| info.pays_fee == Pays::Yes)
+                .fold(Weight::zero(), |total, info| {
+                    total.saturating_add(info.weight)
+                });
+            let estimated_fee = Self::compute_fee(paying_weight, Pays::Yes);
+            let tx_fee = Self::withdraw_fee(&signer, estimated_fee)?;
+
+            // Dispatch calls, all or nothing
+            let mut results = Vec::with_capacity(calls.len());
+            let mut actual_weight = Weight::zero();
+            let mut actual_paying_weight = Weight::zero();
+            let _ = with_transaction(|| {
+                for call in calls.into_iter() {
+                    let dispatch_info = call.get_dispatch_info();
+                    let new_origin = frame_system::RawOrigin::Signed(signer.clone()).into();
+                    let res = call.dispatch(new_origin);
+                    let post_info = match &res {
+                        Ok(post_info) => *post_info,
+                        Err(err) => err.post_info,
+                    };
+
+                    let weight = post_info.calc_actual_weight(&dispatch_info);
+                    actual_weight.saturating_accrue(weight);
+                    if post_info.pays_fee(&dispatch_info) == Pays::Yes {
+                        actual_paying_weight.saturating_accrue(weight);
+                    }
+
+                    let failed = res.is_err();
+                    results.push(res.map(|_| ()).map_err(|e| e.error));
+                    if failed {
+                        return TransactionOutcome::Rollback(Ok::<(), DispatchError>(()));
+                    }
+                }
+                TransactionOutcome::Commit(Ok(()))
+            });
+
+            // Refund unused weight fee
+            let actual_fee = Self::compute_fee(actual_paying_weight, Pays::Yes);
+            Self::settle_fee(&signer, tx_fee, estimated_fee.saturating_sub(actual_fee));
+
+            Self::deposit_event(Event::BatchExecuted(signer, results));
+
+            // Fee already charged
+            Ok(PostDispatchInfo {
+                actual_weight: Some(actual_weight.saturating_add(Self::call_overhead_weight())),
+                pays_fee: Pays::No,
+            })
+        }
+
+        /// Set the amount of fees the origin is willing to pay for calls signed by `user`.
+        ///
+        /// Setting zero allowance removes the sponsorship.
+        #[pallet::call_index(2)]
+        #[pallet::weight(T::DbWeight::get().writes(1).saturating_add(Weight::from_ref_time(10_000)))]
+        pub fn set_sponsor_allowance(
+            origin: OriginFor<T>,
+            user: T::AccountId,
+            allowance: BalanceOf<T>,
+        ) -> DispatchResult {
+            let sponsor = ensure_signed(origin)?;
+
+            if allowance.is_zero() {
+                SponsorAllowance::<T>::remove(&sponsor, &user);
+            } else {
+                SponsorAllowance::<T>::insert(&sponsor, &user, allowance);
+            }
+
+            Self::deposit_event(Event::SponsorAllowanceSet(sponsor, user, allowance));
+            Ok(())
+        }
+
+        /// Same as [`Pallet::call`], but the fee is paid by the origin instead of `signer`.
+        ///
+        /// The fee is deducted from the allowance given by the origin to `signer`, see
+        /// [`Pallet::set_sponsor_allowance`]. The call is dispatched with `signer` origin.
+        ///
+        /// The signed payload contains the SCALE encoded `(origin, call)` pair in place of the
+        /// call, and [`SPONSORED_PAYLOAD_TAG`](crate::SPONSORED_PAYLOAD_TAG) after the magic
+        /// number. EIP-712 signers sign a `SponsoredCall` (or `MortalSponsoredCall`) struct
+        /// instead of a `Call`.
+        #[pallet::call_index(3)]
+        #[pallet::weight({
+            let dispatch_info = call.get_dispatch_info();
+            (dispatch_info.weight
+                .saturating_add(Pallet::<T>::call_overhead_weight())
+                .saturating_add(T::DbWeight::get().reads_writes(1, 1)),
+             dispatch_info.class)
+        })]
+        pub fn sponsored_call(
+            origin: OriginFor<T>,
+            call: Box<<T as Config>::RuntimeCall>,
+            signer: T::AccountId,
+            signature: Vec<u8>,
+            #[pallet::compact] nonce: T::Index,
+            version: PayloadVersion<T::BlockNumber>,
+        ) -> DispatchResultWithPostInfo {
+            let sponsor = ensure_signed(origin)?;
+
+            let dispatch_info = call.get_dispatch_info();
+            let estimated_fee = Self::compute_fee(dispatch_info.weight, dispatch_info.pays_fee);
+            let allowance = SponsorAllowance::<T>::get(&sponsor, &signer);
+            ensure!(estimated_fee <= allowance, Error::<T>::AllowanceExceeded);
+
+            Self::check_signed_payload(
+                &(&sponsor, &call).encode(),
+                PayloadKind::Sponsored,
+                &signer,
+                signature,
+                nonce,
+                &version,
+            )?;
+
+            let (res, actual_weight, actual_fee) =
+                Self::dispatch_with_fee(*call, signer.clone(), &sponsor)?;
+            SponsorAllowance::<T>::insert(&sponsor, &signer, allowance.saturating_sub(actual_fee));
+
+            Self::deposit_event(Event::FeeSponsored(sponsor, signer.clone(), actual_fee));
+            Self::deposit_event(Event::Executed(signer, res));
+
+            // Fee already charged
+            Ok(PostDispatchInfo {
+                actual_weight: Some(
+                    actual_weight
+                        .saturating_add(Self::call_overhead_weight())
+                        .saturating_add(T::DbWeight::get().reads_writes(1, 1)),
+                ),
+                pays_fee: Pays::No,
+            })
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        /// Dispatches `call` with `signer` origin, charging its fee to `payer`.
+        ///
+        /// Returns the call result, the actual weight of `call` and the actually charged fee.
+        fn dispatch_with_fee(
+            call: <T as Config>::RuntimeCall,
+            signer: T::AccountId,
+            payer: &T::AccountId,
+        ) -> Result<(DispatchResult, Weight, BalanceOf<T>), DispatchError> {
+            // Processing fee
+            let dispatch_info = call.get_dispatch_info();
+            let estimated_fee = Self::compute_fee(dispatch_info.weight, dispatch_info.pays_fee);
+            let tx_fee = Self::withdraw_fee(payer, estimated_fee)?;
+
+            // Dispatch call
+            let new_origin = frame_system::RawOrigin::Signed(signer).into();
+            let res = call.dispatch(new_origin);
+            let post_info = match &res {
+                Ok(post_info) => *post_info,
+                Err(err) => err.post_info,
+            };
+
+            // Refund unused weight fee
+            let actual_weight = post_info.calc_actual_weight(&dispatch_info);
+            let actual_fee = Self::compute_fee(actual_weight, post_info.pays_fee(&dispatch_info));
+            Self::settle_fee(payer, tx_fee, estimated_fee.saturating_sub(actual_fee));
+
+            Ok((
+                res.map(|_| ()).map_err(|e| e.error),
+                actual_weight,
+                actual_fee,
+            ))
+        }
+
+        /// Fee charged for dispatching a call with the given `weight`.
+        pub fn compute_fee(weight: Weight, pays_fee: Pays) -> BalanceOf<T> {
+            let weight_fee = match pays_fee {
+                Pays::Yes => T::WeightToFee::weight_to_fee(&weight),
+                Pays::No => Zero::zero(),
+            };
+            T::CallFee::get().saturating_add(weight_fee)
+        }
+
+        /// Weight of the signature checks and fee handling around the dispatched call.
+        fn call_overhead_weight() -> Weight {
+            T::DbWeight::get()
+                .reads_writes(1, 1)
+                .saturating_add(Weight::from_ref_time(10_000))
+        }
+
+        /// Total weight of a batch, dispatched as operational only if all its calls are.
+        fn batch_weight_and_class(calls: &[<T as Config>::RuntimeCall]) -> (Weight, DispatchClass) {
+            let dispatch_infos = calls.iter().map(|call| call.get_dispatch_info());
+            let weight = dispatch_infos.clone().fold(Weight::zero(), |total, info| {
+                total.saturating_add(info.weight)
+            });
+            let class = if dispatch_infos
+                .clone()
+                .all(|info| info.class == DispatchClass::Operational)
+                && !calls.is_empty()
+            {
+                DispatchClass::Operational
+            } else {
+                DispatchClass::Normal
+            };
+            (weight, class)
+        }
+
+        /// Checks nonce, expiry and signature of a signed payload and bumps the signer's nonce.
+        fn check_signed_payload(
+            encoded_call: &[u8],
+            kind: PayloadKind,
+            signer: &T::AccountId,
+            signature: Vec<u8>,
+            nonce: T::Index,
+            version: &PayloadVersion<T::BlockNumber>,
+        ) -> DispatchResult {
             // Ensure that transaction isn't stale
             ensure!(
                 nonce == frame_system::Pallet::<T>::account_nonce(signer.clone()),
                 Error::<T>::BadNonce,
             );
+            ensure!(!Self::is_expired(version), Error::<T>::Expired);
 
             let signature = <T as Config>::Signature::try_from(signature)
                 .map_err(|_| Error::<T>::DecodeFailure)?;
 
             // Ensure that transaction signature is valid
             ensure!(
-                Self::valid_signature(&call, &signer, &signature, &nonce),
+                Self::valid_payload_signature(
+                    encoded_call,
+                    kind,
+                    signer,
+                    &signature,
+                    &nonce,
+                    version
+                ),
                 Error::<T>::InvalidSignature
             );
 
             // Increment account nonce
             frame_system::Pallet::<T>::inc_account_nonce(signer.clone());
+            Ok(())
+        }
 
-            // Processing fee
-            let tx_fee = T::Currency::withdraw(
-                &signer,
-                T::CallFee::get(),
+        /// Withdraws the estimated call fee from `who`.
+        fn withdraw_fee(
+            who: &T::AccountId,
+            fee: BalanceOf<T>,
+        ) -> Result<NegativeImbalanceOf<T>, DispatchError> {
+            T::Currency::withdraw(
+                who,
+                fee,
                 WithdrawReasons::FEE,
                 ExistenceRequirement::AllowDeath,
-            )?;
-            T::OnChargeTransaction::on_unbalanced(tx_fee);
-
-            // Dispatch call
-            let new_origin = frame_system::RawOrigin::Signed(signer.clone()).into();
-            let res = call.dispatch(new_origin).map(|_| ());
-            Self::deposit_event(Event::Executed(signer, res.map_err(|e| e.error)));
+            )
+        }
 
-            // Fee already charged
-            Ok(Pays::No.into())
+        /// Refunds `refund` of the withdrawn fee to `who`, and hands the rest over to
+        /// `OnChargeTransaction`.
+        fn settle_fee(who: &T::AccountId, tx_fee: NegativeImbalanceOf<T>, refund: BalanceOf<T>) {
+            let (refund, tx_fee) = tx_fee.split(refund);
+            T::Currency::resolve_creating(who, refund);
+            T::OnChargeTransaction::on_unbalanced(tx_fee);
         }
-    }
 
-    impl<T: Config> Pallet<T> {
         /// Verify custom signature and returns `true` if correct.
         pub fn valid_signature(
             call: &Box<<T as Config>::RuntimeCall>,
             signer: &T::AccountId,
             signature: &T::Signature,
             nonce: &T::Index,
+            version: &PayloadVersion<T::BlockNumber>,
         ) -> bool {
-            let payload = (T::CallMagicNumber::get(), *nonce, call.clone());
-            signature.verify(&payload.encode()[..], signer)
+            Self::valid_payload_signature(
+                &call.encode(),
+                PayloadKind::Call,
+                signer,
+                signature,
+                nonce,
+                version,
+            )
         }
-    }
 
-    pub(crate) const SIGNATURE_DECODE_FAILURE: u8 = 1;
+        /// Verify custom signature over the SCALE encoded call(s) and returns `true` if correct.
+        pub fn valid_payload_signature(
+            encoded_call: &[u8],
+            kind: PayloadKind,
+            signer: &T::AccountId,
+            signature: &T::Signature,
+            nonce: &T::Index,
+            version: &PayloadVersion<T::BlockNumber>,
+        ) -> bool {
+            let payload = SignedPayload::<T> {
+                version,
+                kind,
+                nonce: *nonce,
+                call: encoded_call,
+            };
+            let message = signature.signing_message(&payload);
+            signature.is_scoped_to::<T>() && signature.verify(&message[..], signer)
+        }
 
-    #[pallet::validate_unsigned]
-    impl<T: Config> frame_support::unsigned::ValidateUnsigned for Pallet<T> {
-        type Call = Call<T>;
+        /// Whether the payload is past its expiry block.
+        fn is_expired(version: &PayloadVersion<T::BlockNumber>) -> bool {
+            version
+                .expires_at()
+                .map_or(false, |at| frame_system::Pallet::<T>::block_number() > at)
+        }
 
-        fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
-            // Call decomposition (we have only one possible value here)
-            let (call, signer, signature, nonce) = match call {
-                Call::call {
-                    call,
-                    signer,
-                    signature,
-                    nonce,
-                } => (call, signer, signature, nonce),
-                _ => return InvalidTransaction::Call.into(),
-            };
+        /// Transaction pool longevity of a signed payload.
+        fn longevity(version: &PayloadVersion<T::BlockNumber>) -> TransactionLongevity {
+            match version.expires_at() {
+                Some(at) => UniqueSaturatedInto::<TransactionLongevity>::unique_saturated_into(
+                    at.saturating_sub(frame_system::Pallet::<T>::block_number()),
+                )
+                .max(1),
+                None => DEFAULT_LONGEVITY,
+            }
+        }
 
+        /// Validates a signed payload for the transaction pool.
+        fn validate_signed_payload(
+            encoded_call: &[u8],
+            kind: PayloadKind,
+            signer: &T::AccountId,
+            signature: &[u8],
+            nonce: &T::Index,
+            version: &PayloadVersion<T::BlockNumber>,
+            provides: impl Encode,
+        ) -> TransactionValidity {
             // Check that tx isn't stale
-            if *nonce != frame_system::Pallet::<T>::account_nonce(signer.clone()) {
+            if *nonce != frame_system::Pallet::<T>::account_nonce(signer.clone())
+                || Self::is_expired(version)
+            {
                 return InvalidTransaction::Stale.into();
             }
 
             // Check signature encoding
-            if let Ok(signature) = <T as Config>::Signature::try_from(signature.clone()) {
+            if let Ok(signature) = <T as Config>::Signature::try_from(signature.to_vec()) {
                 // Verify signature
-                if Self::valid_signature(call, signer, &signature, nonce) {
+                if Self::valid_payload_signature(
+                    encoded_call,
+                    kind,
+                    signer,
+                    &signature,
+                    nonce,
+                    version,
+                ) {
                     ValidTransaction::with_tag_prefix("CustomSignatures")
                         .priority(T::UnsignedPriority::get())
-                        .and_provides((call, signer, nonce))
-                        .longevity(64_u64)
+                        .and_provides(provides)
+                        .longevity(Self::longevity(version))
                         .propagate(true)
                         .build()
                 } else {
@@ -217,4 +706,50 @@ pub mod pallet {
             }
         }
     }
+
+    pub(crate) const SIGNATURE_DECODE_FAILURE: u8 = 1;
+
+    /// Longevity of payloads without expiry.
+    const DEFAULT_LONGEVITY: TransactionLongevity = 64;
+
+    #[pallet::validate_unsigned]
+    impl<T: Config> frame_support::unsigned::ValidateUnsigned for Pallet<T> {
+        type Call = Call<T>;
+
+        fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+            match call {
+                Call::call {
+                    call,
+                    signer,
+                    signature,
+                    nonce,
+                    version,
+                } => Self::validate_signed_payload(
+                    &call.encode(),
+                    PayloadKind::Call,
+                    signer,
+                    signature,
+                    nonce,
+                    version,
+                    (call, signer, nonce),
+                ),
+                Call::call_batch {
+                    calls,
+                    signer,
+                    signature,
+                    nonce,
+                    version,
+                } => Self::validate_signed_payload(
+                    &calls.encode(),
+                    PayloadKind::Batch,
+                    signer,
+                    signature,
+                    nonce,
+                    version,
+                    (calls, signer, nonce),
+                ),
+                _ => InvalidTransaction::Call.into(),
+            }
+        }
+    }
 }
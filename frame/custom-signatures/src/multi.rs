@@ -0,0 +1,155 @@
+// This file is part of Astar.
+
+// Copyright (C) 2019-2023 Stake Technologies Pte.Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Signatures of any of the supported schemes, selected by the signer.
+
+use crate::{
+    ethereum::{Eip712Signature, EthereumSignature},
+    secp256r1::{Secp256r1Public, Secp256r1Signature, WebAuthnSignature},
+    Config, SignedPayload, SigningMessage,
+};
+use parity_scale_codec::{Decode, DecodeAll, Encode};
+use sp_core::ecdsa;
+use sp_runtime::{
+    traits::{IdentifyAccount, Lazy, Verify},
+    AccountId32, MultiSigner, RuntimeDebug,
+};
+use sp_std::prelude::*;
+
+/// Signer of any of the supported signature schemes.
+#[derive(Encode, Decode, PartialEq, Eq, Clone, RuntimeDebug, scale_info::TypeInfo)]
+pub enum MultiSchemeSigner {
+    /// secp256k1 public key, used by Ethereum signatures.
+    Ecdsa(ecdsa::Public),
+    /// P-256 public key, used by secp256r1 and WebAuthn signatures.
+    Secp256r1(Secp256r1Public),
+}
+
+/// Ethereum accounts are derived the same way as `MultiSigner::Ecdsa` ones.
+impl IdentifyAccount for MultiSchemeSigner {
+    type AccountId = AccountId32;
+
+    fn into_account(self) -> AccountId32 {
+        match self {
+            Self::Ecdsa(public) => MultiSigner::from(public).into_account(),
+            Self::Secp256r1(public) => public.into_account(),
+        }
+    }
+}
+
+impl From<ecdsa::Public> for MultiSchemeSigner {
+    fn from(public: ecdsa::Public) -> Self {
+        Self::Ecdsa(public)
+    }
+}
+
+impl From<Secp256r1Public> for MultiSchemeSigner {
+    fn from(public: Secp256r1Public) -> Self {
+        Self::Secp256r1(public)
+    }
+}
+
+/// Signature of any of the supported signature schemes.
+///
+/// Allows a runtime to accept signatures of different wallets with a single
+/// `Config::Signature`. Signature bytes passed to the pallet are the SCALE encoded enum.
+#[derive(Encode, Decode, PartialEq, Eq, Clone, RuntimeDebug, scale_info::TypeInfo)]
+pub enum MultiSchemeSignature {
+    /// Ethereum `eth_sign` signature.
+    Ethereum(EthereumSignature),
+    /// Ethereum EIP-712 typed data signature.
+    Eip712(Eip712Signature),
+    /// P-256 signature.
+    Secp256r1(Secp256r1Signature),
+    /// WebAuthn (passkey) assertion.
+    WebAuthn(WebAuthnSignature),
+}
+
+impl sp_std::convert::TryFrom<Vec<u8>> for MultiSchemeSignature {
+    type Error = ();
+
+    fn try_from(data: Vec<u8>) -> Result<Self, Self::Error> {
+        Self::decode_all(&mut &data[..]).map_err(|_| ())
+    }
+}
+
+impl Verify for MultiSchemeSignature {
+    type Signer = MultiSchemeSigner;
+
+    fn verify<L: Lazy<[u8]>>(&self, msg: L, account: &AccountId32) -> bool {
+        match self {
+            Self::Ethereum(signature) => signature.verify(msg, account),
+            Self::Eip712(signature) => signature.verify(msg, account),
+            Self::Secp256r1(signature) => signature.verify(msg, account),
+            Self::WebAuthn(signature) => signature.verify(msg, account),
+        }
+    }
+}
+
+impl SigningMessage for MultiSchemeSignature {
+    fn signing_message<T: Config>(&self, payload: &SignedPayload<T>) -> Vec<u8> {
+        match self {
+            Self::Ethereum(signature) => signature.signing_message(payload),
+            Self::Eip712(signature) => signature.signing_message(payload),
+            Self::Secp256r1(signature) => signature.signing_message(payload),
+            Self::WebAuthn(signature) => signature.signing_message(payload),
+        }
+    }
+
+    fn is_scoped_to<T: Config>(&self) -> bool {
+        match self {
+            Self::WebAuthn(signature) => signature.is_scoped_to::<T>(),
+            _ => true,
+        }
+    }
+}
+
+#[test]
+fn multi_scheme_verify_works() {
+    use hex_literal::hex;
+    use sp_core::Pair;
+
+    let msg = "test eth signed message";
+    let pair = ecdsa::Pair::from_seed(&hex![
+        "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80"
+    ]);
+    let account = MultiSchemeSigner::from(pair.public()).into_account();
+    assert_eq!(
+        account,
+        <sp_runtime::MultiSignature as Verify>::Signer::from(pair.public()).into_account()
+    );
+
+    let signature = MultiSchemeSignature::Ethereum(EthereumSignature(hex!["f5d5cc953828e3fb0d81f3176d88fa5c73d3ad3dc4bc7a8061b03a6db2cd73337778df75a1443e8c642f6ceae0db39b90c321ac270ad7836695cae76f703f3031c"]));
+    assert!(signature.verify(msg.as_ref(), &account));
+    assert_eq!(
+        MultiSchemeSignature::try_from(signature.encode()),
+        Ok(signature.clone())
+    );
+
+    // raw signature bytes aren't accepted without the scheme
+    let mut raw = signature.encode();
+    raw.remove(0);
+    assert_eq!(MultiSchemeSignature::try_from(raw), Err(()));
+
+    // same key bytes give different accounts under different schemes
+    let mut public = [0u8; 33];
+    public.copy_from_slice(pair.public().as_ref());
+    assert_ne!(
+        MultiSchemeSigner::Secp256r1(Secp256r1Public(public)).into_account(),
+        account
+    );
+}
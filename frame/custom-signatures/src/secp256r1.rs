@@ -0,0 +1,379 @@
+// This file is part of Astar.
+
+// Copyright (C) 2019-2023 Stake Technologies Pte.Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! NIST P-256 signatures, both raw and as WebAuthn (passkey) assertions.
+//!
+//! P-256 accounts are derived from the signer public key hashed with [`ACCOUNT_PREFIX`], so
+//! they never collide with accounts of other schemes.
+
+use crate::{Config, SignedPayload, SigningMessage};
+use frame_support::traits::Get;
+use p256::ecdsa::{signature::Verifier, Signature, VerifyingKey};
+use parity_scale_codec::{Decode, DecodeAll, Encode};
+use sp_io::hashing::{blake2_256, sha2_256};
+use sp_runtime::{
+    traits::{IdentifyAccount, Lazy, Verify},
+    AccountId32,
+};
+use sp_std::prelude::*;
+
+/// Prefix of the account derivation pre-image, separates P-256 accounts from other schemes.
+pub const ACCOUNT_PREFIX: &[u8] = b"secp256r1:";
+
+/// WebAuthn `clientDataJSON` type of an assertion.
+pub const WEBAUTHN_GET_TYPE: &[u8] = b"\"type\":\"webauthn.get\"";
+
+/// WebAuthn `clientDataJSON` challenge key.
+pub const WEBAUTHN_CHALLENGE_KEY: &[u8] = b"\"challenge\":\"";
+
+/// WebAuthn `clientDataJSON` origin key.
+pub const WEBAUTHN_ORIGIN_KEY: &[u8] = b"\"origin\":\"";
+
+/// Authenticator data flag: user present.
+const FLAG_USER_PRESENT: u8 = 0x01;
+
+/// Length of `rpIdHash ‖ flags ‖ signCount` prefix of the authenticator data.
+const AUTHENTICATOR_DATA_MIN_LEN: usize = 37;
+
+/// Compressed SEC1 encoded P-256 public key.
+#[derive(Encode, Decode, PartialEq, Eq, Clone, scale_info::TypeInfo)]
+pub struct Secp256r1Public(pub [u8; 33]);
+
+impl sp_std::fmt::Debug for Secp256r1Public {
+    fn fmt(&self, f: &mut sp_std::fmt::Formatter<'_>) -> sp_std::fmt::Result {
+        write!(f, "Secp256r1Public({:?})", &self.0[..])
+    }
+}
+
+/// Account ID is `blake2_256(ACCOUNT_PREFIX ‖ public)`.
+impl IdentifyAccount for Secp256r1Public {
+    type AccountId = AccountId32;
+
+    fn into_account(self) -> AccountId32 {
+        let mut preimage = ACCOUNT_PREFIX.to_vec();
+        preimage.extend_from_slice(&self.0[..]);
+        blake2_256(&preimage).into()
+    }
+}
+
+impl Secp256r1Public {
+    /// Checks `signature` of `SHA-256(msg)` by this key.
+    fn verify_raw(&self, msg: &[u8], signature: &[u8; 64]) -> bool {
+        match (
+            VerifyingKey::from_sec1_bytes(&self.0[..]),
+            Signature::from_slice(&signature[..]),
+        ) {
+            (Ok(key), Ok(signature)) => key.verify(msg, &signature).is_ok(),
+            _ => false,
+        }
+    }
+}
+
+/// NIST P-256 ECDSA signature type.
+///
+/// P-256 public keys can't be recovered from signatures, so the key is carried along
+/// with the signature and checked to match the signer account.
+#[derive(Encode, Decode, PartialEq, Eq, Clone, scale_info::TypeInfo)]
+pub struct Secp256r1Signature {
+    /// Signer public key.
+    pub public: Secp256r1Public,
+    /// Raw `r ‖ s` signature over `SHA-256(message)`.
+    pub signature: [u8; 64],
+}
+
+impl sp_std::fmt::Debug for Secp256r1Signature {
+    fn fmt(&self, f: &mut sp_std::fmt::Formatter<'_>) -> sp_std::fmt::Result {
+        write!(
+            f,
+            "Secp256r1Signature({:?}, {:?})",
+            self.public,
+            &self.signature[..]
+        )
+    }
+}
+
+/// Expects `public ‖ signature` bytes.
+impl sp_std::convert::TryFrom<Vec<u8>> for Secp256r1Signature {
+    type Error = ();
+
+    fn try_from(data: Vec<u8>) -> Result<Self, Self::Error> {
+        if data.len() == 97 {
+            let mut public = [0u8; 33];
+            let mut signature = [0u8; 64];
+            public.copy_from_slice(&data[..33]);
+            signature.copy_from_slice(&data[33..]);
+            Ok(Secp256r1Signature {
+                public: Secp256r1Public(public),
+                signature,
+            })
+        } else {
+            Err(())
+        }
+    }
+}
+
+impl Verify for Secp256r1Signature {
+    type Signer = Secp256r1Public;
+
+    fn verify<L: Lazy<[u8]>>(&self, mut msg: L, account: &AccountId32) -> bool {
+        self.public.clone().into_account() == *account
+            && self.public.verify_raw(msg.get(), &self.signature)
+    }
+}
+
+impl SigningMessage for Secp256r1Signature {
+    fn signing_message<T: Config>(&self, payload: &SignedPayload<T>) -> Vec<u8> {
+        payload.scale_encoded()
+    }
+}
+
+/// WebAuthn (passkey) assertion over a P-256 credential.
+///
+/// The authenticator signs `authenticatorData ‖ SHA-256(clientDataJSON)`. The message is bound
+/// to the assertion through the `clientDataJSON` challenge, which must be the unpadded base64url
+/// encoding of `SHA-256(message)`.
+#[derive(Encode, Decode, PartialEq, Eq, Clone, scale_info::TypeInfo)]
+pub struct WebAuthnSignature {
+    /// Credential public key.
+    pub public: Secp256r1Public,
+    /// Authenticator data, as returned by the authenticator.
+    pub authenticator_data: Vec<u8>,
+    /// Client data JSON, as returned by the client.
+    pub client_data_json: Vec<u8>,
+    /// Raw `r ‖ s` assertion signature (converted from the ASN.1 DER returned by clients).
+    pub signature: [u8; 64],
+}
+
+impl sp_std::fmt::Debug for WebAuthnSignature {
+    fn fmt(&self, f: &mut sp_std::fmt::Formatter<'_>) -> sp_std::fmt::Result {
+        write!(
+            f,
+            "WebAuthnSignature({:?}, {:?}, {:?}, {:?})",
+            self.public,
+            &self.authenticator_data[..],
+            &self.client_data_json[..],
+            &self.signature[..]
+        )
+    }
+}
+
+/// Expects SCALE encoded assertion.
+impl sp_std::convert::TryFrom<Vec<u8>> for WebAuthnSignature {
+    type Error = ();
+
+    fn try_from(data: Vec<u8>) -> Result<Self, Self::Error> {
+        Self::decode_all(&mut &data[..]).map_err(|_| ())
+    }
+}
+
+impl WebAuthnSignature {
+    /// Challenge value of the `clientDataJSON`, if it is an assertion.
+    pub fn challenge(&self) -> Option<&[u8]> {
+        find(&self.client_data_json, WEBAUTHN_GET_TYPE)?;
+        self.client_data_value(WEBAUTHN_CHALLENGE_KEY)
+    }
+
+    /// Origin of the `clientDataJSON`.
+    pub fn origin(&self) -> Option<&[u8]> {
+        self.client_data_value(WEBAUTHN_ORIGIN_KEY)
+    }
+
+    /// SHA-256 hash of the relying party ID the credential is scoped to.
+    pub fn rp_id_hash(&self) -> Option<&[u8]> {
+        self.authenticator_data.get(..32)
+    }
+
+    /// String value following `key` in the `clientDataJSON`.
+    fn client_data_value(&self, key: &[u8]) -> Option<&[u8]> {
+        let json = &self.client_data_json[..];
+        let start = find(json, key)? + key.len();
+        let len = json[start..].iter().position(|c| *c == b'"')?;
+        Some(&json[start..start + len])
+    }
+
+    /// Whether the authenticator reports the user as present.
+    fn user_present(&self) -> bool {
+        self.authenticator_data.len() >= AUTHENTICATOR_DATA_MIN_LEN
+            && self.authenticator_data[32] & FLAG_USER_PRESENT != 0
+    }
+}
+
+impl Verify for WebAuthnSignature {
+    type Signer = Secp256r1Public;
+
+    fn verify<L: Lazy<[u8]>>(&self, mut msg: L, account: &AccountId32) -> bool {
+        if self.public.clone().into_account() != *account || !self.user_present() {
+            return false;
+        }
+
+        let expected_challenge = base64url_encode(&sha2_256(msg.get()));
+        if self.challenge() != Some(&expected_challenge[..]) {
+            return false;
+        }
+
+        let mut signed = self.authenticator_data.clone();
+        signed.extend_from_slice(&sha2_256(&self.client_data_json));
+        self.public.verify_raw(&signed, &self.signature)
+    }
+}
+
+/// Assertions are scoped to the runtime by `Config::WebAuthnRpId` and `Config::WebAuthnOrigin`.
+impl SigningMessage for WebAuthnSignature {
+    fn signing_message<T: Config>(&self, payload: &SignedPayload<T>) -> Vec<u8> {
+        payload.scale_encoded()
+    }
+
+    fn is_scoped_to<T: Config>(&self) -> bool {
+        self.rp_id_hash() == Some(&sha2_256(T::WebAuthnRpId::get())[..])
+            && self.origin() == Some(T::WebAuthnOrigin::get())
+    }
+}
+
+/// Position of the first occurrence of `needle` in `haystack`.
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+/// Unpadded base64url encoding, as used by WebAuthn challenges.
+pub fn base64url_encode(data: &[u8]) -> Vec<u8> {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+    let mut out = Vec::with_capacity((data.len() * 4 + 2) / 3);
+    for chunk in data.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = (u32::from(b[0]) << 16) | (u32::from(b[1]) << 8) | u32::from(b[2]);
+        for i in 0..=chunk.len() {
+            out.push(ALPHABET[((n >> (18 - 6 * i)) & 0x3f) as usize]);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use p256::ecdsa::{signature::Signer, SigningKey};
+
+    fn signing_key() -> SigningKey {
+        SigningKey::from_bytes(&[7u8; 32].into()).expect("valid key")
+    }
+
+    fn public(key: &SigningKey) -> Secp256r1Public {
+        let mut public = [0u8; 33];
+        public.copy_from_slice(key.verifying_key().to_encoded_point(true).as_bytes());
+        Secp256r1Public(public)
+    }
+
+    fn sign(key: &SigningKey, msg: &[u8]) -> [u8; 64] {
+        let signature: Signature = key.sign(msg);
+        let mut out = [0u8; 64];
+        out.copy_from_slice(&signature.to_bytes()[..]);
+        out
+    }
+
+    fn assertion(key: &SigningKey, msg: &[u8]) -> WebAuthnSignature {
+        let mut authenticator_data = [0u8; 37].to_vec();
+        authenticator_data[32] = FLAG_USER_PRESENT;
+        let mut client_data_json = b"{\"type\":\"webauthn.get\",\"challenge\":\"".to_vec();
+        client_data_json.extend(base64url_encode(&sha2_256(msg)));
+        client_data_json.extend_from_slice(b"\",\"origin\":\"https://astar.network\"}");
+
+        let mut signed = authenticator_data.clone();
+        signed.extend_from_slice(&sha2_256(&client_data_json));
+        WebAuthnSignature {
+            public: public(key),
+            authenticator_data,
+            client_data_json,
+            signature: sign(key, &signed),
+        }
+    }
+
+    #[test]
+    fn base64url_works() {
+        assert_eq!(base64url_encode(b""), b"".to_vec());
+        assert_eq!(base64url_encode(b"f"), b"Zg".to_vec());
+        assert_eq!(base64url_encode(b"fo"), b"Zm8".to_vec());
+        assert_eq!(base64url_encode(b"foo"), b"Zm9v".to_vec());
+        assert_eq!(base64url_encode(&[0xfb, 0xff]), b"-_8".to_vec());
+    }
+
+    #[test]
+    fn secp256r1_verify_works() {
+        let key = signing_key();
+        let account = public(&key).into_account();
+        let msg = b"test secp256r1 signed message";
+        let signature = Secp256r1Signature {
+            public: public(&key),
+            signature: sign(&key, msg),
+        };
+
+        assert!(signature.verify(&msg[..], &account));
+        assert!(!signature.verify(&b"other message"[..], &account));
+        assert!(!signature.verify(&msg[..], &AccountId32::new([0u8; 32])));
+
+        let mut encoded = signature.public.0.to_vec();
+        encoded.extend_from_slice(&signature.signature);
+        assert_eq!(Secp256r1Signature::try_from(encoded), Ok(signature));
+    }
+
+    #[test]
+    fn webauthn_verify_works() {
+        let key = signing_key();
+        let account = public(&key).into_account();
+        let msg = b"test webauthn signed message";
+        let signature = assertion(&key, msg);
+
+        assert!(signature.verify(&msg[..], &account));
+        // challenge bound to the message
+        assert!(!signature.verify(&b"other message"[..], &account));
+        assert_eq!(
+            WebAuthnSignature::try_from(signature.encode()),
+            Ok(signature.clone())
+        );
+
+        // user must be present
+        let mut absent = assertion(&key, msg);
+        absent.authenticator_data[32] = 0;
+        assert!(!absent.verify(&msg[..], &account));
+
+        // registration isn't an assertion
+        let mut create = signature;
+        create.client_data_json = String::from_utf8(create.client_data_json)
+            .unwrap()
+            .replace("webauthn.get", "webauthn.create")
+            .into_bytes();
+        assert_eq!(create.challenge(), None);
+        assert!(!create.verify(&msg[..], &account));
+    }
+
+    #[test]
+    fn webauthn_decode_rejects_trailing_bytes() {
+        let signature = assertion(&signing_key(), b"msg");
+        assert_eq!(signature.origin(), Some(&b"https://astar.network"[..]));
+        assert_eq!(signature.rp_id_hash(), Some(&[0u8; 32][..]));
+
+        let mut encoded = signature.encode();
+        encoded.push(0);
+        assert_eq!(WebAuthnSignature::try_from(encoded), Err(()));
+    }
+}
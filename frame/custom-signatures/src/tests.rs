@@ -16,8 +16,10 @@ use crate as custom_signatures;
 use assert_matches::assert_matches;
 use custom_signatures::*;
 use frame_support::{
+    dispatch::GetDispatchInfo,
     traits::Contains,
-    {assert_err, assert_ok, parameter_types},
+    weights::{Weight, WeightToFee},
+    BoundedVec, {assert_err, assert_ok, parameter_types},
 };
 use hex_literal::hex;
 use parity_scale_codec::Encode;
@@ -26,8 +28,10 @@ use sp_io::{hashing::keccak_256, TestExternalities};
 use sp_keyring::AccountKeyring as Keyring;
 use sp_runtime::{
     testing::{Header, H256},
-    traits::{BlakeTwo256, IdentifyAccount, IdentityLookup, Verify},
-    transaction_validity::TransactionPriority,
+    traits::{BlakeTwo256, IdentifyAccount, IdentityLookup, ValidateUnsigned, Verify},
+    transaction_validity::{
+        InvalidTransaction, TransactionPriority, TransactionSource, TransactionValidityError,
+    },
     MultiSignature, MultiSigner,
 };
 
@@ -41,6 +45,34 @@ type AccountId = <<Signature as Verify>::Signer as IdentifyAccount>::AccountId;
 type Block = frame_system::mocking::MockBlock<Runtime>;
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Runtime>;
 
+#[frame_support::pallet]
+pub mod test_pallet {
+    use frame_support::pallet_prelude::*;
+    use frame_system::pallet_prelude::*;
+
+    #[pallet::config]
+    pub trait Config: frame_system::Config {}
+
+    #[pallet::pallet]
+    pub struct Pallet<T>(_);
+
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        /// Declares `declared` weight, but reports `actual` weight after dispatch.
+        #[pallet::call_index(0)]
+        #[pallet::weight(Weight::from_ref_time(*declared))]
+        pub fn heavy(
+            origin: OriginFor<T>,
+            declared: u64,
+            actual: u64,
+        ) -> DispatchResultWithPostInfo {
+            ensure_signed(origin)?;
+            let _ = declared;
+            Ok(Some(Weight::from_ref_time(actual)).into())
+        }
+    }
+}
+
 frame_support::construct_runtime!(
     pub enum Runtime where
        Block = Block,
@@ -50,6 +82,7 @@ frame_support::construct_runtime!(
         Balances: pallet_balances,
         System: frame_system,
         CustomSignatures: custom_signatures,
+        TestPallet: test_pallet,
     }
 );
 
@@ -113,11 +146,30 @@ impl pallet_balances::Config for Runtime {
     type ReserveIdentifier = ();
 }
 
+impl test_pallet::Config for Runtime {}
+
+parameter_types! {
+    pub static WeightFeeCoefficient: Balance = 0;
+}
+
+pub struct LinearWeightToFee;
+impl WeightToFee for LinearWeightToFee {
+    type Balance = Balance;
+
+    fn weight_to_fee(weight: &Weight) -> Balance {
+        Balance::from(weight.ref_time()) * WeightFeeCoefficient::get()
+    }
+}
+
 const MAGIC_NUMBER: u16 = 0xff50;
 parameter_types! {
     pub const Priority: TransactionPriority = TransactionPriority::MAX;
     pub const CallFee: Balance = 42;
     pub const CallMagicNumber: u16 = MAGIC_NUMBER;
+    pub const ChainId: u64 = 592;
+    pub const MaxBatchCalls: u32 = 4;
+    pub const WebAuthnRpId: &'static [u8] = b"astar.network";
+    pub const WebAuthnOrigin: &'static [u8] = b"https://astar.network";
 }
 
 impl Config for Runtime {
@@ -126,12 +178,116 @@ impl Config for Runtime {
     type Signature = ethereum::EthereumSignature;
     type Signer = <Signature as Verify>::Signer;
     type CallMagicNumber = CallMagicNumber;
+    type ChainId = ChainId;
+    type MaxBatchCalls = MaxBatchCalls;
+    type WebAuthnRpId = WebAuthnRpId;
+    type WebAuthnOrigin = WebAuthnOrigin;
     type Currency = Balances;
     type CallFee = CallFee;
+    type WeightToFee = LinearWeightToFee;
     type OnChargeTransaction = ();
     type UnsignedPriority = Priority;
 }
 
+/// Declares module `$name` with a runtime using `$signature` as `Config::Signature`.
+macro_rules! signature_runtime {
+    ($name:ident, $signature:ty, $signer:ty) => {
+        mod $name {
+            use super::*;
+
+            type Block = frame_system::mocking::MockBlock<Runtime>;
+            type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Runtime>;
+
+            frame_support::construct_runtime!(
+                pub enum Runtime where
+                   Block = Block,
+                   NodeBlock = Block,
+                   UncheckedExtrinsic = UncheckedExtrinsic,
+                {
+                    Balances: pallet_balances,
+                    System: frame_system,
+                    CustomSignatures: custom_signatures,
+                }
+            );
+
+            impl frame_system::Config for Runtime {
+                type RuntimeOrigin = RuntimeOrigin;
+                type BaseCallFilter = frame_support::traits::Everything;
+                type Index = u32;
+                type BlockNumber = BlockNumber;
+                type RuntimeCall = RuntimeCall;
+                type Hash = H256;
+                type Hashing = BlakeTwo256;
+                type AccountId = AccountId;
+                type Lookup = IdentityLookup<Self::AccountId>;
+                type Header = Header;
+                type RuntimeEvent = RuntimeEvent;
+                type BlockHashCount = BlockHashCount;
+                type Version = ();
+                type PalletInfo = PalletInfo;
+                type AccountData = pallet_balances::AccountData<Balance>;
+                type OnNewAccount = ();
+                type OnKilledAccount = ();
+                type DbWeight = ();
+                type SystemWeightInfo = ();
+                type BlockWeights = ();
+                type BlockLength = ();
+                type SS58Prefix = ();
+                type OnSetCode = ();
+                type MaxConsumers = frame_support::traits::ConstU32<16>;
+            }
+
+            impl pallet_balances::Config for Runtime {
+                type Balance = Balance;
+                type RuntimeEvent = RuntimeEvent;
+                type DustRemoval = ();
+                type ExistentialDeposit = ExistentialDeposit;
+                type AccountStore = frame_system::Pallet<Runtime>;
+                type WeightInfo = ();
+                type MaxLocks = ();
+                type MaxReserves = ();
+                type ReserveIdentifier = ();
+            }
+
+            impl Config for Runtime {
+                type RuntimeEvent = RuntimeEvent;
+                type RuntimeCall = RuntimeCall;
+                type Signature = $signature;
+                type Signer = $signer;
+                type CallMagicNumber = CallMagicNumber;
+                type ChainId = ChainId;
+                type MaxBatchCalls = MaxBatchCalls;
+                type WebAuthnRpId = WebAuthnRpId;
+                type WebAuthnOrigin = WebAuthnOrigin;
+                type Currency = Balances;
+                type CallFee = CallFee;
+                type WeightToFee = LinearWeightToFee;
+                type OnChargeTransaction = ();
+                type UnsignedPriority = Priority;
+            }
+
+            pub fn new_test_ext(balances: Vec<(AccountId, Balance)>) -> TestExternalities {
+                let mut storage = frame_system::GenesisConfig::default()
+                    .build_storage::<Runtime>()
+                    .unwrap();
+                let _ = pallet_balances::GenesisConfig::<Runtime> { balances }
+                    .assimilate_storage(&mut storage);
+
+                let mut ext = TestExternalities::from(storage);
+                ext.execute_with(|| System::set_block_number(1));
+                ext
+            }
+        }
+    };
+}
+
+signature_runtime!(eip712_runtime, ethereum::Eip712Signature, MultiSigner);
+signature_runtime!(
+    multi_runtime,
+    multi::MultiSchemeSignature,
+    multi::MultiSchemeSigner
+);
+
 fn new_test_ext() -> TestExternalities {
     let mut storage = frame_system::GenesisConfig::default()
         .build_storage::<Runtime>()
@@ -162,6 +318,73 @@ fn eth_sign(seed: &[u8; 32], data: &[u8]) -> Vec<u8> {
     out
 }
 
+/// Simple `eth_signTypedData_v4` implementation over already built typed data message
+fn eth_sign_typed_data(seed: &[u8; 32], typed_data: &[u8]) -> [u8; 65] {
+    let ecdsa_msg = libsecp256k1::Message::parse(&keccak_256(typed_data));
+    let secret = libsecp256k1::SecretKey::parse(&seed).expect("valid seed");
+    let (signature, recovery_id) = libsecp256k1::sign(&ecdsa_msg, &secret);
+    let mut out = [0u8; 65];
+    out[..64].copy_from_slice(&signature.serialize()[..]);
+    out[64] = recovery_id.serialize() + 27;
+    out
+}
+
+fn p256_public(key: &p256::ecdsa::SigningKey) -> secp256r1::Secp256r1Public {
+    let mut public = [0u8; 33];
+    public.copy_from_slice(key.verifying_key().to_encoded_point(true).as_bytes());
+    secp256r1::Secp256r1Public(public)
+}
+
+/// P-256 signature of `SHA-256(msg)`
+fn p256_sign(key: &p256::ecdsa::SigningKey, msg: &[u8]) -> [u8; 64] {
+    use p256::ecdsa::signature::Signer;
+    let signature: p256::ecdsa::Signature = key.sign(msg);
+    let mut out = [0u8; 64];
+    out.copy_from_slice(&signature.to_bytes()[..]);
+    out
+}
+
+/// Simple `navigator.credentials.get` implementation, asserting `msg` for the relying party
+fn webauthn_sign(
+    key: &p256::ecdsa::SigningKey,
+    msg: &[u8],
+    rp_id: &[u8],
+    origin: &str,
+) -> secp256r1::WebAuthnSignature {
+    // rpIdHash ‖ flags (user present) ‖ signCount
+    let mut authenticator_data = sp_io::hashing::sha2_256(rp_id).to_vec();
+    authenticator_data.extend_from_slice(&[0x01, 0, 0, 0, 1]);
+    let client_data_json = format!(
+        r#"{{"type":"webauthn.get","challenge":"{}","origin":"{}"}}"#,
+        String::from_utf8(secp256r1::base64url_encode(&sp_io::hashing::sha2_256(msg))).unwrap(),
+        origin,
+    )
+    .into_bytes();
+
+    let mut signed = authenticator_data.clone();
+    signed.extend_from_slice(&sp_io::hashing::sha2_256(&client_data_json));
+    secp256r1::WebAuthnSignature {
+        public: p256_public(key),
+        authenticator_data,
+        client_data_json,
+        signature: p256_sign(key, &signed),
+    }
+}
+
+fn signed_payload<'a>(
+    version: &'a PayloadVersion<BlockNumber>,
+    kind: PayloadKind,
+    nonce: u32,
+    call: &'a [u8],
+) -> SignedPayload<'a, Runtime> {
+    SignedPayload {
+        version,
+        kind,
+        nonce,
+        call,
+    }
+}
+
 #[test]
 fn eth_sign_works() {
     let seed = hex!["ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80"];
@@ -182,7 +405,14 @@ fn invalid_signature() {
     let signature = Vec::from(&hex!["dd0992d40e5cdf99db76bed162808508ac65acd7ae2fdc8573594f03ed9c939773e813181788fc02c3c68f3fdc592759b35f6354484343e18cb5317d34dab6c61b"][..]);
     new_test_ext().execute_with(|| {
         assert_err!(
-            CustomSignatures::call(RuntimeOrigin::none(), Box::new(call), bob, signature, 0),
+            CustomSignatures::call(
+                RuntimeOrigin::none(),
+                Box::new(call),
+                bob,
+                signature,
+                0,
+                PayloadVersion::V1,
+            ),
             Error::<Runtime>::InvalidSignature,
         );
     });
@@ -212,6 +442,7 @@ fn balance_transfer() {
             account.clone(),
             signature,
             0,
+            PayloadVersion::V1,
         ));
         assert_eq!(System::account(alice.clone()).data.free, 1_000);
         assert_eq!(System::account(account.clone()).nonce, 1);
@@ -234,6 +465,7 @@ fn balance_transfer() {
                 account.clone(),
                 signature,
                 0,
+                PayloadVersion::V1,
             ),
             Error::<Runtime>::BadNonce,
         );
@@ -247,6 +479,7 @@ fn balance_transfer() {
             account.clone(),
             signature,
             1,
+            PayloadVersion::V1,
         ));
         assert_eq!(System::account(alice).data.free, 2_000);
         assert_eq!(System::account(account.clone()).nonce, 2);
@@ -313,6 +546,7 @@ fn not_allowed_call_filtered() {
             account.clone(),
             signature,
             0,
+            PayloadVersion::V1,
         ));
         assert_eq!(System::account(account.clone()).nonce, 1);
 
@@ -327,3 +561,916 @@ fn not_allowed_call_filtered() {
         );
     })
 }
+
+#[test]
+fn eip712_signature_works() {
+    new_test_ext().execute_with(|| {
+        let pair = ecdsa::Pair::from_seed(&ECDSA_SEED);
+        let account = MultiSigner::from(pair.public()).into_account();
+
+        let alice: <Runtime as frame_system::Config>::AccountId = Keyring::Alice.into();
+        let call: RuntimeCall = pallet_balances::Call::<Runtime>::transfer {
+            dest: alice,
+            value: 1_000,
+        }
+        .into();
+
+        let encoded_call = call.encode();
+        let domain = ethereum::Eip712Domain::of::<Runtime>();
+        assert_eq!(domain.chain_id, 592);
+        assert_eq!(domain.call_magic_number, MAGIC_NUMBER);
+        assert_eq!(domain.genesis_hash, System::block_hash(0));
+
+        let typed_data = ethereum::eip712_signable_message(
+            &domain,
+            &ethereum::eip712_call_hash(&encoded_call, 0),
+        );
+        let signature = ethereum::Eip712Signature(eth_sign_typed_data(&ECDSA_SEED, &typed_data));
+
+        // message built by the signature scheme matches what the wallet signed
+        assert_eq!(
+            signature.signing_message(&signed_payload(
+                &PayloadVersion::V1,
+                PayloadKind::Call,
+                0,
+                &encoded_call
+            )),
+            typed_data
+        );
+        assert!(signature.verify(&typed_data[..], &account));
+
+        // signature is bound to the nonce
+        let other_nonce = signature.signing_message(&signed_payload(
+            &PayloadVersion::V1,
+            PayloadKind::Call,
+            1,
+            &encoded_call,
+        ));
+        assert!(!signature.verify(&other_nonce[..], &account));
+
+        // and to the chain
+        let other_chain = ethereum::Eip712Domain {
+            chain_id: 336,
+            ..domain.clone()
+        };
+        assert_ne!(other_chain.separator(), domain.separator());
+        let other_chain = ethereum::eip712_signable_message(
+            &other_chain,
+            &ethereum::eip712_call_hash(&encoded_call, 0),
+        );
+        assert!(!signature.verify(&other_chain[..], &account));
+
+        // typed data signatures aren't valid `personal_sign` signatures
+        let personal = ethereum::EthereumSignature(signature.0);
+        assert!(!personal.verify(&typed_data[..], &account));
+    })
+}
+
+#[test]
+fn fee_follows_call_weight() {
+    new_test_ext().execute_with(|| {
+        WeightFeeCoefficient::set(2);
+        let pair = ecdsa::Pair::from_seed(&ECDSA_SEED);
+        let account = MultiSigner::from(pair.public()).into_account();
+        let alice: <Runtime as frame_system::Config>::AccountId = Keyring::Alice.into();
+
+        let call: RuntimeCall = pallet_balances::Call::<Runtime>::transfer {
+            dest: alice.clone(),
+            value: 1_000,
+        }
+        .into();
+        let weight_fee = LinearWeightToFee::weight_to_fee(&call.get_dispatch_info().weight);
+        assert!(weight_fee > 0);
+
+        let payload = (MAGIC_NUMBER, 0u32, call.clone());
+        let signature = eth_sign(&ECDSA_SEED, payload.encode().as_ref()).into();
+        assert_ok!(CustomSignatures::call(
+            RuntimeOrigin::none(),
+            Box::new(call),
+            account.clone(),
+            signature,
+            0,
+            PayloadVersion::V1,
+        ));
+        assert_eq!(System::account(alice).data.free, 1_000);
+        assert_eq!(
+            System::account(account).data.free,
+            1_000_000_000 - 1_000 - CallFee::get() - weight_fee
+        );
+    })
+}
+
+#[test]
+fn unused_weight_fee_is_refunded() {
+    new_test_ext().execute_with(|| {
+        WeightFeeCoefficient::set(2);
+        let pair = ecdsa::Pair::from_seed(&ECDSA_SEED);
+        let account = MultiSigner::from(pair.public()).into_account();
+        let issuance = Balances::total_issuance();
+
+        let call: RuntimeCall = test_pallet::Call::<Runtime>::heavy {
+            declared: 1_000,
+            actual: 400,
+        }
+        .into();
+        let payload = (MAGIC_NUMBER, 0u32, call.clone());
+        let signature = eth_sign(&ECDSA_SEED, payload.encode().as_ref()).into();
+
+        let post_info = CustomSignatures::call(
+            RuntimeOrigin::none(),
+            Box::new(call),
+            account.clone(),
+            signature,
+            0,
+            PayloadVersion::V1,
+        )
+        .expect("call should succeed");
+        assert_eq!(post_info.pays_fee, frame_support::dispatch::Pays::No);
+        // used weight of the call plus the pallet overhead
+        assert_eq!(
+            post_info.actual_weight,
+            Some(Weight::from_ref_time(400 + 10_000))
+        );
+
+        // only the actually used weight is charged
+        let charged = CallFee::get() + 2 * 400;
+        assert_eq!(System::account(account).data.free, 1_000_000_000 - charged);
+        // and routed to `OnChargeTransaction`, which burns it
+        assert_eq!(Balances::total_issuance(), issuance - charged);
+    })
+}
+
+#[test]
+fn mortal_payload_works() {
+    new_test_ext().execute_with(|| {
+        let pair = ecdsa::Pair::from_seed(&ECDSA_SEED);
+        let account = MultiSigner::from(pair.public()).into_account();
+        let alice: <Runtime as frame_system::Config>::AccountId = Keyring::Alice.into();
+        let call: RuntimeCall = pallet_balances::Call::<Runtime>::transfer {
+            dest: alice.clone(),
+            value: 1_000,
+        }
+        .into();
+
+        let version = PayloadVersion::V2 {
+            expires_at: Some(10),
+        };
+        let payload = (
+            MAGIC_NUMBER,
+            System::block_hash(0),
+            Some(10u64),
+            0u32,
+            call.clone(),
+        );
+        let signature: Vec<u8> = eth_sign(&ECDSA_SEED, payload.encode().as_ref()).into();
+
+        // the legacy format doesn't match the signed payload
+        assert_err!(
+            CustomSignatures::call(
+                RuntimeOrigin::none(),
+                Box::new(call.clone()),
+                account.clone(),
+                signature.clone(),
+                0,
+                PayloadVersion::V1,
+            ),
+            Error::<Runtime>::InvalidSignature,
+        );
+
+        // longevity follows the expiry block
+        let unsigned_call = custom_signatures::Call::<Runtime>::call {
+            call: Box::new(call.clone()),
+            signer: account.clone(),
+            signature: signature.clone(),
+            nonce: 0,
+            version: version.clone(),
+        };
+        let validity = <CustomSignatures as ValidateUnsigned>::validate_unsigned(
+            TransactionSource::External,
+            &unsigned_call,
+        )
+        .expect("transaction should be valid");
+        assert_eq!(validity.longevity, 9);
+
+        assert_ok!(CustomSignatures::call(
+            RuntimeOrigin::none(),
+            Box::new(call),
+            account,
+            signature,
+            0,
+            version,
+        ));
+        assert_eq!(System::account(alice).data.free, 1_000);
+    })
+}
+
+#[test]
+fn expired_payload_rejected() {
+    new_test_ext().execute_with(|| {
+        let pair = ecdsa::Pair::from_seed(&ECDSA_SEED);
+        let account = MultiSigner::from(pair.public()).into_account();
+        let alice: <Runtime as frame_system::Config>::AccountId = Keyring::Alice.into();
+        let call: RuntimeCall = pallet_balances::Call::<Runtime>::transfer {
+            dest: alice,
+            value: 1_000,
+        }
+        .into();
+
+        let version = PayloadVersion::V2 {
+            expires_at: Some(10),
+        };
+        let payload = (
+            MAGIC_NUMBER,
+            System::block_hash(0),
+            Some(10u64),
+            0u32,
+            call.clone(),
+        );
+        let signature: Vec<u8> = eth_sign(&ECDSA_SEED, payload.encode().as_ref()).into();
+
+        System::set_block_number(11);
+        let unsigned_call = custom_signatures::Call::<Runtime>::call {
+            call: Box::new(call.clone()),
+            signer: account.clone(),
+            signature: signature.clone(),
+            nonce: 0,
+            version: version.clone(),
+        };
+        assert_eq!(
+            <CustomSignatures as ValidateUnsigned>::validate_unsigned(
+                TransactionSource::External,
+                &unsigned_call,
+            ),
+            Err(TransactionValidityError::Invalid(InvalidTransaction::Stale)),
+        );
+        assert_err!(
+            CustomSignatures::call(
+                RuntimeOrigin::none(),
+                Box::new(call),
+                account,
+                signature,
+                0,
+                version,
+            ),
+            Error::<Runtime>::Expired,
+        );
+    })
+}
+
+#[test]
+fn payload_bound_to_genesis_hash() {
+    new_test_ext().execute_with(|| {
+        let pair = ecdsa::Pair::from_seed(&ECDSA_SEED);
+        let account = MultiSigner::from(pair.public()).into_account();
+        let alice: <Runtime as frame_system::Config>::AccountId = Keyring::Alice.into();
+        let call: RuntimeCall = pallet_balances::Call::<Runtime>::transfer {
+            dest: alice,
+            value: 1_000,
+        }
+        .into();
+
+        // signed for a fork with another genesis
+        let payload = (
+            MAGIC_NUMBER,
+            H256::repeat_byte(1),
+            None::<u64>,
+            0u32,
+            call.clone(),
+        );
+        let signature = eth_sign(&ECDSA_SEED, payload.encode().as_ref()).into();
+
+        assert_err!(
+            CustomSignatures::call(
+                RuntimeOrigin::none(),
+                Box::new(call),
+                account,
+                signature,
+                0,
+                PayloadVersion::V2 { expires_at: None },
+            ),
+            Error::<Runtime>::InvalidSignature,
+        );
+    })
+}
+
+#[test]
+fn call_batch_works() {
+    new_test_ext().execute_with(|| {
+        WeightFeeCoefficient::set(2);
+        let pair = ecdsa::Pair::from_seed(&ECDSA_SEED);
+        let account = MultiSigner::from(pair.public()).into_account();
+        let alice: <Runtime as frame_system::Config>::AccountId = Keyring::Alice.into();
+        let bob: <Runtime as frame_system::Config>::AccountId = Keyring::Bob.into();
+
+        let calls: BoundedVec<RuntimeCall, MaxBatchCalls> = vec![
+            pallet_balances::Call::<Runtime>::transfer {
+                dest: alice.clone(),
+                value: 1_000,
+            }
+            .into(),
+            pallet_balances::Call::<Runtime>::transfer {
+                dest: bob.clone(),
+                value: 2_000,
+            }
+            .into(),
+            test_pallet::Call::<Runtime>::heavy {
+                declared: 1_000,
+                actual: 400,
+            }
+            .into(),
+        ]
+        .try_into()
+        .unwrap();
+        let declared_weight = calls
+            .iter()
+            .fold(Weight::zero(), |w, c| w + c.get_dispatch_info().weight);
+        let used_weight = declared_weight - Weight::from_ref_time(600);
+
+        let payload = (MAGIC_NUMBER, BATCH_PAYLOAD_TAG, 0u32, calls.clone());
+        let signature = eth_sign(&ECDSA_SEED, payload.encode().as_ref()).into();
+
+        // signature of a single call doesn't match a batch
+        let single_payload = (MAGIC_NUMBER, 0u32, calls[0].clone());
+        let single_signature = eth_sign(&ECDSA_SEED, single_payload.encode().as_ref()).into();
+        assert_err!(
+            CustomSignatures::call_batch(
+                RuntimeOrigin::none(),
+                calls.clone(),
+                account.clone(),
+                single_signature,
+                0,
+                PayloadVersion::V1,
+            ),
+            Error::<Runtime>::InvalidSignature,
+        );
+
+        assert_ok!(<CustomSignatures as ValidateUnsigned>::validate_unsigned(
+            TransactionSource::External,
+            &Call::call_batch {
+                calls: calls.clone(),
+                signer: account.clone(),
+                signature: signature.clone(),
+                nonce: 0,
+                version: PayloadVersion::V1,
+            },
+        ));
+
+        let post_info = CustomSignatures::call_batch(
+            RuntimeOrigin::none(),
+            calls,
+            account.clone(),
+            signature,
+            0,
+            PayloadVersion::V1,
+        )
+        .expect("batch should succeed");
+        assert_eq!(
+            post_info.actual_weight,
+            Some(used_weight + Weight::from_ref_time(10_000))
+        );
+
+        System::assert_last_event(RuntimeEvent::CustomSignatures(Event::BatchExecuted(
+            account.clone(),
+            vec![Ok(()), Ok(()), Ok(())],
+        )));
+        assert_eq!(System::account(alice).data.free, 1_000);
+        assert_eq!(System::account(bob).data.free, 2_000);
+        // single base fee, weight fee only for the used weight
+        let charged = CallFee::get() + 2 * Balance::from(used_weight.ref_time());
+        assert_eq!(
+            System::account(account.clone()).data.free,
+            1_000_000_000 - 3_000 - charged
+        );
+        assert_eq!(System::account_nonce(account), 1);
+    })
+}
+
+#[test]
+fn single_call_signature_not_valid_for_batch() {
+    new_test_ext().execute_with(|| {
+        let pair = ecdsa::Pair::from_seed(&ECDSA_SEED);
+        let account = MultiSigner::from(pair.public()).into_account();
+        let alice: <Runtime as frame_system::Config>::AccountId = Keyring::Alice.into();
+
+        let calls: BoundedVec<RuntimeCall, MaxBatchCalls> =
+            vec![pallet_balances::Call::<Runtime>::transfer {
+                dest: alice,
+                value: 1_000,
+            }
+            .into()]
+            .try_into()
+            .unwrap();
+        let encoded_calls = calls.encode();
+
+        // single call payload over the very same bytes as the batch
+        let mut single_payload = (MAGIC_NUMBER, 0u32).encode();
+        single_payload.extend_from_slice(&encoded_calls);
+        let signature = eth_sign(&ECDSA_SEED, &single_payload);
+
+        assert_eq!(
+            <CustomSignatures as ValidateUnsigned>::validate_unsigned(
+                TransactionSource::External,
+                &Call::call_batch {
+                    calls: calls.clone(),
+                    signer: account.clone(),
+                    signature: signature.clone(),
+                    nonce: 0,
+                    version: PayloadVersion::V1,
+                },
+            ),
+            Err(TransactionValidityError::Invalid(
+                InvalidTransaction::BadProof
+            )),
+        );
+        assert_err!(
+            CustomSignatures::call_batch(
+                RuntimeOrigin::none(),
+                calls,
+                account.clone(),
+                signature,
+                0,
+                PayloadVersion::V1,
+            ),
+            Error::<Runtime>::InvalidSignature,
+        );
+        assert_eq!(System::account_nonce(account), 0);
+
+        // typed data of calls and batches are distinct types
+        for version in [PayloadVersion::V1, PayloadVersion::V2 { expires_at: None }] {
+            let call = signed_payload(&version, PayloadKind::Call, 0, &encoded_calls);
+            let batch = signed_payload(&version, PayloadKind::Batch, 0, &encoded_calls);
+            assert_ne!(call.scale_encoded(), batch.scale_encoded());
+            let signature = ethereum::Eip712Signature([0u8; 65]);
+            assert_ne!(
+                signature.signing_message(&call),
+                signature.signing_message(&batch)
+            );
+        }
+    })
+}
+
+#[test]
+fn call_batch_reverted_on_failure() {
+    new_test_ext().execute_with(|| {
+        let pair = ecdsa::Pair::from_seed(&ECDSA_SEED);
+        let account = MultiSigner::from(pair.public()).into_account();
+        let alice: <Runtime as frame_system::Config>::AccountId = Keyring::Alice.into();
+
+        let calls: BoundedVec<RuntimeCall, MaxBatchCalls> = vec![
+            pallet_balances::Call::<Runtime>::transfer {
+                dest: alice.clone(),
+                value: 1_000,
+            }
+            .into(),
+            // filtered out by `BaseCallFilter`
+            frame_system::Call::<Runtime>::remark { remark: vec![] }.into(),
+            pallet_balances::Call::<Runtime>::transfer {
+                dest: alice.clone(),
+                value: 1_000,
+            }
+            .into(),
+        ]
+        .try_into()
+        .unwrap();
+        let payload = (MAGIC_NUMBER, BATCH_PAYLOAD_TAG, 0u32, calls.clone());
+        let signature = eth_sign(&ECDSA_SEED, payload.encode().as_ref()).into();
+
+        assert_ok!(CustomSignatures::call_batch(
+            RuntimeOrigin::none(),
+            calls,
+            account.clone(),
+            signature,
+            0,
+            PayloadVersion::V1,
+        ));
+
+        // results up to the failed call are reported
+        System::assert_last_event(RuntimeEvent::CustomSignatures(Event::BatchExecuted(
+            account.clone(),
+            vec![
+                Ok(()),
+                Err(frame_system::Error::<Runtime>::CallFiltered.into()),
+            ],
+        )));
+        // first transfer is reverted, fee and nonce are still charged
+        assert_eq!(System::account(alice).data.free, 0);
+        assert_eq!(
+            System::account(account.clone()).data.free,
+            1_000_000_000 - CallFee::get()
+        );
+        assert_eq!(System::account_nonce(account), 1);
+    })
+}
+
+#[test]
+fn sponsored_call_works() {
+    new_test_ext().execute_with(|| {
+        let pair = ecdsa::Pair::from_seed(&ECDSA_SEED);
+        let sponsor = MultiSigner::from(pair.public()).into_account();
+        // user without any balance
+        let user_seed = [7u8; 32];
+        let user_pair = ecdsa::Pair::from_seed(&user_seed);
+        let user = MultiSigner::from(user_pair.public()).into_account();
+
+        let call: RuntimeCall = frame_system::Call::<Runtime>::remark_with_event {
+            remark: b"hello".to_vec(),
+        }
+        .into();
+        let payload = (
+            MAGIC_NUMBER,
+            SPONSORED_PAYLOAD_TAG,
+            0u32,
+            sponsor.clone(),
+            call.clone(),
+        );
+        let signature: Vec<u8> = eth_sign(&user_seed, payload.encode().as_ref()).into();
+
+        // no allowance given yet
+        assert_err!(
+            CustomSignatures::sponsored_call(
+                RuntimeOrigin::signed(sponsor.clone()),
+                Box::new(call.clone()),
+                user.clone(),
+                signature.clone(),
+                0,
+                PayloadVersion::V1,
+            ),
+            Error::<Runtime>::AllowanceExceeded,
+        );
+
+        assert_ok!(CustomSignatures::set_sponsor_allowance(
+            RuntimeOrigin::signed(sponsor.clone()),
+            user.clone(),
+            50,
+        ));
+        System::assert_last_event(RuntimeEvent::CustomSignatures(Event::SponsorAllowanceSet(
+            sponsor.clone(),
+            user.clone(),
+            50,
+        )));
+
+        // signature of the user is required
+        assert_err!(
+            CustomSignatures::sponsored_call(
+                RuntimeOrigin::signed(sponsor.clone()),
+                Box::new(call.clone()),
+                user.clone(),
+                eth_sign(&ECDSA_SEED, payload.encode().as_ref()).into(),
+                0,
+                PayloadVersion::V1,
+            ),
+            Error::<Runtime>::InvalidSignature,
+        );
+
+        let post_info = CustomSignatures::sponsored_call(
+            RuntimeOrigin::signed(sponsor.clone()),
+            Box::new(call),
+            user.clone(),
+            signature,
+            0,
+            PayloadVersion::V1,
+        )
+        .expect("sponsored call should succeed");
+        assert_eq!(post_info.pays_fee, frame_support::dispatch::Pays::No);
+
+        assert_eq!(
+            System::events()
+                .iter()
+                .filter(|r| r.event
+                    == RuntimeEvent::CustomSignatures(Event::FeeSponsored(
+                        sponsor.clone(),
+                        user.clone(),
+                        CallFee::get(),
+                    )))
+                .count(),
+            1
+        );
+        System::assert_last_event(RuntimeEvent::CustomSignatures(Event::Executed(
+            user.clone(),
+            Ok(()),
+        )));
+        // fee paid by the sponsor and deducted from the allowance
+        assert_eq!(
+            System::account(sponsor.clone()).data.free,
+            1_000_000_000 - CallFee::get()
+        );
+        assert_eq!(System::account(user.clone()).data.free, 0);
+        assert_eq!(System::account_nonce(user.clone()), 1);
+        assert_eq!(
+            CustomSignatures::sponsor_allowance(&sponsor, &user),
+            50 - CallFee::get()
+        );
+
+        // remaining allowance doesn't cover another call
+        let call: RuntimeCall = frame_system::Call::<Runtime>::remark_with_event {
+            remark: b"again".to_vec(),
+        }
+        .into();
+        let payload = (
+            MAGIC_NUMBER,
+            SPONSORED_PAYLOAD_TAG,
+            1u32,
+            sponsor.clone(),
+            call.clone(),
+        );
+        assert_err!(
+            CustomSignatures::sponsored_call(
+                RuntimeOrigin::signed(sponsor.clone()),
+                Box::new(call),
+                user.clone(),
+                eth_sign(&user_seed, payload.encode().as_ref()).into(),
+                1,
+                PayloadVersion::V1,
+            ),
+            Error::<Runtime>::AllowanceExceeded,
+        );
+
+        // zero allowance removes the sponsorship
+        assert_ok!(CustomSignatures::set_sponsor_allowance(
+            RuntimeOrigin::signed(sponsor.clone()),
+            user.clone(),
+            0,
+        ));
+        assert!(!SponsorAllowance::<Runtime>::contains_key(&sponsor, &user));
+    })
+}
+
+#[test]
+fn sponsored_signatures_are_bound_to_sponsor() {
+    new_test_ext().execute_with(|| {
+        let pair = ecdsa::Pair::from_seed(&ECDSA_SEED);
+        let sponsor = MultiSigner::from(pair.public()).into_account();
+        let other_sponsor: AccountId = Keyring::Bob.into();
+        let user_seed = [7u8; 32];
+        let user_pair = ecdsa::Pair::from_seed(&user_seed);
+        let user = MultiSigner::from(user_pair.public()).into_account();
+        for sponsor in [&sponsor, &other_sponsor] {
+            assert_ok!(CustomSignatures::set_sponsor_allowance(
+                RuntimeOrigin::signed(sponsor.clone()),
+                user.clone(),
+                50,
+            ));
+        }
+
+        let call: RuntimeCall = frame_system::Call::<Runtime>::remark_with_event {
+            remark: b"hello".to_vec(),
+        }
+        .into();
+        let payload = (
+            MAGIC_NUMBER,
+            SPONSORED_PAYLOAD_TAG,
+            0u32,
+            sponsor.clone(),
+            call.clone(),
+        );
+        let signature: Vec<u8> = eth_sign(&user_seed, payload.encode().as_ref()).into();
+
+        // sponsored signatures can't be submitted at the signer's expense
+        assert_eq!(
+            <CustomSignatures as ValidateUnsigned>::validate_unsigned(
+                TransactionSource::External,
+                &Call::call {
+                    call: Box::new(call.clone()),
+                    signer: user.clone(),
+                    signature: signature.clone(),
+                    nonce: 0,
+                    version: PayloadVersion::V1,
+                },
+            ),
+            Err(TransactionValidityError::Invalid(
+                InvalidTransaction::BadProof
+            )),
+        );
+        assert_err!(
+            CustomSignatures::call(
+                RuntimeOrigin::none(),
+                Box::new(call.clone()),
+                user.clone(),
+                signature.clone(),
+                0,
+                PayloadVersion::V1,
+            ),
+            Error::<Runtime>::InvalidSignature,
+        );
+
+        // nor by another sponsor
+        assert_err!(
+            CustomSignatures::sponsored_call(
+                RuntimeOrigin::signed(other_sponsor),
+                Box::new(call.clone()),
+                user.clone(),
+                signature.clone(),
+                0,
+                PayloadVersion::V1,
+            ),
+            Error::<Runtime>::InvalidSignature,
+        );
+
+        // signatures paid by the signer can't be sponsored
+        let payload = (MAGIC_NUMBER, 0u32, call.clone());
+        assert_err!(
+            CustomSignatures::sponsored_call(
+                RuntimeOrigin::signed(sponsor.clone()),
+                Box::new(call.clone()),
+                user.clone(),
+                eth_sign(&user_seed, payload.encode().as_ref()).into(),
+                0,
+                PayloadVersion::V1,
+            ),
+            Error::<Runtime>::InvalidSignature,
+        );
+
+        assert_ok!(CustomSignatures::sponsored_call(
+            RuntimeOrigin::signed(sponsor),
+            Box::new(call),
+            user.clone(),
+            signature,
+            0,
+            PayloadVersion::V1,
+        ));
+        assert_eq!(System::account_nonce(user), 1);
+    })
+}
+
+#[test]
+fn eip712_signed_calls_work() {
+    use eip712_runtime::{
+        CustomSignatures, Runtime, RuntimeCall, RuntimeEvent, RuntimeOrigin, System,
+    };
+
+    let pair = ecdsa::Pair::from_seed(&ECDSA_SEED);
+    let account = MultiSigner::from(pair.public()).into_account();
+    eip712_runtime::new_test_ext(vec![(account.clone(), 1_000_000_000)]).execute_with(|| {
+        let alice: AccountId = Keyring::Alice.into();
+        let call: RuntimeCall = pallet_balances::Call::<Runtime>::transfer {
+            dest: alice.clone(),
+            value: 1_000,
+        }
+        .into();
+        let domain = ethereum::Eip712Domain::of::<Runtime>();
+
+        // `personal_sign` signature of the payload isn't a typed data signature
+        let payload = (MAGIC_NUMBER, 0u32, call.clone());
+        assert_err!(
+            CustomSignatures::call(
+                RuntimeOrigin::none(),
+                Box::new(call.clone()),
+                account.clone(),
+                eth_sign(&ECDSA_SEED, payload.encode().as_ref()),
+                0,
+                PayloadVersion::V1,
+            ),
+            Error::<Runtime>::InvalidSignature,
+        );
+
+        let typed_data = ethereum::eip712_signable_message(
+            &domain,
+            &ethereum::eip712_call_hash(&call.encode(), 0),
+        );
+        let signature = eth_sign_typed_data(&ECDSA_SEED, &typed_data).to_vec();
+        assert_ok!(<CustomSignatures as ValidateUnsigned>::validate_unsigned(
+            TransactionSource::External,
+            &Call::call {
+                call: Box::new(call.clone()),
+                signer: account.clone(),
+                signature: signature.clone(),
+                nonce: 0,
+                version: PayloadVersion::V1,
+            },
+        ));
+        assert_ok!(CustomSignatures::call(
+            RuntimeOrigin::none(),
+            Box::new(call.clone()),
+            account.clone(),
+            signature,
+            0,
+            PayloadVersion::V1,
+        ));
+        System::assert_last_event(RuntimeEvent::CustomSignatures(Event::Executed(
+            account.clone(),
+            Ok(()),
+        )));
+        assert_eq!(System::account(alice.clone()).data.free, 1_000);
+        assert_eq!(System::account_nonce(account.clone()), 1);
+
+        // mortal batch signed as `MortalBatch` typed data
+        let calls: BoundedVec<RuntimeCall, MaxBatchCalls> =
+            vec![call.clone(), call].try_into().unwrap();
+        let typed_data = ethereum::eip712_signable_message(
+            &domain,
+            &ethereum::eip712_mortal_batch_hash(&calls.encode(), 1, 10),
+        );
+        assert_ok!(CustomSignatures::call_batch(
+            RuntimeOrigin::none(),
+            calls,
+            account.clone(),
+            eth_sign_typed_data(&ECDSA_SEED, &typed_data).to_vec(),
+            1,
+            PayloadVersion::V2 {
+                expires_at: Some(10)
+            },
+        ));
+        assert_eq!(System::account(alice).data.free, 3_000);
+        assert_eq!(System::account_nonce(account), 2);
+    })
+}
+
+#[test]
+fn multi_scheme_signed_calls_work() {
+    use multi::{MultiSchemeSignature, MultiSchemeSigner};
+    use multi_runtime::{CustomSignatures, Runtime, RuntimeCall, RuntimeOrigin, System};
+
+    let pair = ecdsa::Pair::from_seed(&ECDSA_SEED);
+    let eth_account = MultiSchemeSigner::from(pair.public()).into_account();
+    let p256_key = p256::ecdsa::SigningKey::from_bytes(&[7u8; 32].into()).unwrap();
+    let p256_account = MultiSchemeSigner::from(p256_public(&p256_key)).into_account();
+    let passkey = p256::ecdsa::SigningKey::from_bytes(&[8u8; 32].into()).unwrap();
+    let passkey_account = MultiSchemeSigner::from(p256_public(&passkey)).into_account();
+
+    multi_runtime::new_test_ext(vec![
+        (eth_account.clone(), 1_000_000_000),
+        (p256_account.clone(), 1_000_000_000),
+        (passkey_account.clone(), 1_000_000_000),
+    ])
+    .execute_with(|| {
+        let alice: AccountId = Keyring::Alice.into();
+        let call: RuntimeCall = pallet_balances::Call::<Runtime>::transfer {
+            dest: alice.clone(),
+            value: 1_000,
+        }
+        .into();
+        let dispatch = |signer: &AccountId, signature: Vec<u8>, nonce: u32| {
+            CustomSignatures::call(
+                RuntimeOrigin::none(),
+                Box::new(call.clone()),
+                signer.clone(),
+                signature,
+                nonce,
+                PayloadVersion::V1,
+            )
+        };
+
+        // Ethereum `personal_sign`
+        let eth_signature = eth_sign(
+            &ECDSA_SEED,
+            (MAGIC_NUMBER, 0u32, call.clone()).encode().as_ref(),
+        );
+        // raw signature bytes lack the scheme
+        assert_err!(
+            dispatch(&eth_account, eth_signature.clone(), 0),
+            Error::<Runtime>::DecodeFailure,
+        );
+        let signature = MultiSchemeSignature::Ethereum(ethereum::EthereumSignature(
+            eth_signature.try_into().unwrap(),
+        ));
+        assert_ok!(dispatch(&eth_account, signature.encode(), 0));
+
+        // EIP-712 typed data, same account
+        let typed_data = ethereum::eip712_signable_message(
+            &ethereum::Eip712Domain::of::<Runtime>(),
+            &ethereum::eip712_call_hash(&call.encode(), 1),
+        );
+        let signature = MultiSchemeSignature::Eip712(ethereum::Eip712Signature(
+            eth_sign_typed_data(&ECDSA_SEED, &typed_data),
+        ));
+        assert_ok!(dispatch(&eth_account, signature.encode(), 1));
+        assert_eq!(System::account_nonce(&eth_account), 2);
+
+        // P-256
+        let message = (MAGIC_NUMBER, 0u32, call.clone()).encode();
+        let signature = MultiSchemeSignature::Secp256r1(secp256r1::Secp256r1Signature {
+            public: p256_public(&p256_key),
+            signature: p256_sign(&p256_key, &message),
+        });
+        // the key is bound to its account
+        assert_err!(
+            dispatch(&eth_account, signature.encode(), 2),
+            Error::<Runtime>::InvalidSignature,
+        );
+        assert_ok!(dispatch(&p256_account, signature.encode(), 0));
+        assert_eq!(System::account_nonce(&p256_account), 1);
+
+        // WebAuthn, scoped to the configured relying party and origin
+        for (rp_id, origin) in [
+            (&b"evil.network"[..], "https://astar.network"),
+            (&b"astar.network"[..], "https://evil.network"),
+        ] {
+            let signature =
+                MultiSchemeSignature::WebAuthn(webauthn_sign(&passkey, &message, rp_id, origin));
+            assert_err!(
+                dispatch(&passkey_account, signature.encode(), 0),
+                Error::<Runtime>::InvalidSignature,
+            );
+        }
+        let signature = MultiSchemeSignature::WebAuthn(webauthn_sign(
+            &passkey,
+            &message,
+            b"astar.network",
+            "https://astar.network",
+        ));
+        assert_ok!(dispatch(&passkey_account, signature.encode(), 0));
+        assert_eq!(System::account_nonce(&passkey_account), 1);
+
+        assert_eq!(System::account(alice).data.free, 4_000);
+    })
+}
@@ -0,0 +1,729 @@
+// This file is part of Astar.
+
+// Copyright (C) 2019-2023 Stake Technologies Pte.Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// Astar is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Astar is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Astar. If not, see <http://www.gnu.org/licenses/>.
+
+//! Translation of typed XVM call arguments into the ABI of the target VM.
+//!
+//! A call with [`XvmContext::typed_input`] set carries a SCALE encoded [`TypedInput`] as input,
+//! instead of input already encoded for the target VM. The backend encodes its arguments with
+//! the Solidity ABI for EVM contracts, or with SCALE for ink! contracts, after the selector.
+
+use crate::*;
+use parity_scale_codec::{Compact, DecodeLimit};
+use sp_core::{H160, U256};
+use sp_std::{boxed::Box, vec};
+
+/// Maximum nesting depth of arrays in call arguments.
+pub const MAX_ARRAY_DEPTH: u32 = 8;
+
+/// Depth limit of decoding a [`TypedInput`], generous enough for arguments nested up to
+/// [`MAX_ARRAY_DEPTH`] which is checked once decoded.
+const TYPED_INPUT_DECODE_DEPTH_LIMIT: u32 = 4 * MAX_ARRAY_DEPTH;
+
+/// Type of an XVM call argument.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, scale_info::TypeInfo)]
+pub enum XvmType {
+    Bool,
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+    U256,
+    I8,
+    I16,
+    I32,
+    I64,
+    I128,
+    /// Dynamically sized byte array.
+    Bytes,
+    /// EVM address, `address` in Solidity.
+    Address,
+    /// 32 bytes account identifier, `bytes32` in Solidity.
+    AccountId,
+    /// Dynamically sized array of the given type.
+    Array(Box<XvmType>),
+}
+
+impl XvmType {
+    /// Number of nested arrays of the type, zero for non-array types.
+    pub fn depth(&self) -> u32 {
+        let mut depth = 0;
+        let mut ty = self;
+        while let XvmType::Array(element) = ty {
+            depth += 1;
+            ty = element;
+        }
+        depth
+    }
+}
+
+/// XVM call argument.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, scale_info::TypeInfo)]
+pub enum XvmValue {
+    Bool(bool),
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    U128(u128),
+    U256(U256),
+    I8(i8),
+    I16(i16),
+    I32(i32),
+    I64(i64),
+    I128(i128),
+    Bytes(Vec<u8>),
+    Address(H160),
+    AccountId([u8; 32]),
+    /// Array with its element type, all elements must be of that type.
+    Array(XvmType, Vec<XvmValue>),
+}
+
+impl XvmValue {
+    /// Type of the value.
+    pub fn ty(&self) -> XvmType {
+        match self {
+            XvmValue::Bool(_) => XvmType::Bool,
+            XvmValue::U8(_) => XvmType::U8,
+            XvmValue::U16(_) => XvmType::U16,
+            XvmValue::U32(_) => XvmType::U32,
+            XvmValue::U64(_) => XvmType::U64,
+            XvmValue::U128(_) => XvmType::U128,
+            XvmValue::U256(_) => XvmType::U256,
+            XvmValue::I8(_) => XvmType::I8,
+            XvmValue::I16(_) => XvmType::I16,
+            XvmValue::I32(_) => XvmType::I32,
+            XvmValue::I64(_) => XvmType::I64,
+            XvmValue::I128(_) => XvmType::I128,
+            XvmValue::Bytes(_) => XvmType::Bytes,
+            XvmValue::Address(_) => XvmType::Address,
+            XvmValue::AccountId(_) => XvmType::AccountId,
+            XvmValue::Array(ty, _) => XvmType::Array(Box::new(ty.clone())),
+        }
+    }
+
+    /// Checks array elements are of the array element type, and arrays are nested at most
+    /// `depth` times.
+    fn check(&self, depth: u32) -> Result<(), AbiError> {
+        if let XvmValue::Array(ty, values) = self {
+            if ty.depth() >= depth {
+                return Err(AbiError::DepthExceeded);
+            }
+            for value in values {
+                if value.ty() != *ty {
+                    return Err(AbiError::TypeMismatch);
+                }
+                value.check(depth - 1)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// ABI translation errors.
+#[derive(PartialEq, Eq, Clone, Copy, RuntimeDebug)]
+pub enum AbiError {
+    /// Array element doesn't match the array element type.
+    TypeMismatch,
+    /// Data doesn't match the expected types.
+    InvalidEncoding,
+    /// Arrays are nested deeper than [`MAX_ARRAY_DEPTH`].
+    DepthExceeded,
+}
+
+/// ABI of a VM.
+#[derive(PartialEq, Eq, Clone, Copy, RuntimeDebug)]
+pub enum TargetAbi {
+    /// Solidity contract ABI.
+    Solidity,
+    /// ink! message ABI, i.e. SCALE.
+    Ink,
+}
+
+/// Typed input of an XVM call.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, scale_info::TypeInfo)]
+pub struct TypedInput {
+    /// Function selector, prepended to the encoded arguments.
+    pub selector: Vec<u8>,
+    /// Call arguments.
+    pub args: Vec<XvmValue>,
+}
+
+impl TypedInput {
+    /// Encodes the call input for the target ABI.
+    pub fn encode_for(&self, abi: TargetAbi) -> Result<Vec<u8>, AbiError> {
+        let args = match abi {
+            TargetAbi::Solidity => encode_solidity(&self.args)?,
+            TargetAbi::Ink => encode_ink(&self.args)?,
+        };
+        let mut input = self.selector.clone();
+        input.extend(args);
+        Ok(input)
+    }
+}
+
+/// Translates call `input` for the target ABI if the context asks for it.
+pub fn prepare_input(
+    context: &XvmContext,
+    input: Vec<u8>,
+    abi: TargetAbi,
+) -> Result<Vec<u8>, XvmError> {
+    if !context.typed_input {
+        return Ok(input);
+    }
+
+    TypedInput::decode_all_with_depth_limit(TYPED_INPUT_DECODE_DEPTH_LIMIT, &mut input.as_ref())
+        .map_err(|_| XvmError::EncodingFailure)?
+        .encode_for(abi)
+        .map_err(|_| XvmError::EncodingFailure)
+}
+
+/// Encodes arguments with the Solidity ABI, as the components of a tuple.
+pub fn encode_solidity(args: &[XvmValue]) -> Result<Vec<u8>, AbiError> {
+    for arg in args {
+        arg.check(MAX_ARRAY_DEPTH)?;
+    }
+    Ok(encode_tuple(args))
+}
+
+/// Decodes arguments of the given types encoded with the Solidity ABI.
+pub fn decode_solidity(types: &[XvmType], data: &[u8]) -> Result<Vec<XvmValue>, AbiError> {
+    check_depth(types)?;
+    decode_tuple(types, data)
+}
+
+/// Encodes arguments with SCALE, as ink! messages expect them.
+pub fn encode_ink(args: &[XvmValue]) -> Result<Vec<u8>, AbiError> {
+    let mut data = Vec::new();
+    for arg in args {
+        arg.check(MAX_ARRAY_DEPTH)?;
+        encode_scale(arg, &mut data);
+    }
+    Ok(data)
+}
+
+/// Decodes arguments of the given types encoded with SCALE.
+pub fn decode_ink(types: &[XvmType], mut data: &[u8]) -> Result<Vec<XvmValue>, AbiError> {
+    check_depth(types)?;
+    let values = types
+        .iter()
+        .map(|ty| decode_scale(ty, &mut data))
+        .collect::<Result<Vec<_>, _>>()?;
+    if !data.is_empty() {
+        return Err(AbiError::InvalidEncoding);
+    }
+    Ok(values)
+}
+
+/// Bounds the recursion of `decode_tuple` and `decode_scale`, which descend once per array.
+fn check_depth(types: &[XvmType]) -> Result<(), AbiError> {
+    if types.iter().any(|ty| ty.depth() > MAX_ARRAY_DEPTH) {
+        return Err(AbiError::DepthExceeded);
+    }
+    Ok(())
+}
+
+/// Solidity ABI word.
+type Word = [u8; 32];
+
+fn is_dynamic(ty: &XvmType) -> bool {
+    matches!(ty, XvmType::Bytes | XvmType::Array(_))
+}
+
+fn unsigned_word(value: U256) -> Word {
+    let mut word = [0u8; 32];
+    value.to_big_endian(&mut word);
+    word
+}
+
+fn signed_word(value: i128) -> Word {
+    let mut word = if value < 0 { [0xffu8; 32] } else { [0u8; 32] };
+    word[16..].copy_from_slice(&value.to_be_bytes());
+    word
+}
+
+fn encode_tuple(values: &[XvmValue]) -> Vec<u8> {
+    // every supported static type fits in a single word
+    let head_len = 32 * values.len();
+    let mut head = Vec::with_capacity(head_len);
+    let mut tail = Vec::new();
+
+    for value in values {
+        if is_dynamic(&value.ty()) {
+            head.extend(unsigned_word((head_len + tail.len()).into()));
+            tail.extend(encode_dynamic(value));
+        } else {
+            head.extend(encode_static(value));
+        }
+    }
+
+    head.extend(tail);
+    head
+}
+
+fn encode_static(value: &XvmValue) -> Word {
+    match value {
+        XvmValue::Bool(value) => unsigned_word((*value as u8).into()),
+        XvmValue::U8(value) => unsigned_word((*value).into()),
+        XvmValue::U16(value) => unsigned_word((*value).into()),
+        XvmValue::U32(value) => unsigned_word((*value).into()),
+        XvmValue::U64(value) => unsigned_word((*value).into()),
+        XvmValue::U128(value) => unsigned_word((*value).into()),
+        XvmValue::U256(value) => unsigned_word(*value),
+        XvmValue::I8(value) => signed_word((*value).into()),
+        XvmValue::I16(value) => signed_word((*value).into()),
+        XvmValue::I32(value) => signed_word((*value).into()),
+        XvmValue::I64(value) => signed_word((*value).into()),
+        XvmValue::I128(value) => signed_word(*value),
+        XvmValue::Address(address) => {
+            let mut word = [0u8; 32];
+            word[12..].copy_from_slice(address.as_bytes());
+            word
+        }
+        XvmValue::AccountId(account) => *account,
+        XvmValue::Bytes(_) | XvmValue::Array(..) => unreachable!("dynamic types aren't static"),
+    }
+}
+
+fn encode_dynamic(value: &XvmValue) -> Vec<u8> {
+    match value {
+        XvmValue::Bytes(bytes) => {
+            let mut data = unsigned_word(bytes.len().into()).to_vec();
+            data.extend(bytes);
+            data.resize(32 + (bytes.len() + 31) / 32 * 32, 0);
+            data
+        }
+        XvmValue::Array(_, values) => {
+            let mut data = unsigned_word(values.len().into()).to_vec();
+            data.extend(encode_tuple(values));
+            data
+        }
+        _ => unreachable!("static types aren't dynamic"),
+    }
+}
+
+fn word_at(data: &[u8], offset: usize) -> Result<&Word, AbiError> {
+    data.get(offset..offset.saturating_add(32))
+        .and_then(|word| word.try_into().ok())
+        .ok_or(AbiError::InvalidEncoding)
+}
+
+fn word_to_usize(word: &Word) -> Result<usize, AbiError> {
+    if word[..28].iter().any(|byte| *byte != 0) {
+        return Err(AbiError::InvalidEncoding);
+    }
+    Ok(u32::from_be_bytes([word[28], word[29], word[30], word[31]]) as usize)
+}
+
+/// Value bytes of an unsigned integer of `N` bytes, checking the padding.
+fn unsigned_bytes<const N: usize>(word: &Word) -> Result<[u8; N], AbiError> {
+    if word[..32 - N].iter().any(|byte| *byte != 0) {
+        return Err(AbiError::InvalidEncoding);
+    }
+    word[32 - N..]
+        .try_into()
+        .map_err(|_| AbiError::InvalidEncoding)
+}
+
+/// Value bytes of a signed integer of `N` bytes, checking the sign extension.
+fn signed_bytes<const N: usize>(word: &Word) -> Result<[u8; N], AbiError> {
+    let padding = if word[32 - N] & 0x80 == 0 { 0 } else { 0xff };
+    if word[..32 - N].iter().any(|byte| *byte != padding) {
+        return Err(AbiError::InvalidEncoding);
+    }
+    word[32 - N..]
+        .try_into()
+        .map_err(|_| AbiError::InvalidEncoding)
+}
+
+fn decode_tuple(types: &[XvmType], data: &[u8]) -> Result<Vec<XvmValue>, AbiError> {
+    types
+        .iter()
+        .enumerate()
+        .map(|(index, ty)| {
+            let head = word_at(data, 32 * index)?;
+            if is_dynamic(ty) {
+                let offset = word_to_usize(head)?;
+                let data = data.get(offset..).ok_or(AbiError::InvalidEncoding)?;
+                decode_dynamic(ty, data)
+            } else {
+                decode_static(ty, head)
+            }
+        })
+        .collect()
+}
+
+fn decode_static(ty: &XvmType, word: &Word) -> Result<XvmValue, AbiError> {
+    Ok(match ty {
+        XvmType::Bool => match unsigned_bytes::<1>(word)? {
+            [0] => XvmValue::Bool(false),
+            [1] => XvmValue::Bool(true),
+            _ => return Err(AbiError::InvalidEncoding),
+        },
+        XvmType::U8 => XvmValue::U8(u8::from_be_bytes(unsigned_bytes(word)?)),
+        XvmType::U16 => XvmValue::U16(u16::from_be_bytes(unsigned_bytes(word)?)),
+        XvmType::U32 => XvmValue::U32(u32::from_be_bytes(unsigned_bytes(word)?)),
+        XvmType::U64 => XvmValue::U64(u64::from_be_bytes(unsigned_bytes(word)?)),
+        XvmType::U128 => XvmValue::U128(u128::from_be_bytes(unsigned_bytes(word)?)),
+        XvmType::U256 => XvmValue::U256(U256::from_big_endian(word)),
+        XvmType::I8 => XvmValue::I8(i8::from_be_bytes(signed_bytes(word)?)),
+        XvmType::I16 => XvmValue::I16(i16::from_be_bytes(signed_bytes(word)?)),
+        XvmType::I32 => XvmValue::I32(i32::from_be_bytes(signed_bytes(word)?)),
+        XvmType::I64 => XvmValue::I64(i64::from_be_bytes(signed_bytes(word)?)),
+        XvmType::I128 => XvmValue::I128(i128::from_be_bytes(signed_bytes(word)?)),
+        XvmType::Address => XvmValue::Address(H160(unsigned_bytes(word)?)),
+        XvmType::AccountId => XvmValue::AccountId(*word),
+        XvmType::Bytes | XvmType::Array(_) => return Err(AbiError::InvalidEncoding),
+    })
+}
+
+fn decode_dynamic(ty: &XvmType, data: &[u8]) -> Result<XvmValue, AbiError> {
+    let len = word_to_usize(word_at(data, 0)?)?;
+    let data = &data[32..];
+
+    match ty {
+        XvmType::Bytes => data
+            .get(..len)
+            .map(|bytes| XvmValue::Bytes(bytes.to_vec()))
+            .ok_or(AbiError::InvalidEncoding),
+        XvmType::Array(element) => {
+            // each element takes at least one word, bounds the allocation below
+            if len.saturating_mul(32) > data.len() {
+                return Err(AbiError::InvalidEncoding);
+            }
+            let types = vec![(**element).clone(); len];
+            Ok(XvmValue::Array(
+                (**element).clone(),
+                decode_tuple(&types, data)?,
+            ))
+        }
+        _ => Err(AbiError::InvalidEncoding),
+    }
+}
+
+fn encode_scale(value: &XvmValue, data: &mut Vec<u8>) {
+    match value {
+        XvmValue::Bool(value) => value.encode_to(data),
+        XvmValue::U8(value) => value.encode_to(data),
+        XvmValue::U16(value) => value.encode_to(data),
+        XvmValue::U32(value) => value.encode_to(data),
+        XvmValue::U64(value) => value.encode_to(data),
+        XvmValue::U128(value) => value.encode_to(data),
+        XvmValue::U256(value) => value.encode_to(data),
+        XvmValue::I8(value) => value.encode_to(data),
+        XvmValue::I16(value) => value.encode_to(data),
+        XvmValue::I32(value) => value.encode_to(data),
+        XvmValue::I64(value) => value.encode_to(data),
+        XvmValue::I128(value) => value.encode_to(data),
+        XvmValue::Bytes(bytes) => bytes.encode_to(data),
+        XvmValue::Address(address) => address.encode_to(data),
+        XvmValue::AccountId(account) => account.encode_to(data),
+        XvmValue::Array(_, values) => {
+            Compact(values.len() as u32).encode_to(data);
+            for value in values {
+                encode_scale(value, data);
+            }
+        }
+    }
+}
+
+fn decode_scale(ty: &XvmType, data: &mut &[u8]) -> Result<XvmValue, AbiError> {
+    fn decode<T: Decode>(data: &mut &[u8]) -> Result<T, AbiError> {
+        T::decode(data).map_err(|_| AbiError::InvalidEncoding)
+    }
+
+    Ok(match ty {
+        XvmType::Bool => XvmValue::Bool(decode(data)?),
+        XvmType::U8 => XvmValue::U8(decode(data)?),
+        XvmType::U16 => XvmValue::U16(decode(data)?),
+        XvmType::U32 => XvmValue::U32(decode(data)?),
+        XvmType::U64 => XvmValue::U64(decode(data)?),
+        XvmType::U128 => XvmValue::U128(decode(data)?),
+        XvmType::U256 => XvmValue::U256(decode(data)?),
+        XvmType::I8 => XvmValue::I8(decode(data)?),
+        XvmType::I16 => XvmValue::I16(decode(data)?),
+        XvmType::I32 => XvmValue::I32(decode(data)?),
+        XvmType::I64 => XvmValue::I64(decode(data)?),
+        XvmType::I128 => XvmValue::I128(decode(data)?),
+        XvmType::Bytes => XvmValue::Bytes(decode(data)?),
+        XvmType::Address => XvmValue::Address(decode(data)?),
+        XvmType::AccountId => XvmValue::AccountId(decode(data)?),
+        XvmType::Array(element) => {
+            let len = decode::<Compact<u32>>(data)?.0 as usize;
+            // each element takes at least one byte, bounds the allocation below
+            if len > data.len() {
+                return Err(AbiError::InvalidEncoding);
+            }
+            let mut values = Vec::with_capacity(len);
+            for _ in 0..len {
+                values.push(decode_scale(element, data)?);
+            }
+            XvmValue::Array((**element).clone(), values)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(value: u8) -> Vec<u8> {
+        unsigned_word(value.into()).to_vec()
+    }
+
+    fn all_types_args() -> Vec<XvmValue> {
+        vec![
+            XvmValue::Bool(true),
+            XvmValue::U8(u8::MAX),
+            XvmValue::U16(517),
+            XvmValue::U32(69),
+            XvmValue::U64(u64::MAX),
+            XvmValue::U128(1 << 100),
+            XvmValue::U256(U256::MAX),
+            XvmValue::I8(i8::MIN),
+            XvmValue::I16(-517),
+            XvmValue::I32(69),
+            XvmValue::I64(-1),
+            XvmValue::I128(i128::MIN),
+            XvmValue::Bytes(b"xvm".to_vec()),
+            XvmValue::Address(H160::repeat_byte(0xaa)),
+            XvmValue::AccountId([0xbb; 32]),
+            XvmValue::Array(XvmType::U32, vec![XvmValue::U32(1), XvmValue::U32(2)]),
+            XvmValue::Array(
+                XvmType::Array(Box::new(XvmType::Bytes)),
+                vec![
+                    XvmValue::Array(XvmType::Bytes, vec![XvmValue::Bytes(vec![1; 40])]),
+                    XvmValue::Array(XvmType::Bytes, vec![]),
+                ],
+            ),
+        ]
+    }
+
+    #[test]
+    fn solidity_encoding_matches_spec() {
+        // `baz(uint32,bool)` with `(69, true)`
+        assert_eq!(
+            encode_solidity(&[XvmValue::U32(69), XvmValue::Bool(true)]).unwrap(),
+            [word(69), word(1)].concat()
+        );
+
+        // `sam(bytes,bool,uint256[])` with `("dave", true, [1, 2, 3])`
+        let mut dave = b"dave".to_vec();
+        dave.resize(32, 0);
+        let args = vec![
+            XvmValue::Bytes(b"dave".to_vec()),
+            XvmValue::Bool(true),
+            XvmValue::Array(
+                XvmType::U256,
+                vec![
+                    XvmValue::U256(U256::from(1u8)),
+                    XvmValue::U256(U256::from(2u8)),
+                    XvmValue::U256(U256::from(3u8)),
+                ],
+            ),
+        ];
+        assert_eq!(
+            encode_solidity(&args).unwrap(),
+            [
+                word(0x60),
+                word(1),
+                word(0xa0),
+                word(4),
+                dave,
+                word(3),
+                word(1),
+                word(2),
+                word(3)
+            ]
+            .concat()
+        );
+    }
+
+    #[test]
+    fn ink_encoding_is_scale() {
+        let args = vec![
+            XvmValue::Bool(true),
+            XvmValue::U32(5),
+            XvmValue::Bytes(vec![1, 2]),
+            XvmValue::AccountId([7; 32]),
+            XvmValue::Array(XvmType::I16, vec![XvmValue::I16(-1)]),
+        ];
+        assert_eq!(
+            encode_ink(&args).unwrap(),
+            (true, 5u32, vec![1u8, 2], [7u8; 32], vec![-1i16]).encode()
+        );
+    }
+
+    #[test]
+    fn round_trip_works() {
+        let args = all_types_args();
+        let types: Vec<_> = args.iter().map(XvmValue::ty).collect();
+
+        let encoded = encode_solidity(&args).unwrap();
+        assert_eq!(decode_solidity(&types, &encoded).unwrap(), args);
+
+        let encoded = encode_ink(&args).unwrap();
+        assert_eq!(decode_ink(&types, &encoded).unwrap(), args);
+    }
+
+    #[test]
+    fn invalid_data_is_rejected() {
+        let mismatched = XvmValue::Array(XvmType::U8, vec![XvmValue::Bool(true)]);
+        assert_eq!(
+            encode_solidity(&[mismatched.clone()]),
+            Err(AbiError::TypeMismatch)
+        );
+        assert_eq!(encode_ink(&[mismatched]), Err(AbiError::TypeMismatch));
+
+        // out of range and badly sign extended integers
+        let mut too_large = word(1);
+        too_large[0] = 1;
+        assert_eq!(
+            decode_solidity(&[XvmType::U8], &too_large),
+            Err(AbiError::InvalidEncoding)
+        );
+        assert_eq!(
+            decode_solidity(&[XvmType::I8], &word(0xff)),
+            Err(AbiError::InvalidEncoding)
+        );
+        assert_eq!(
+            decode_solidity(&[XvmType::Bool], &word(2)),
+            Err(AbiError::InvalidEncoding)
+        );
+        // truncated data and trailing bytes
+        assert_eq!(
+            decode_solidity(&[XvmType::U32, XvmType::U32], &word(1)),
+            Err(AbiError::InvalidEncoding)
+        );
+        assert_eq!(
+            decode_ink(&[XvmType::U8], &[1, 2]),
+            Err(AbiError::InvalidEncoding)
+        );
+        // array length exceeding the data
+        assert_eq!(
+            decode_solidity(
+                &[XvmType::Array(Box::new(XvmType::U8))],
+                &[word(0x20), word(0xff)].concat()
+            ),
+            Err(AbiError::InvalidEncoding)
+        );
+    }
+
+    #[test]
+    fn prepare_input_works() {
+        let input = TypedInput {
+            selector: vec![0xca, 0xfe, 0xca, 0xfe],
+            args: vec![XvmValue::U8(1)],
+        };
+        let mut context = XvmContext::default();
+
+        // raw input is passed through
+        assert_eq!(
+            prepare_input(&context, input.encode(), TargetAbi::Ink),
+            Ok(input.encode())
+        );
+
+        context.typed_input = true;
+        assert_eq!(
+            prepare_input(&context, input.encode(), TargetAbi::Ink),
+            Ok(vec![0xca, 0xfe, 0xca, 0xfe, 1])
+        );
+        assert_eq!(
+            prepare_input(&context, input.encode(), TargetAbi::Solidity),
+            Ok([vec![0xca, 0xfe, 0xca, 0xfe], word(1)].concat())
+        );
+        assert_eq!(
+            prepare_input(&context, vec![0xff], TargetAbi::Solidity),
+            Err(XvmError::EncodingFailure)
+        );
+    }
+
+    fn nested_array(depth: u32) -> XvmValue {
+        let mut value = XvmValue::Array(XvmType::U8, vec![XvmValue::U8(1)]);
+        for _ in 1..depth {
+            value = XvmValue::Array(value.ty(), vec![value]);
+        }
+        value
+    }
+
+    #[test]
+    fn nesting_depth_is_limited() {
+        let max = nested_array(MAX_ARRAY_DEPTH);
+        let too_deep = nested_array(MAX_ARRAY_DEPTH + 1);
+        assert_eq!(max.ty().depth(), MAX_ARRAY_DEPTH);
+
+        let encoded = encode_solidity(&[max.clone()]).unwrap();
+        assert_eq!(
+            decode_solidity(&[max.ty()], &encoded).unwrap(),
+            vec![max.clone()]
+        );
+        let encoded = encode_ink(&[max.clone()]).unwrap();
+        assert_eq!(
+            decode_ink(&[max.ty()], &encoded).unwrap(),
+            vec![max.clone()]
+        );
+
+        assert_eq!(
+            encode_solidity(&[too_deep.clone()]),
+            Err(AbiError::DepthExceeded)
+        );
+        assert_eq!(
+            encode_ink(&[too_deep.clone()]),
+            Err(AbiError::DepthExceeded)
+        );
+        assert_eq!(
+            decode_solidity(&[too_deep.ty()], &[]),
+            Err(AbiError::DepthExceeded)
+        );
+        assert_eq!(
+            decode_ink(&[too_deep.ty()], &[]),
+            Err(AbiError::DepthExceeded)
+        );
+        // element type of an empty array counts too
+        let empty = XvmValue::Array(too_deep.ty(), vec![]);
+        assert_eq!(encode_ink(&[empty]), Err(AbiError::DepthExceeded));
+
+        let context = XvmContext {
+            typed_input: true,
+            ..Default::default()
+        };
+        let input = |arg| {
+            TypedInput {
+                selector: vec![],
+                args: vec![arg],
+            }
+            .encode()
+        };
+        assert!(prepare_input(&context, input(max), TargetAbi::Ink).is_ok());
+        assert_eq!(
+            prepare_input(&context, input(too_deep), TargetAbi::Ink),
+            Err(XvmError::EncodingFailure)
+        );
+
+        // deeply nested types are rejected while decoding, without exhausting the stack
+        let array_index = XvmType::Array(Box::new(XvmType::Bool)).encode()[0];
+        let mut input = vec![0, 4];
+        input.push(XvmValue::Array(XvmType::Bool, vec![]).encode()[0]);
+        input.extend(vec![array_index; 100_000]);
+        input.extend([0, 0]);
+        assert_eq!(
+            prepare_input(&context, input, TargetAbi::Ink),
+            Err(XvmError::EncodingFailure)
+        );
+    }
+}
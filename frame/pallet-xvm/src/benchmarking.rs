@@ -0,0 +1,248 @@
+// This file is part of Astar.
+
+// Copyright (C) 2019-2023 Stake Technologies Pte.Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// Astar is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Astar is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Astar. If not, see <http://www.gnu.org/licenses/>.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+use crate::Pallet as Xvm;
+
+use frame_benchmarking::{
+    account, benchmarks, impl_benchmark_test_suite, whitelisted_caller, BenchmarkError,
+};
+use frame_support::{
+    assert_ok,
+    traits::{ConstU8, EnsureOrigin, Get},
+};
+use frame_system::RawOrigin;
+use sp_runtime::traits::{One, Saturating};
+use sp_std::vec;
+
+/// VM which no backend answers to, for benchmarks independent of the VM.
+const UNKNOWN_VM_ID: VmId = VmId::MAX;
+
+fn unknown_vm_context() -> XvmContext {
+    XvmContext {
+        id: UNKNOWN_VM_ID,
+        ..Default::default()
+    }
+}
+
+/// VM which synchronous calls are dispatched to, the unknown one if none is configured.
+fn sync_vm_id<T: Config>() -> VmId {
+    T::BenchmarkVmId::get().unwrap_or(UNKNOWN_VM_ID)
+}
+
+/// Context of a synchronous call, with enough weight for the VM to reach its target.
+fn sync_vm_context<T: Config>() -> XvmContext {
+    XvmContext {
+        id: sync_vm_id::<T>(),
+        max_weight: Weight::from_parts(1_000_000_000, 64 * 1024),
+        ..Default::default()
+    }
+}
+
+fn max_payload<T: Config>() -> Vec<u8> {
+    vec![0u8; T::MaxMessageSize::get() as usize]
+}
+
+/// Fills `inbox` with `n` messages of maximum size, returns their identifiers.
+fn fill_inbox<T: Config>(inbox: &T::AccountId, n: u32) -> Vec<MessageId> {
+    let sender: T::AccountId = account("sender", 0, 0);
+    (0..n)
+        .map(|_| {
+            Xvm::<T>::enqueue_message(sender.clone(), inbox.clone(), max_payload::<T>(), None)
+                .expect("inbox has room")
+        })
+        .collect()
+}
+
+/// Schedules `n` deliveries for the next block, each to a distinct recipient.
+fn fill_delivery_queue<T: Config>(n: u32) {
+    let sender: T::AccountId = account("sender", 0, 0);
+    for i in 0..n {
+        let delivery = XvmDelivery {
+            vm_id: UNKNOWN_VM_ID,
+            to: vec![0u8; 32],
+        };
+        Xvm::<T>::enqueue_message(
+            sender.clone(),
+            account("recipient", i, 0),
+            max_payload::<T>(),
+            Some(delivery),
+        )
+        .expect("delivery queue has room");
+    }
+}
+
+benchmarks! {
+
+    xvm_call {
+        let caller: T::AccountId = whitelisted_caller();
+        // An allowlisted target is the most expensive to check.
+        let target = vec![0u8; 32];
+        TargetFilters::<T>::insert(sync_vm_id::<T>(), TargetFilter::Allowlist);
+        ListedTargets::<T>::insert(sync_vm_id::<T>(), &target, ());
+    }: _(RawOrigin::Signed(caller), sync_vm_context::<T>(), target, vec![0u8; 32])
+
+    xvm_send {
+        let caller: T::AccountId = whitelisted_caller();
+    }: _(RawOrigin::Signed(caller), unknown_vm_context(), vec![0u8; 32], max_payload::<T>())
+
+    xvm_query {
+        let caller: T::AccountId = whitelisted_caller();
+    }: _(RawOrigin::Signed(caller), unknown_vm_context())
+
+    xvm_ack {
+        let n in 1 .. T::MaxInboxSize::get();
+
+        let caller: T::AccountId = whitelisted_caller();
+        let ids = fill_inbox::<T>(&caller, n);
+
+    }: _(RawOrigin::Signed(caller.clone()), ids)
+    verify {
+        assert!(Inboxes::<T>::get(&caller).is_empty());
+    }
+
+    enqueue_message {
+        let sender: T::AccountId = account("sender", 0, 0);
+        let inbox: T::AccountId = whitelisted_caller();
+        fill_inbox::<T>(&inbox, T::MaxInboxSize::get().saturating_sub(1));
+        fill_delivery_queue::<T>(T::MaxDeliveriesPerBlock::get().saturating_sub(1));
+        let delivery = XvmDelivery {
+            vm_id: UNKNOWN_VM_ID,
+            to: vec![0u8; 32],
+        };
+
+    }: {
+        Xvm::<T>::enqueue_message(sender, inbox.clone(), max_payload::<T>(), Some(delivery))
+            .expect("inbox has room");
+    }
+    verify {
+        assert_eq!(Inboxes::<T>::get(&inbox).len() as u32, T::MaxInboxSize::get());
+    }
+
+    query_inbox {
+        let inbox: T::AccountId = whitelisted_caller();
+        fill_inbox::<T>(&inbox, T::MaxInboxSize::get());
+
+    }: {
+        crate::inbox::Inbox::<ConstU8<0>, T>::xvm_query(Default::default(), inbox)
+            .expect("query never fails");
+    }
+
+    deliver_messages {
+        let n in 0 .. T::MaxDeliveriesPerBlock::get();
+
+        fill_delivery_queue::<T>(n);
+        let next_block = frame_system::Pallet::<T>::block_number().saturating_add(One::one());
+
+    }: {
+        Xvm::<T>::deliver_messages(next_block);
+    }
+    verify {
+        assert!(DeliveryQueue::<T>::get(next_block).is_empty());
+    }
+
+    set_reentrancy_guard {
+        let origin = T::ManagerOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+        let target = vec![0u8; 32];
+    }: {
+        assert_ok!(Xvm::<T>::set_reentrancy_guard(origin, UNKNOWN_VM_ID, target.clone(), true));
+    }
+    verify {
+        assert!(ReentrancyGuards::<T>::contains_key(UNKNOWN_VM_ID, &target));
+    }
+
+    xvm_instantiate {
+        let caller: T::AccountId = whitelisted_caller();
+        // Instantiations are only allowed without a filter or with a denylist.
+        TargetFilters::<T>::insert(sync_vm_id::<T>(), TargetFilter::Denylist);
+    }: _(RawOrigin::Signed(caller), sync_vm_context::<T>(), vec![0u8; 32], vec![0u8; 32], vec![0u8; 32])
+
+    set_vm_enabled {
+        let origin = T::ManagerOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+    }: {
+        assert_ok!(Xvm::<T>::set_vm_enabled(origin, UNKNOWN_VM_ID, false));
+    }
+    verify {
+        assert!(DisabledVms::<T>::contains_key(UNKNOWN_VM_ID));
+    }
+
+    set_target_filter {
+        let origin = T::ManagerOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+    }: {
+        assert_ok!(Xvm::<T>::set_target_filter(origin, UNKNOWN_VM_ID, Some(TargetFilter::Allowlist)));
+    }
+    verify {
+        assert_eq!(TargetFilters::<T>::get(UNKNOWN_VM_ID), Some(TargetFilter::Allowlist));
+    }
+
+    set_target_listed {
+        let origin = T::ManagerOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+        let target = vec![0u8; 32];
+    }: {
+        assert_ok!(Xvm::<T>::set_target_listed(origin, UNKNOWN_VM_ID, target.clone(), true));
+    }
+    verify {
+        assert!(ListedTargets::<T>::contains_key(UNKNOWN_VM_ID, &target));
+    }
+
+    contract_logs {
+        let e in 0 .. T::MaxScannedEvents::get();
+
+        // No events are deposited in the genesis block.
+        frame_system::Pallet::<T>::set_block_number(One::one());
+        let caller: T::AccountId = whitelisted_caller();
+        let log = XvmLog {
+            address: vec![0u8; 32],
+            topics: vec![Default::default(); 4],
+            data: max_payload::<T>(),
+        };
+        for _ in 0..e {
+            Xvm::<T>::deposit_event(Event::<T>::XvmCallExecuted {
+                caller: caller.clone(),
+                callee: vec![0u8; 32],
+                vm_id: UNKNOWN_VM_ID,
+                success: true,
+                consumed_weight: Weight::zero(),
+                logs: vec![log.clone()],
+            });
+        }
+
+    }: {
+        frame_system::Pallet::<T>::event_count();
+        frame_system::Pallet::<T>::read_events_no_consensus().for_each(drop);
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mock;
+    use sp_io::TestExternalities;
+
+    pub fn new_test_ext() -> TestExternalities {
+        mock::ExternalityBuilder::build()
+    }
+}
+
+impl_benchmark_test_suite!(
+    Xvm,
+    crate::benchmarking::tests::new_test_ext(),
+    crate::mock::TestRuntime
+);
@@ -19,20 +19,39 @@
 //! EVM support for XVM pallet.
 
 use crate::*;
-use pallet_evm::{GasWeightMapping, Runner};
-use sp_core::{H160, U256};
+use ::evm::{ExitError, ExitFatal, ExitReason};
+use frame_support::{
+    storage::{with_transaction, TransactionOutcome},
+    traits::{Currency, ExistenceRequirement},
+};
+use pallet_evm::{runner::RunnerError, ExecutionInfo, GasWeightMapping, Runner};
+use pallet_unified_accounts::UnifiedAddressMapper;
+use sp_core::{H160, H256, U256};
 use sp_runtime::traits::{Get, UniqueSaturatedInto};
 
+type BalanceOf<T> = <<T as pallet_evm::Config>::Currency as Currency<
+    <T as frame_system::Config>::AccountId,
+>>::Balance;
+
 /// EVM adapter for XVM calls.
 ///
 /// This adapter supports generic XVM calls and encode it into EVM native calls
 /// using Solidity ABI codec (https://docs.soliditylang.org/en/v0.8.16/abi-spec.html).
-pub struct EVM<I, T>(sp_std::marker::PhantomData<(I, T)>);
+///
+/// The EVM caller address is the unified address of the XVM caller, as mapped by `M`. Value
+/// transferred with the call is first moved from the XVM caller to the account backing that
+/// address.
+///
+/// Query calls can't transfer value, their state changes are discarded and emitting logs
+/// fails them.
+pub struct EVM<I, T, M>(sp_std::marker::PhantomData<(I, T, M)>);
 
-impl<I, T> SyncVM<T::AccountId> for EVM<I, T>
+impl<I, T, M> SyncVM<T::AccountId> for EVM<I, T, M>
 where
     I: Get<VmId>,
     T: pallet_evm::Config + frame_system::Config,
+    M: UnifiedAddressMapper<T::AccountId>,
+    BalanceOf<T>: TryFrom<U256> + Into<U256>,
 {
     fn id() -> VmId {
         I::get()
@@ -41,59 +60,287 @@ where
     fn xvm_call(context: XvmContext, from: T::AccountId, to: Vec<u8>, input: Vec<u8>) -> XvmResult {
         log::trace!(
             target: "xvm::EVM::xvm_call",
-            "Start EVM XVM: {:?}, {:?}, {:?}",
-            from, to, input,
+            "Start EVM XVM: {:?}, {:?}, {:?}, {:?}",
+            from, to, input, context.value,
+        );
+        let evm_to = Decode::decode(&mut to.as_ref()).map_err(|_| XvmCallError {
+            error: XvmError::EncodingFailure,
+            consumed_weight: Weight::zero(),
+        })?;
+        let input =
+            abi::prepare_input(&context, input, abi::TargetAbi::Solidity).map_err(|error| {
+                XvmCallError {
+                    error,
+                    consumed_weight: Weight::zero(),
+                }
+            })?;
+        // Static calls can't transfer value.
+        if context.query && context.value > 0 {
+            return Err(XvmCallError {
+                error: XvmError::ExecutionError(b"ValueInStaticCall".to_vec()),
+                consumed_weight: Weight::zero(),
+            });
+        }
+
+        let (output, consumed_weight, logs) = Self::execute(
+            &context,
+            from,
+            |source, value, gas_limit| {
+                let mut info = T::Runner::call(
+                    source,
+                    evm_to,
+                    input,
+                    value,
+                    gas_limit,
+                    // Tells the EVM executor that no fees should be charged for this execution.
+                    Some(U256::zero()),
+                    None,
+                    None,
+                    Vec::new(),
+                    IS_TRANSACTIONAL,
+                    VALIDATE,
+                    T::config(),
+                )?;
+                // The runner has no read-only mode, queries emitting logs are failed instead.
+                if context.query && info.exit_reason.is_succeed() && !info.logs.is_empty() {
+                    info.exit_reason =
+                        ExitReason::Error(ExitError::Other("StateChangeInStaticCall".into()));
+                }
+                Ok(info)
+            },
+            |output| output,
+        )?;
+
+        Ok(XvmCallOk {
+            output,
+            consumed_weight,
+            logs,
+        })
+    }
+
+    fn xvm_instantiate(
+        context: XvmContext,
+        from: T::AccountId,
+        code: Vec<u8>,
+        input: Vec<u8>,
+        salt: Vec<u8>,
+    ) -> XvmResult {
+        log::trace!(
+            target: "xvm::EVM::xvm_instantiate",
+            "Start EVM XVM instantiate: {:?}, {:?}, {:?}, {:?}, {:?}",
+            from, code, input, salt, context.value,
         );
-        let value = U256::zero();
+        // Empty salt deploys with `CREATE`, a 32 bytes one with `CREATE2`.
+        let salt = match salt.len() {
+            0 => None,
+            32 => Some(H256::from_slice(&salt)),
+            _ => {
+                return Err(XvmCallError {
+                    error: XvmError::EncodingFailure,
+                    consumed_weight: Weight::zero(),
+                })
+            }
+        };
+        // Constructor arguments are appended to the init code.
+        let input =
+            abi::prepare_input(&context, input, abi::TargetAbi::Solidity).map_err(|error| {
+                XvmCallError {
+                    error,
+                    consumed_weight: Weight::zero(),
+                }
+            })?;
+        let mut init = code;
+        init.extend(input);
+
+        let (address, consumed_weight, logs) = Self::execute(
+            &context,
+            from,
+            |source, value, gas_limit| match salt {
+                Some(salt) => T::Runner::create2(
+                    source,
+                    init,
+                    salt,
+                    value,
+                    gas_limit,
+                    Some(U256::zero()),
+                    None,
+                    None,
+                    Vec::new(),
+                    IS_TRANSACTIONAL,
+                    VALIDATE,
+                    T::config(),
+                ),
+                None => T::Runner::create(
+                    source,
+                    init,
+                    value,
+                    gas_limit,
+                    Some(U256::zero()),
+                    None,
+                    None,
+                    Vec::new(),
+                    IS_TRANSACTIONAL,
+                    VALIDATE,
+                    T::config(),
+                ),
+            },
+            // The runner doesn't return the revert data of a constructor.
+            |_| Vec::new(),
+        )?;
+
+        Ok(XvmCallOk {
+            output: address.encode(),
+            consumed_weight,
+            logs,
+        })
+    }
+}
+
+const IS_TRANSACTIONAL: bool = true;
+/// Since this is in the context of XVM, no standard validation is required.
+const VALIDATE: bool = false;
 
-        // Tells the EVM executor that no fees should be charged for this execution.
-        let max_fee_per_gas = U256::zero();
+impl<I, T, M> EVM<I, T, M>
+where
+    T: pallet_evm::Config + frame_system::Config,
+    M: UnifiedAddressMapper<T::AccountId>,
+    BalanceOf<T>: TryFrom<U256> + Into<U256>,
+{
+    /// Funds the EVM caller with the context value and runs `run` with the caller address,
+    /// value and gas limit.
+    ///
+    /// Returns the execution value, the consumed weight and the emitted logs. Failed
+    /// executions are mapped onto XVM errors, with `revert_data` extracting the data
+    /// of a reverted execution.
+    fn execute<R>(
+        context: &XvmContext,
+        from: T::AccountId,
+        run: impl FnOnce(
+            H160,
+            U256,
+            u64,
+        )
+            -> Result<ExecutionInfo<R>, RunnerError<<T::Runner as Runner<T>>::Error>>,
+        revert_data: impl FnOnce(R) -> Vec<u8>,
+    ) -> Result<(R, Weight, Vec<XvmLog>), XvmCallError> {
+        let value = U256::from(context.value);
         let gas_limit = T::GasWeightMapping::weight_to_gas(context.max_weight);
         log::trace!(
-            target: "xvm::EVM::xvm_call",
-            "EVM xvm call gas limit: {:?} or as weight: {:?}", gas_limit, context.max_weight);
-        let evm_to = Decode::decode(&mut to.as_ref()).map_err(|_| XvmCallError {
+            target: "xvm::EVM::execute",
+            "EVM xvm gas limit: {:?} or as weight: {:?}", gas_limit, context.max_weight);
+        let evm_from = M::to_h160_or_default(&from);
+        let transfer_value: BalanceOf<T> = context.value.try_into().map_err(|_| XvmCallError {
             error: XvmError::EncodingFailure,
-            consumed_weight: PLACEHOLDER_WEIGHT,
+            consumed_weight: Weight::zero(),
         })?;
 
-        let is_transactional = true;
-        // Since this is in the context of XVM, no standard validation is required.
-        let validate = false;
-        let info = T::Runner::call(
-            H160::from_slice(&from.encode()[0..20]),
-            evm_to,
-            input,
-            value,
-            gas_limit,
-            Some(max_fee_per_gas),
-            None,
-            None,
-            Vec::new(),
-            is_transactional,
-            validate,
-            T::config(),
-        )
-        .map_err(|e| {
-            let consumed_weight = e.weight.ref_time();
-            XvmCallError {
-                error: XvmError::ExecutionError(Into::<&str>::into(e.error.into()).into()),
-                consumed_weight,
+        // Funding the EVM caller reads and writes both accounts.
+        let transfer_weight = if context.value > 0 {
+            T::DbWeight::get().reads_writes(2, 2)
+        } else {
+            Weight::zero()
+        };
+
+        // Value funding of the EVM caller is reverted together with a failed execution.
+        let result = with_transaction(|| {
+            if context.value > 0 {
+                let evm_from_account = M::to_account_id_or_default(&evm_from);
+                if let Err(e) = T::Currency::transfer(
+                    &from,
+                    &evm_from_account,
+                    transfer_value,
+                    ExistenceRequirement::AllowDeath,
+                ) {
+                    return TransactionOutcome::Rollback(Ok(Err(XvmCallError {
+                        error: XvmError::ExecutionError(Into::<&str>::into(e).into()),
+                        consumed_weight: transfer_weight,
+                    })));
+                }
             }
+
+            match run(evm_from, value, gas_limit) {
+                Ok(info) if info.exit_reason.is_succeed() => {
+                    TransactionOutcome::Commit(Ok(Ok(info)))
+                }
+                Ok(info) => TransactionOutcome::Rollback(Ok(Ok(info))),
+                Err(e) => {
+                    let consumed_weight = e.weight.saturating_add(transfer_weight);
+                    TransactionOutcome::Rollback(Ok(Err(XvmCallError {
+                        error: XvmError::ExecutionError(Into::<&str>::into(e.error.into()).into()),
+                        consumed_weight,
+                    })))
+                }
+            }
+        })
+        .map_err(|e| XvmCallError {
+            error: XvmError::ExecutionError(Into::<&str>::into(e).into()),
+            consumed_weight: Weight::zero(),
         })?;
+        let info = result?;
 
         log::trace!(
-            target: "xvm::EVM::xvm_call",
-            "EVM XVM call result: exit_reason: {:?}, used_gas: {:?}", info.exit_reason, info.used_gas,
+            target: "xvm::EVM::execute",
+            "EVM XVM result: exit_reason: {:?}, used_gas: {:?}", info.exit_reason, info.used_gas,
         );
 
-        Ok(XvmCallOk {
-            output: info.value,
-            consumed_weight: T::GasWeightMapping::gas_to_weight(
-                info.used_gas.unique_saturated_into(),
-                false,
-            )
-            .ref_time(),
+        let consumed_weight =
+            T::GasWeightMapping::gas_to_weight(info.used_gas.unique_saturated_into(), true)
+                .saturating_add(transfer_weight);
+        let error = match info.exit_reason {
+            ExitReason::Succeed(_) => {
+                let logs = info
+                    .logs
+                    .into_iter()
+                    .map(|log| XvmLog {
+                        address: log.address.as_bytes().to_vec(),
+                        topics: log.topics,
+                        data: log.data,
+                    })
+                    .collect();
+                return Ok((info.value, consumed_weight, logs));
+            }
+            ExitReason::Revert(_) => XvmError::Reverted(revert_data(info.value)),
+            ExitReason::Error(ExitError::OutOfGas) => XvmError::OutOfGas,
+            ExitReason::Error(error) => XvmError::ExecutionError(exit_error_data(error)),
+            ExitReason::Fatal(ExitFatal::CallErrorAsFatal(error)) => {
+                XvmError::ExecutionError(exit_error_data(error))
+            }
+            ExitReason::Fatal(ExitFatal::Other(reason)) => {
+                XvmError::ExecutionError(reason.as_bytes().to_vec())
+            }
+            ExitReason::Fatal(ExitFatal::NotSupported) => {
+                XvmError::ExecutionError(b"NotSupported".to_vec())
+            }
+            ExitReason::Fatal(ExitFatal::UnhandledInterrupt) => {
+                XvmError::ExecutionError(b"UnhandledInterrupt".to_vec())
+            }
+        };
+
+        Err(XvmCallError {
+            error,
+            consumed_weight,
         })
     }
 }
+
+/// Describes an EVM exit error as bytes.
+fn exit_error_data(error: ExitError) -> Vec<u8> {
+    let reason: &str = match error {
+        ExitError::StackUnderflow => "StackUnderflow",
+        ExitError::StackOverflow => "StackOverflow",
+        ExitError::InvalidJump => "InvalidJump",
+        ExitError::InvalidRange => "InvalidRange",
+        ExitError::DesignatedInvalid => "DesignatedInvalid",
+        ExitError::CallTooDeep => "CallTooDeep",
+        ExitError::CreateCollision => "CreateCollision",
+        ExitError::CreateContractLimit => "CreateContractLimit",
+        ExitError::InvalidCode(_) => "InvalidCode",
+        ExitError::OutOfOffset => "OutOfOffset",
+        ExitError::OutOfGas => "OutOfGas",
+        ExitError::OutOfFund => "OutOfFund",
+        ExitError::PCUnderflow => "PCUnderflow",
+        ExitError::CreateEmpty => "CreateEmpty",
+        ExitError::Other(reason) => return reason.as_bytes().to_vec(),
+    };
+    reason.as_bytes().to_vec()
+}
@@ -0,0 +1,101 @@
+// This file is part of Astar.
+
+// Copyright (C) 2019-2023 Stake Technologies Pte.Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// Astar is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Astar is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Astar. If not, see <http://www.gnu.org/licenses/>.
+
+//! Asynchronous XVM messaging through on-chain inboxes.
+//!
+//! Sent messages are stored in the inbox of the recipient account, where they stay until the
+//! recipient acknowledges them. If the sender asks for it, the message is also delivered on the
+//! next block by calling a contract of a synchronous VM with the message payload as input.
+//! Successfully delivered messages are removed from the inbox. The weight of a delivery,
+//! `Config::DeliveryWeight`, is charged to the sender together with the send itself.
+
+use crate::*;
+use frame_support::{traits::Get, weights::Weight};
+use parity_scale_codec::DecodeAll;
+
+/// Inbox adapter for asynchronous XVM calls.
+///
+/// `xvm_send` expects `to` to be the SCALE encoded recipient account. The context `env`
+/// can carry a SCALE encoded [`XvmDelivery`] to deliver the message into a contract.
+pub struct Inbox<I, T>(sp_std::marker::PhantomData<(I, T)>);
+
+impl<I, T> AsyncVM<T::AccountId> for Inbox<I, T>
+where
+    I: Get<VmId>,
+    T: Config,
+{
+    fn id() -> VmId {
+        I::get()
+    }
+
+    fn xvm_send(
+        context: XvmContext,
+        from: T::AccountId,
+        to: Vec<u8>,
+        message: Vec<u8>,
+    ) -> XvmResult {
+        log::trace!(
+            target: "xvm::Inbox::xvm_send",
+            "Send XVM message: {:?}, {:?}, {:?}",
+            from, to, message,
+        );
+        let error = |error, consumed_weight| XvmCallError {
+            error,
+            consumed_weight,
+        };
+
+        let to = T::AccountId::decode_all(&mut to.as_ref())
+            .map_err(|_| error(XvmError::EncodingFailure, Weight::zero()))?;
+        let delivery = context
+            .env
+            .map(|env| XvmDelivery::decode_all(&mut env.as_ref()))
+            .transpose()
+            .map_err(|_| error(XvmError::ContextConversionFailed, Weight::zero()))?;
+
+        // Delivery runs in `on_initialize` of the next block, the sender pays for it upfront.
+        let consumed_weight = match delivery {
+            Some(_) => T::WeightInfo::enqueue_message().saturating_add(T::DeliveryWeight::get()),
+            None => T::WeightInfo::enqueue_message(),
+        };
+        if consumed_weight.any_gt(context.max_weight) {
+            return Err(error(XvmError::OutOfGas, Weight::zero()));
+        }
+        let id = Pallet::<T>::enqueue_message(from, to, message, delivery)
+            .map_err(|e| error(e, consumed_weight))?;
+
+        Ok(XvmCallOk {
+            output: id.encode(),
+            consumed_weight,
+            logs: Vec::new(),
+        })
+    }
+
+    fn xvm_query(_context: XvmContext, inbox: T::AccountId) -> XvmResult {
+        let messages = Inboxes::<T>::get(&inbox);
+        log::trace!(
+            target: "xvm::Inbox::xvm_query",
+            "Query XVM inbox {:?}: {:?} messages", inbox, messages.len(),
+        );
+
+        Ok(XvmCallOk {
+            output: messages.into_inner().encode(),
+            consumed_weight: T::WeightInfo::query_inbox(),
+            logs: Vec::new(),
+        })
+    }
+}
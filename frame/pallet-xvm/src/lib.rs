@@ -32,7 +32,8 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use frame_support::weights::Weight;
-use parity_scale_codec::{Decode, Encode};
+use parity_scale_codec::{Decode, DecodeAll, Encode};
+use sp_core::H256;
 use sp_runtime::{traits::Member, RuntimeDebug};
 use sp_std::prelude::*;
 
@@ -47,21 +48,91 @@ pub mod evm;
 #[cfg(feature = "wasm")]
 pub mod wasm;
 
+/// Asynchronous messaging adapter.
+pub mod inbox;
+
+/// Call arguments translation between VM ABIs.
+pub mod abi;
+
+#[cfg(any(test, feature = "runtime-benchmarks"))]
+mod benchmarking;
+
+pub mod weights;
+pub use weights::WeightInfo;
+
+#[cfg(test)]
+mod mock;
+#[cfg(all(test, feature = "evm"))]
+mod mock_evm;
+#[cfg(test)]
+mod tests;
+
 /// Unique VM identifier.
 pub type VmId = u8;
 
-// TODO: remove later after solution is properly benchmarked
-// Just a arbitrary weight constant to avoid having ZERO weight in some parts of execution
-pub const PLACEHOLDER_WEIGHT: u64 = 1_000_000;
+/// Identifier of the Frontier EVM.
+pub const FRONTIER_VM_ID: VmId = 0x0F;
+
+/// Unique asynchronous message identifier.
+pub type MessageId = u64;
 
 #[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, scale_info::TypeInfo)]
 pub enum XvmError {
+    /// Target VM isn't known.
     VmNotRecognized,
+    /// Call destination or arguments couldn't be decoded.
     EncodingFailure,
+    /// Caller couldn't be represented in the target VM.
     ContextConversionFailed,
+    /// Call ran out of gas (weight).
     OutOfGas,
+    /// VM specific failure, described by the VM exit reason.
     ExecutionError(Vec<u8>),
-    // extend this list as part of improved error handling
+    /// Recipient inbox can't take more messages.
+    InboxFull,
+    /// Message exceeds the maximum message size.
+    MessageTooLarge,
+    /// No more deliveries can be scheduled for the next block.
+    DeliveryQueueFull,
+    /// Callee reverted, carrying its revert data. EVM constructors revert without data.
+    Reverted(Vec<u8>),
+    /// Call destination doesn't exist or isn't a contract.
+    InvalidTarget,
+    /// Too many nested XVM calls.
+    CallDepthExceeded,
+    /// Call re-enters a target protected against reentrancy.
+    ReentrantCall,
+    /// Target VM is disabled, or the call target isn't allowed.
+    CallBlocked,
+}
+
+impl XvmError {
+    /// Stable error code, shared by the chain extension and the precompile.
+    pub fn code(&self) -> u8 {
+        match self {
+            XvmError::VmNotRecognized => 1,
+            XvmError::EncodingFailure => 2,
+            XvmError::ContextConversionFailed => 3,
+            XvmError::OutOfGas => 4,
+            XvmError::ExecutionError(_) => 5,
+            XvmError::Reverted(_) => 6,
+            XvmError::InvalidTarget => 7,
+            XvmError::InboxFull => 8,
+            XvmError::MessageTooLarge => 9,
+            XvmError::DeliveryQueueFull => 10,
+            XvmError::CallDepthExceeded => 11,
+            XvmError::ReentrantCall => 12,
+            XvmError::CallBlocked => 13,
+        }
+    }
+
+    /// Data attached to the error, i.e. revert data or VM exit reason.
+    pub fn data(&self) -> &[u8] {
+        match self {
+            XvmError::ExecutionError(data) | XvmError::Reverted(data) => data,
+            _ => &[],
+        }
+    }
 }
 
 // TODO: Currently our precompile/chain-extension calls rely on direct `Call` usage of XVM pallet.
@@ -76,24 +147,40 @@ pub enum XvmError {
 pub struct XvmCallOk {
     /// Output of XVM call. E.g. if call was a query, this will contain query response.
     output: Vec<u8>,
-    /// Total consumed weight. This is in context of Substrate (1 unit of weight ~ 1 ps of execution time)
-    consumed_weight: u64,
+    /// Total consumed weight, both execution time and proof size.
+    consumed_weight: Weight,
+    /// Logs emitted by the callee.
+    logs: Vec<XvmLog>,
 }
 
 impl XvmCallOk {
     pub fn output(&self) -> &[u8] {
         &self.output
     }
+
+    pub fn logs(&self) -> &[XvmLog] {
+        &self.logs
+    }
+}
+
+/// Log emitted by a contract during an XVM call.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, scale_info::TypeInfo)]
+pub struct XvmLog {
+    /// Address of the emitting contract, encoded for its VM.
+    pub address: Vec<u8>,
+    /// Indexed topics.
+    pub topics: Vec<H256>,
+    /// Log data.
+    pub data: Vec<u8>,
 }
 
 /// Denotes an successful XVM call execution
 #[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, scale_info::TypeInfo)]
 pub struct XvmCallError {
     /// Result of XVM call
-    // TODO: use XvmError enum from pallet? Perhaps that's a better approach. Or at least provide mapping?
     error: XvmError,
-    /// Total consumed weight. This is in context of Substrate (1 unit of weight ~ 1 ps of execution time)
-    consumed_weight: u64,
+    /// Total consumed weight, both execution time and proof size.
+    consumed_weight: Weight,
 }
 
 impl XvmCallError {
@@ -105,7 +192,8 @@ impl XvmCallError {
 /// Result for executing X-VM calls
 pub type XvmResult = Result<XvmCallOk, XvmCallError>;
 
-pub fn consumed_weight(result: &XvmResult) -> u64 {
+/// Weight consumed by the VM, excluding the XVM dispatch overhead.
+pub fn consumed_weight(result: &XvmResult) -> Weight {
     match result {
         Ok(res) => res.consumed_weight,
         Err(err) => err.consumed_weight,
@@ -121,6 +209,76 @@ pub struct XvmContext {
     pub max_weight: Weight,
     /// Encoded VM execution environment.
     pub env: Option<Vec<u8>>,
+    /// Native balance transferred from the caller to the callee along with the call.
+    pub value: u128,
+    /// Run the call read-only, discarding all of its state changes.
+    pub query: bool,
+    /// Input is a SCALE encoded [`abi::TypedInput`], to be encoded with the target VM ABI.
+    pub typed_input: bool,
+}
+
+impl XvmContext {
+    /// Decodes a context of either the current or the [`XvmContextV1`] layout.
+    ///
+    /// Both layouts are decoded in full, so they can't be mistaken for each other.
+    pub fn decode_versioned(data: &[u8]) -> Result<Self, parity_scale_codec::Error> {
+        Self::decode_all(&mut &data[..])
+            .or_else(|_| XvmContextV1::decode_all(&mut &data[..]).map(Into::into))
+    }
+}
+
+/// Layout of the [`XvmContext`] before value, query and typed input were added, still encoded
+/// by deployed contracts.
+#[derive(Default, PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, scale_info::TypeInfo)]
+pub struct XvmContextV1 {
+    /// Identifier (should be unique for each VM in tuple).
+    pub id: VmId,
+    /// Max allowed weight for the call
+    pub max_weight: Weight,
+    /// Encoded VM execution environment.
+    pub env: Option<Vec<u8>>,
+}
+
+impl From<XvmContextV1> for XvmContext {
+    fn from(context: XvmContextV1) -> Self {
+        Self {
+            id: context.id,
+            max_weight: context.max_weight,
+            env: context.env,
+            ..Default::default()
+        }
+    }
+}
+
+/// Message stored in an XVM inbox.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, scale_info::TypeInfo)]
+pub struct XvmMessage<AccountId, BlockNumber> {
+    /// Message identifier.
+    pub id: MessageId,
+    /// Message sender.
+    pub from: AccountId,
+    /// Block the message was sent in.
+    pub sent_at: BlockNumber,
+    /// Message content.
+    pub payload: Vec<u8>,
+}
+
+/// Delivery of an asynchronous message into a synchronous VM contract.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, scale_info::TypeInfo)]
+pub struct XvmDelivery {
+    /// Synchronous VM of the contract.
+    pub vm_id: VmId,
+    /// Encoded contract address.
+    pub to: Vec<u8>,
+}
+
+/// How the listed targets of a VM are treated.
+#[derive(PartialEq, Eq, Clone, Copy, Encode, Decode, RuntimeDebug, scale_info::TypeInfo)]
+pub enum TargetFilter {
+    /// Only listed targets can be called, contracts can't be instantiated.
+    Allowlist,
+    /// Listed targets can't be called.
+    Denylist,
 }
 
 /// The engine that support synchronous smart contract execution.
@@ -133,6 +291,17 @@ pub trait SyncVM<AccountId> {
     ///
     ///
     fn xvm_call(context: XvmContext, from: AccountId, to: Vec<u8>, input: Vec<u8>) -> XvmResult;
+
+    /// Instantiate a VM contract from `code` and return its encoded address as output.
+    ///
+    /// `input` holds the constructor arguments, `salt` makes the address deterministic.
+    fn xvm_instantiate(
+        context: XvmContext,
+        from: AccountId,
+        code: Vec<u8>,
+        input: Vec<u8>,
+        salt: Vec<u8>,
+    ) -> XvmResult;
 }
 
 #[impl_trait_for_tuples::impl_for_tuples(30)]
@@ -158,7 +327,34 @@ impl<AccountId: Member> SyncVM<AccountId> for Tuple {
         );
         Err(XvmCallError {
             error: XvmError::VmNotRecognized,
-            consumed_weight: PLACEHOLDER_WEIGHT,
+            consumed_weight: Weight::zero(),
+        })
+    }
+
+    fn xvm_instantiate(
+        context: XvmContext,
+        from: AccountId,
+        code: Vec<u8>,
+        input: Vec<u8>,
+        salt: Vec<u8>,
+    ) -> XvmResult {
+        for_tuples!( #(
+            if Tuple::id() == context.id {
+                log::trace!(
+                    target: "xvm::SyncVm::xvm_instantiate",
+                    "VM found, run XVM instantiate: {:?}, {:?}, {:?}, {:?}, {:?}",
+                    context, from, code, input, salt,
+                );
+                return Tuple::xvm_instantiate(context, from, code, input, salt)
+            }
+        )* );
+        log::trace!(
+            target: "xvm::SyncVm::xvm_instantiate",
+            "VM with ID {:?} not found", context.id
+        );
+        Err(XvmCallError {
+            error: XvmError::VmNotRecognized,
+            consumed_weight: Weight::zero(),
         })
     }
 }
@@ -200,7 +396,7 @@ impl<AccountId: Member> AsyncVM<AccountId> for Tuple {
 
         Err(XvmCallError {
             error: XvmError::VmNotRecognized,
-            consumed_weight: PLACEHOLDER_WEIGHT,
+            consumed_weight: Weight::zero(),
         })
     }
 
@@ -222,7 +418,7 @@ impl<AccountId: Member> AsyncVM<AccountId> for Tuple {
 
         Err(XvmCallError {
             error: XvmError::VmNotRecognized,
-            consumed_weight: PLACEHOLDER_WEIGHT,
+            consumed_weight: Weight::zero(),
         })
     }
 }
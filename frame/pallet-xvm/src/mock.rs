@@ -0,0 +1,247 @@
+// This file is part of Astar.
+
+// Copyright (C) 2019-2023 Stake Technologies Pte.Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// Astar is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Astar is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Astar. If not, see <http://www.gnu.org/licenses/>.
+
+//! Testing utilities.
+
+use crate::{self as pallet_xvm, *};
+
+use frame_support::{construct_runtime, parameter_types, traits::Everything};
+use sp_core::H256;
+use sp_runtime::{
+    testing::Header,
+    traits::{BlakeTwo256, IdentityLookup},
+};
+use std::cell::RefCell;
+
+pub type AccountId = u64;
+pub type BlockNumber = u64;
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<TestRuntime>;
+type Block = frame_system::mocking::MockBlock<TestRuntime>;
+
+pub const ALICE: AccountId = 1;
+pub const BOB: AccountId = 2;
+
+/// Identifier of the mock synchronous VM.
+pub const MOCK_VM_ID: VmId = 0x01;
+/// Identifier of the inbox asynchronous VM.
+pub const INBOX_VM_ID: VmId = 0x0A;
+
+/// Weight consumed by every mock VM call.
+pub const MOCK_CALL_WEIGHT: Weight = Weight::from_parts(1_000, 100);
+
+thread_local! {
+    /// Calls received by the mock VM, as `(from, to, input)`.
+    pub static MOCK_VM_CALLS: RefCell<Vec<(AccountId, Vec<u8>, Vec<u8>)>> = RefCell::new(Vec::new());
+}
+
+/// Synchronous VM which records its calls.
+///
+/// Calls to the `b"fail"` address fail, calls to `b"ping"` and `b"pong"` call each other
+/// back through XVM, calls to `b"send"` store the input in the caller inbox, other calls echo
+/// their input and log it.
+///
+/// Instantiation of `b"fail"` code reverts, other contracts are deployed at the code followed
+/// by the salt and log their constructor input.
+pub struct MockVM;
+
+impl SyncVM<AccountId> for MockVM {
+    fn id() -> VmId {
+        MOCK_VM_ID
+    }
+
+    fn xvm_call(_context: XvmContext, from: AccountId, to: Vec<u8>, input: Vec<u8>) -> XvmResult {
+        MOCK_VM_CALLS.with(|calls| calls.borrow_mut().push((from, to.clone(), input.clone())));
+
+        let bounce_to: Option<&[u8]> = match to.as_slice() {
+            b"ping" => Some(b"pong"),
+            b"pong" => Some(b"ping"),
+            _ => None,
+        };
+        if let Some(bounce_to) = bounce_to {
+            let context = XvmContext {
+                id: MOCK_VM_ID,
+                ..Default::default()
+            };
+            return Xvm::xvm_bare_call(context, from, bounce_to.to_vec(), input);
+        }
+
+        if to == b"send" {
+            let id = Xvm::enqueue_message(from.clone(), from, input, None).map_err(|error| {
+                XvmCallError {
+                    error,
+                    consumed_weight: MOCK_CALL_WEIGHT,
+                }
+            })?;
+            return Ok(XvmCallOk {
+                output: id.encode(),
+                consumed_weight: MOCK_CALL_WEIGHT,
+                logs: Vec::new(),
+            });
+        }
+
+        if to == b"fail" {
+            Err(XvmCallError {
+                error: XvmError::ExecutionError(b"failed".to_vec()),
+                consumed_weight: MOCK_CALL_WEIGHT,
+            })
+        } else {
+            Ok(XvmCallOk {
+                output: input.clone(),
+                consumed_weight: MOCK_CALL_WEIGHT,
+                logs: vec![XvmLog {
+                    address: to,
+                    topics: Vec::new(),
+                    data: input,
+                }],
+            })
+        }
+    }
+
+    fn xvm_instantiate(
+        _context: XvmContext,
+        from: AccountId,
+        code: Vec<u8>,
+        input: Vec<u8>,
+        salt: Vec<u8>,
+    ) -> XvmResult {
+        MOCK_VM_CALLS.with(|calls| calls.borrow_mut().push((from, code.clone(), input.clone())));
+
+        if code == b"fail" {
+            return Err(XvmCallError {
+                error: XvmError::Reverted(Vec::new()),
+                consumed_weight: MOCK_CALL_WEIGHT,
+            });
+        }
+
+        let address = [code, salt].concat();
+        Ok(XvmCallOk {
+            output: address.clone(),
+            consumed_weight: MOCK_CALL_WEIGHT,
+            logs: vec![XvmLog {
+                address,
+                topics: Vec::new(),
+                data: input,
+            }],
+        })
+    }
+}
+
+/// Takes the calls received by the mock VM so far.
+pub fn take_mock_vm_calls() -> Vec<(AccountId, Vec<u8>, Vec<u8>)> {
+    MOCK_VM_CALLS.with(|calls| calls.take())
+}
+
+construct_runtime!(
+    pub struct TestRuntime
+    where
+        Block = Block,
+        NodeBlock = Block,
+        UncheckedExtrinsic = UncheckedExtrinsic,
+    {
+        System: frame_system,
+        Xvm: pallet_xvm,
+    }
+);
+
+parameter_types! {
+    pub const BlockHashCount: u64 = 250;
+}
+
+impl frame_system::Config for TestRuntime {
+    type BaseCallFilter = Everything;
+    type BlockWeights = ();
+    type BlockLength = ();
+    type RuntimeOrigin = RuntimeOrigin;
+    type Index = u64;
+    type RuntimeCall = RuntimeCall;
+    type BlockNumber = BlockNumber;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = AccountId;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type Header = Header;
+    type RuntimeEvent = RuntimeEvent;
+    type BlockHashCount = BlockHashCount;
+    type DbWeight = ();
+    type Version = ();
+    type PalletInfo = PalletInfo;
+    type AccountData = ();
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type SS58Prefix = ();
+    type OnSetCode = ();
+    type MaxConsumers = frame_support::traits::ConstU32<16>;
+}
+
+parameter_types! {
+    pub const InboxVmId: VmId = INBOX_VM_ID;
+    pub const MaxInboxSize: u32 = 4;
+    pub const MaxMessageSize: u32 = 64;
+    pub const MaxDeliveriesPerBlock: u32 = 2;
+    pub DeliveryWeight: Weight = Weight::from_ref_time(1_000_000);
+    pub const MaxCallDepth: u32 = 4;
+    pub const MaxScannedEvents: u32 = 64;
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+parameter_types! {
+    pub const BenchmarkVmId: Option<VmId> = Some(MOCK_VM_ID);
+}
+
+impl pallet_xvm::Config for TestRuntime {
+    type RuntimeEvent = RuntimeEvent;
+    type SyncVM = (MockVM,);
+    type AsyncVM = (inbox::Inbox<InboxVmId, TestRuntime>,);
+    type MaxInboxSize = MaxInboxSize;
+    type MaxMessageSize = MaxMessageSize;
+    type MaxDeliveriesPerBlock = MaxDeliveriesPerBlock;
+    type DeliveryWeight = DeliveryWeight;
+    type MaxCallDepth = MaxCallDepth;
+    type MaxScannedEvents = MaxScannedEvents;
+    type ManagerOrigin = frame_system::EnsureRoot<AccountId>;
+    type WeightInfo = ();
+    #[cfg(feature = "runtime-benchmarks")]
+    type BenchmarkVmId = BenchmarkVmId;
+}
+
+pub struct ExternalityBuilder;
+
+impl ExternalityBuilder {
+    pub fn build() -> sp_io::TestExternalities {
+        let storage = frame_system::GenesisConfig::default()
+            .build_storage::<TestRuntime>()
+            .unwrap();
+
+        let mut ext = sp_io::TestExternalities::from(storage);
+        ext.execute_with(|| {
+            System::set_block_number(1);
+            take_mock_vm_calls();
+        });
+        ext
+    }
+}
+
+/// Moves to the next block, running `on_initialize` of the XVM pallet.
+pub fn run_to_next_block() {
+    use frame_support::traits::Hooks;
+
+    let next = System::block_number() + 1;
+    System::set_block_number(next);
+    Xvm::on_initialize(next);
+}
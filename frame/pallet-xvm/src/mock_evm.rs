@@ -0,0 +1,304 @@
+// This file is part of Astar.
+
+// Copyright (C) 2019-2023 Stake Technologies Pte.Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// Astar is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Astar is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Astar. If not, see <http://www.gnu.org/licenses/>.
+
+//! Testing utilities of the EVM backend.
+
+use crate::{self as pallet_xvm, *};
+
+use frame_support::{
+    construct_runtime, parameter_types,
+    traits::{ConstU128, Everything},
+};
+use pallet_evm::{
+    AddressMapping, EnsureAddressNever, EnsureAddressRoot, HashedAddressMapping, PrecompileFailure,
+    PrecompileHandle, PrecompileOutput, PrecompileResult, PrecompileSet,
+};
+use pallet_unified_accounts::HashedAccountMapping;
+use sp_core::{H160, U256};
+use sp_runtime::{
+    testing::Header,
+    traits::{BlakeTwo256, IdentityLookup},
+    AccountId32,
+};
+
+pub type AccountId = AccountId32;
+pub type Balance = u128;
+type BlockNumber = u64;
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<TestRuntime>;
+type Block = frame_system::mocking::MockBlock<TestRuntime>;
+
+pub const ALICE: AccountId32 = AccountId32::new([1u8; 32]);
+pub const BOB: AccountId32 = AccountId32::new([2u8; 32]);
+
+/// Identifier of the EVM.
+pub const EVM_VM_ID: VmId = 0x0F;
+
+/// Identifier of the mock WASM VM.
+pub const WASM_VM_ID: VmId = 0x1F;
+
+/// Mock WASM contract, calling back the EVM contract given as input.
+pub const WASM_CONTRACT: AccountId32 = AccountId32::new([0xAA; 32]);
+
+/// Address of the mock XVM precompile.
+pub const XVM_PRECOMPILE: H160 = H160::repeat_byte(0x5F);
+
+/// Initial balance of `ALICE` and `BOB`.
+pub const INITIAL_BALANCE: Balance = 1_000_000_000;
+
+construct_runtime!(
+    pub struct TestRuntime
+    where
+        Block = Block,
+        NodeBlock = Block,
+        UncheckedExtrinsic = UncheckedExtrinsic,
+    {
+        System: frame_system,
+        Balances: pallet_balances,
+        Timestamp: pallet_timestamp,
+        Evm: pallet_evm,
+        UnifiedAccounts: pallet_unified_accounts,
+        Xvm: pallet_xvm,
+    }
+);
+
+parameter_types! {
+    pub const BlockHashCount: u64 = 250;
+}
+
+impl frame_system::Config for TestRuntime {
+    type BaseCallFilter = Everything;
+    type BlockWeights = ();
+    type BlockLength = ();
+    type RuntimeOrigin = RuntimeOrigin;
+    type Index = u64;
+    type RuntimeCall = RuntimeCall;
+    type BlockNumber = BlockNumber;
+    type Hash = sp_core::H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = AccountId;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type Header = Header;
+    type RuntimeEvent = RuntimeEvent;
+    type BlockHashCount = BlockHashCount;
+    type DbWeight = ();
+    type Version = ();
+    type PalletInfo = PalletInfo;
+    type AccountData = pallet_balances::AccountData<Balance>;
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type SS58Prefix = ();
+    type OnSetCode = ();
+    type MaxConsumers = frame_support::traits::ConstU32<16>;
+}
+
+impl pallet_balances::Config for TestRuntime {
+    type MaxReserves = ();
+    type ReserveIdentifier = ();
+    type MaxLocks = ();
+    type Balance = Balance;
+    type RuntimeEvent = RuntimeEvent;
+    type DustRemoval = ();
+    type ExistentialDeposit = ConstU128<1>;
+    type AccountStore = System;
+    type WeightInfo = ();
+}
+
+parameter_types! {
+    pub const MinimumPeriod: u64 = 5;
+}
+
+impl pallet_timestamp::Config for TestRuntime {
+    type Moment = u64;
+    type OnTimestampSet = ();
+    type MinimumPeriod = MinimumPeriod;
+    type WeightInfo = ();
+}
+
+/// Mock XVM precompile, calling `WASM_CONTRACT` with its input from the calling contract like
+/// the XVM precompile does.
+#[derive(Default)]
+pub struct MockPrecompiles;
+
+impl PrecompileSet for MockPrecompiles {
+    fn execute(&self, handle: &mut impl PrecompileHandle) -> Option<PrecompileResult> {
+        if handle.code_address() != XVM_PRECOMPILE {
+            return None;
+        }
+
+        let caller = handle.context().caller;
+        let context = XvmContext {
+            id: WASM_VM_ID,
+            max_weight: Weight::from_ref_time(1_000_000),
+            ..Default::default()
+        };
+        let result = Xvm::in_origin_frame(EVM_VM_ID, caller.encode(), || {
+            Xvm::xvm_bare_call(
+                context,
+                UnifiedAccounts::into_account_id(caller),
+                WASM_CONTRACT.encode(),
+                handle.input().to_vec(),
+            )
+        });
+
+        Some(match result {
+            Ok(success) => Ok(PrecompileOutput {
+                exit_status: ::evm::ExitSucceed::Returned,
+                output: success.output().to_vec(),
+            }),
+            Err(error) => Err(PrecompileFailure::Revert {
+                exit_status: ::evm::ExitRevert::Reverted,
+                output: error.error().encode(),
+            }),
+        })
+    }
+
+    fn is_precompile(&self, address: H160) -> bool {
+        address == XVM_PRECOMPILE
+    }
+}
+
+parameter_types! {
+    pub WeightPerGas: Weight = Weight::from_ref_time(1);
+    pub BlockGasLimit: U256 = U256::from(u64::MAX);
+    pub Precompiles: MockPrecompiles = MockPrecompiles;
+}
+
+impl pallet_evm::Config for TestRuntime {
+    type FeeCalculator = ();
+    type GasWeightMapping = pallet_evm::FixedGasWeightMapping<Self>;
+    type WeightPerGas = WeightPerGas;
+    type CallOrigin = EnsureAddressRoot<AccountId>;
+    type WithdrawOrigin = EnsureAddressNever<AccountId>;
+    type AddressMapping = UnifiedAccounts;
+    type Currency = Balances;
+    type RuntimeEvent = RuntimeEvent;
+    type Runner = pallet_evm::runner::stack::Runner<Self>;
+    type PrecompilesType = MockPrecompiles;
+    type PrecompilesValue = Precompiles;
+    type ChainId = ();
+    type OnChargeTransaction = ();
+    type BlockGasLimit = BlockGasLimit;
+    type BlockHashMapping = pallet_evm::SubstrateBlockHashMapping<Self>;
+    type FindAuthor = ();
+    type OnCreate = ();
+    type WeightInfo = ();
+}
+
+parameter_types! {
+    pub const ChainId: u64 = 1024;
+}
+
+impl pallet_unified_accounts::Config for TestRuntime {
+    type RuntimeEvent = RuntimeEvent;
+    type DefaultEvmToNative = HashedAddressMapping<BlakeTwo256>;
+    type DefaultNativeToEvm = HashedAccountMapping<BlakeTwo256>;
+    type Currency = Balances;
+    type ChainId = ChainId;
+    type WeightInfo = ();
+}
+
+parameter_types! {
+    pub const EvmVmId: VmId = EVM_VM_ID;
+    pub const MaxInboxSize: u32 = 4;
+    pub const MaxMessageSize: u32 = 64;
+    pub const MaxDeliveriesPerBlock: u32 = 2;
+    pub DeliveryWeight: Weight = Weight::from_ref_time(1_000_000);
+    pub const MaxCallDepth: u32 = 4;
+    pub const MaxScannedEvents: u32 = 64;
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+parameter_types! {
+    pub const BenchmarkVmId: Option<VmId> = Some(EVM_VM_ID);
+}
+
+/// Mock WASM VM whose contracts call back the EVM contract given as input, from their own
+/// address like the XVM chain extension does.
+pub struct MockWasm;
+
+impl SyncVM<AccountId> for MockWasm {
+    fn id() -> VmId {
+        WASM_VM_ID
+    }
+
+    fn xvm_call(context: XvmContext, _from: AccountId, to: Vec<u8>, input: Vec<u8>) -> XvmResult {
+        let contract = AccountId::decode(&mut to.as_slice()).expect("WASM contract address");
+        let context = XvmContext {
+            id: EVM_VM_ID,
+            max_weight: context.max_weight,
+            ..Default::default()
+        };
+        Xvm::in_origin_frame(WASM_VM_ID, to, || {
+            Xvm::xvm_bare_call(context, contract, input, Vec::new())
+        })
+    }
+
+    fn xvm_instantiate(
+        _context: XvmContext,
+        _from: AccountId,
+        _code: Vec<u8>,
+        _input: Vec<u8>,
+        _salt: Vec<u8>,
+    ) -> XvmResult {
+        Err(XvmCallError {
+            error: XvmError::ExecutionError(b"Unsupported".to_vec()),
+            consumed_weight: Weight::zero(),
+        })
+    }
+}
+
+impl pallet_xvm::Config for TestRuntime {
+    type RuntimeEvent = RuntimeEvent;
+    type SyncVM = (evm::EVM<EvmVmId, TestRuntime, UnifiedAccounts>, MockWasm);
+    type AsyncVM = ();
+    type MaxInboxSize = MaxInboxSize;
+    type MaxMessageSize = MaxMessageSize;
+    type MaxDeliveriesPerBlock = MaxDeliveriesPerBlock;
+    type DeliveryWeight = DeliveryWeight;
+    type MaxCallDepth = MaxCallDepth;
+    type MaxScannedEvents = MaxScannedEvents;
+    type ManagerOrigin = frame_system::EnsureRoot<AccountId>;
+    type WeightInfo = ();
+    #[cfg(feature = "runtime-benchmarks")]
+    type BenchmarkVmId = BenchmarkVmId;
+}
+
+/// Deploys `code` as the runtime code of the contract at `address`.
+pub fn deploy(address: H160, code: Vec<u8>) {
+    pallet_evm::AccountCodes::<TestRuntime>::insert(address, code);
+}
+
+pub struct ExternalityBuilder;
+
+impl ExternalityBuilder {
+    pub fn build() -> sp_io::TestExternalities {
+        let mut storage = frame_system::GenesisConfig::default()
+            .build_storage::<TestRuntime>()
+            .unwrap();
+        pallet_balances::GenesisConfig::<TestRuntime> {
+            balances: vec![(ALICE, INITIAL_BALANCE), (BOB, INITIAL_BALANCE)],
+        }
+        .assimilate_storage(&mut storage)
+        .unwrap();
+
+        let mut ext = sp_io::TestExternalities::from(storage);
+        ext.execute_with(|| System::set_block_number(1));
+        ext
+    }
+}
@@ -30,12 +30,20 @@
 //!
 //!
 
+// Synchronous XVM calls being executed, outermost first. Kept in memory as it only lives
+// as long as the outermost call.
+environmental::environmental!(call_stack: sp_std::vec::Vec<(crate::VmId, sp_std::vec::Vec<u8>)>);
+
 #[frame_support::pallet]
 #[allow(clippy::module_inception)]
 pub mod pallet {
     use crate::*;
-    use frame_support::pallet_prelude::*;
+    use frame_support::{
+        pallet_prelude::*,
+        storage::{with_transaction, TransactionOutcome},
+    };
     use frame_system::pallet_prelude::*;
+    use sp_runtime::traits::{One, Saturating};
 
     #[pallet::pallet]
     #[pallet::without_storage_info]
@@ -49,17 +57,159 @@ pub mod pallet {
         type AsyncVM: AsyncVM<Self::AccountId>;
         /// General event type.
         type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+        /// Maximum number of pending messages in an inbox.
+        #[pallet::constant]
+        type MaxInboxSize: Get<u32>;
+        /// Maximum size of a message payload in bytes.
+        #[pallet::constant]
+        type MaxMessageSize: Get<u32>;
+        /// Maximum number of message deliveries processed in a block.
+        #[pallet::constant]
+        type MaxDeliveriesPerBlock: Get<u32>;
+        /// Max allowed weight for a single message delivery.
+        #[pallet::constant]
+        type DeliveryWeight: Get<Weight>;
+        /// Maximum number of nested synchronous XVM calls, the frame of the contract
+        /// originating them included.
+        #[pallet::constant]
+        type MaxCallDepth: Get<u32>;
+        /// Maximum number of events a block may hold for the logs of WASM contracts to be
+        /// collected from them.
+        #[pallet::constant]
+        type MaxScannedEvents: Get<u32>;
+        /// Origin allowed to manage reentrancy protection of call targets, VM kill switches
+        /// and target filters.
+        type ManagerOrigin: EnsureOrigin<<Self as frame_system::Config>::RuntimeOrigin>;
+        /// Weight information for extrinsics in this pallet.
+        type WeightInfo: WeightInfo;
+
+        /// Synchronous VM calls and instantiations are dispatched to. Used only in benchmarks.
+        ///
+        /// The benchmarks target an address without a contract, so that the VM overhead is
+        /// measured without any contract execution. If `None`, only the pallet overhead is.
+        #[cfg(feature = "runtime-benchmarks")]
+        type BenchmarkVmId: Get<Option<VmId>>;
     }
 
     #[pallet::error]
-    pub enum Error<T> {}
+    pub enum Error<T> {
+        /// Message isn't in the inbox.
+        MessageNotFound,
+    }
+
+    /// Identifier of the next sent message.
+    #[pallet::storage]
+    pub type NextMessageId<T> = StorageValue<_, MessageId, ValueQuery>;
+
+    /// Pending messages of each recipient, in the order they were sent.
+    #[pallet::storage]
+    #[pallet::getter(fn inbox)]
+    pub type Inboxes<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        BoundedVec<XvmMessage<T::AccountId, T::BlockNumber>, T::MaxInboxSize>,
+        ValueQuery,
+    >;
+
+    /// Message deliveries to process at the beginning of a block.
+    #[pallet::storage]
+    pub type DeliveryQueue<T: Config> = StorageMap<
+        _,
+        Twox64Concat,
+        T::BlockNumber,
+        BoundedVec<(T::AccountId, MessageId, XvmDelivery), T::MaxDeliveriesPerBlock>,
+        ValueQuery,
+    >;
+
+    /// Call targets which can't be re-entered while they are on the call stack.
+    #[pallet::storage]
+    pub type ReentrancyGuards<T> =
+        StorageDoubleMap<_, Twox64Concat, VmId, Blake2_128Concat, Vec<u8>, (), OptionQuery>;
+
+    /// Synchronous VMs which can't be called nor deployed to.
+    #[pallet::storage]
+    pub type DisabledVms<T> = StorageMap<_, Twox64Concat, VmId, (), OptionQuery>;
+
+    /// Filter applied to the call targets of a VM, any target can be called when unset.
+    #[pallet::storage]
+    pub type TargetFilters<T> = StorageMap<_, Twox64Concat, VmId, TargetFilter, OptionQuery>;
+
+    /// Call targets listed by the filter of their VM.
+    #[pallet::storage]
+    pub type ListedTargets<T> =
+        StorageDoubleMap<_, Twox64Concat, VmId, Blake2_128Concat, Vec<u8>, (), OptionQuery>;
 
     #[pallet::event]
     #[pallet::generate_deposit(pub(crate) fn deposit_event)]
     pub enum Event<T: Config> {
-        XvmCall { result: Result<Vec<u8>, XvmError> },
-        XvmSend { result: Result<Vec<u8>, XvmError> },
-        XvmQuery { result: Result<Vec<u8>, XvmError> },
+        XvmCall {
+            result: Result<Vec<u8>, XvmError>,
+        },
+        XvmSend {
+            result: Result<Vec<u8>, XvmError>,
+        },
+        XvmQuery {
+            result: Result<Vec<u8>, XvmError>,
+        },
+        MessageSent {
+            id: MessageId,
+            from: T::AccountId,
+            to: T::AccountId,
+        },
+        MessageDelivered {
+            id: MessageId,
+            result: Result<Vec<u8>, XvmError>,
+        },
+        MessagesAcknowledged {
+            inbox: T::AccountId,
+            ids: Vec<MessageId>,
+        },
+        ReentrancyGuardSet {
+            vm_id: VmId,
+            target: Vec<u8>,
+            enabled: bool,
+        },
+        /// Synchronous XVM call was executed, along with the logs emitted by the callee.
+        XvmCallExecuted {
+            caller: T::AccountId,
+            callee: Vec<u8>,
+            vm_id: VmId,
+            success: bool,
+            consumed_weight: Weight,
+            logs: Vec<XvmLog>,
+        },
+        XvmInstantiate {
+            result: Result<Vec<u8>, XvmError>,
+        },
+        /// Contract was instantiated through XVM, along with the logs emitted by its constructor.
+        XvmContractInstantiated {
+            deployer: T::AccountId,
+            address: Vec<u8>,
+            vm_id: VmId,
+            consumed_weight: Weight,
+            logs: Vec<XvmLog>,
+        },
+        VmEnabledSet {
+            vm_id: VmId,
+            enabled: bool,
+        },
+        TargetFilterSet {
+            vm_id: VmId,
+            filter: Option<TargetFilter>,
+        },
+        TargetListedSet {
+            vm_id: VmId,
+            target: Vec<u8>,
+            listed: bool,
+        },
+    }
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_initialize(now: BlockNumberFor<T>) -> Weight {
+            Self::deliver_messages(now)
+        }
     }
 
     impl<T: Config> Pallet<T> {
@@ -67,7 +217,7 @@ pub mod pallet {
         /// Essentially does the same thing as `xvm_call`, but a bit differently:
         ///   - It does not verify origin
         ///   - It does not use `Dispatchable` API (cannot be called from tx)
-        ///   - It does not deposit event upon completion
+        ///   - It only deposits the `XvmCallExecuted` event upon completion, unless it's a query
         ///   - It returns `XvmResult` letting the caller get return data directly
         pub fn xvm_bare_call(
             context: XvmContext,
@@ -75,7 +225,7 @@ pub mod pallet {
             to: Vec<u8>,
             input: Vec<u8>,
         ) -> XvmResult {
-            let result = T::SyncVM::xvm_call(context, from, to, input);
+            let result = Self::sync_call(context, from, to, input);
 
             log::trace!(
                 target: "xvm::pallet::xvm_bare_call",
@@ -84,12 +234,332 @@ pub mod pallet {
 
             result
         }
+
+        /// Internal interface for cross-pallet contract deployment.
+        /// Essentially does the same thing as `xvm_instantiate`, with the same differences
+        /// as `xvm_bare_call`. The output of a successful instantiation is the encoded address
+        /// of the new contract.
+        pub fn xvm_bare_instantiate(
+            context: XvmContext,
+            from: T::AccountId,
+            code: Vec<u8>,
+            input: Vec<u8>,
+            salt: Vec<u8>,
+        ) -> XvmResult {
+            let result = Self::sync_instantiate(context, from, code, input, salt);
+
+            log::trace!(
+                target: "xvm::pallet::xvm_bare_instantiate",
+                "Execution result: {:?}", result
+            );
+
+            result
+        }
+
+        /// Executes a synchronous XVM call, tracking it on the call stack.
+        ///
+        /// Fails when the VM is disabled or the target isn't allowed, when the call is too
+        /// deeply nested, or when it re-enters a protected target. Deposits `XvmCallExecuted`
+        /// carrying the callee logs once the call completes, except for query calls which
+        /// leave no trace as their state changes are rolled back.
+        pub(crate) fn sync_call(
+            context: XvmContext,
+            from: T::AccountId,
+            to: Vec<u8>,
+            input: Vec<u8>,
+        ) -> XvmResult {
+            let vm_id = context.id;
+            let query = context.query;
+            Self::ensure_allowed(vm_id, Some(&to)).map_err(|error| XvmCallError {
+                error,
+                consumed_weight: Weight::zero(),
+            })?;
+            let result = Self::in_call_frame(vm_id, to.clone(), query, || {
+                T::SyncVM::xvm_call(context, from.clone(), to.clone(), input)
+            })?;
+            if query {
+                return result;
+            }
+
+            Self::deposit_event(Event::<T>::XvmCallExecuted {
+                caller: from,
+                callee: to,
+                vm_id,
+                success: result.is_ok(),
+                consumed_weight: consumed_weight(&result),
+                logs: result
+                    .as_ref()
+                    .map(|result| result.logs().to_vec())
+                    .unwrap_or_default(),
+            });
+
+            result
+        }
+
+        /// Executes a synchronous XVM contract instantiation, tracking it on the call stack
+        /// like a call to a not yet known address.
+        ///
+        /// Fails when the VM is disabled, or when it has an allowlist: the address of the new
+        /// contract isn't known beforehand, so it can't be listed.
+        /// Deposits `XvmContractInstantiated` once the contract is instantiated.
+        pub(crate) fn sync_instantiate(
+            context: XvmContext,
+            from: T::AccountId,
+            code: Vec<u8>,
+            input: Vec<u8>,
+            salt: Vec<u8>,
+        ) -> XvmResult {
+            let vm_id = context.id;
+            let query = context.query;
+            Self::ensure_allowed(vm_id, None).map_err(|error| XvmCallError {
+                error,
+                consumed_weight: Weight::zero(),
+            })?;
+            let result = Self::in_call_frame(vm_id, Vec::new(), query, || {
+                T::SyncVM::xvm_instantiate(context, from.clone(), code, input, salt)
+            })?;
+
+            if let Ok(success) = &result {
+                Self::deposit_event(Event::<T>::XvmContractInstantiated {
+                    deployer: from,
+                    address: success.output().to_vec(),
+                    vm_id,
+                    consumed_weight: success.consumed_weight,
+                    logs: success.logs().to_vec(),
+                });
+            }
+
+            result
+        }
+
+        /// Fails with `CallBlocked` when `vm_id` is disabled, or when its filter rejects `target`.
+        ///
+        /// Instantiations have no `target`, which is treated as not listed.
+        pub(crate) fn ensure_allowed(
+            vm_id: VmId,
+            target: Option<&Vec<u8>>,
+        ) -> Result<(), XvmError> {
+            ensure!(
+                !DisabledVms::<T>::contains_key(vm_id),
+                XvmError::CallBlocked
+            );
+
+            let listed = || {
+                target.map_or(false, |target| {
+                    ListedTargets::<T>::contains_key(vm_id, target)
+                })
+            };
+            let allowed = match TargetFilters::<T>::get(vm_id) {
+                Some(TargetFilter::Allowlist) => listed(),
+                Some(TargetFilter::Denylist) => !listed(),
+                None => true,
+            };
+            ensure!(allowed, XvmError::CallBlocked);
+
+            Ok(())
+        }
+
+        /// Runs `execute` with `target` pushed on the call stack, rolling back its state
+        /// changes in `query` mode.
+        ///
+        /// Fails without running it when the call is too deeply nested, or when it re-enters
+        /// a protected target.
+        fn in_call_frame(
+            vm_id: VmId,
+            target: Vec<u8>,
+            query: bool,
+            execute: impl FnOnce() -> XvmResult,
+        ) -> Result<XvmResult, XvmCallError> {
+            let error = |error| XvmCallError {
+                error,
+                consumed_weight: Weight::zero(),
+            };
+
+            Self::with_call_stack(|| {
+                super::call_stack::with(|stack| {
+                    ensure!(
+                        stack.len() < T::MaxCallDepth::get() as usize,
+                        XvmError::CallDepthExceeded
+                    );
+                    let frame = (vm_id, target);
+                    ensure!(
+                        !stack.contains(&frame)
+                            || !ReentrancyGuards::<T>::contains_key(vm_id, &frame.1),
+                        XvmError::ReentrantCall
+                    );
+                    stack.push(frame);
+                    Ok(())
+                })
+                .expect("call stack is set by `with_call_stack`; qed")
+                .map_err(error)?;
+
+                let result = if query {
+                    with_transaction(|| {
+                        TransactionOutcome::Rollback(Ok::<_, DispatchError>(execute()))
+                    })
+                    .unwrap_or_else(|e| {
+                        Err(error(XvmError::ExecutionError(
+                            Into::<&str>::into(e).into(),
+                        )))
+                    })
+                } else {
+                    execute()
+                };
+
+                super::call_stack::with(|stack| stack.pop());
+                Ok(result)
+            })
+        }
+
+        /// Runs `f` with the frame of the `address` contract of `vm_id` on top of the call
+        /// stack, so that XVM calls made by `f` can't re-enter a protected originating
+        /// contract.
+        ///
+        /// Meant to wrap the XVM calls made by VM entry points such as precompiles and chain
+        /// extensions. The frame isn't pushed again when it's already on top.
+        pub fn in_origin_frame<R>(vm_id: VmId, address: Vec<u8>, f: impl FnOnce() -> R) -> R {
+            Self::with_call_stack(|| {
+                let frame = (vm_id, address);
+                let pushed = super::call_stack::with(|stack| {
+                    if stack.last() == Some(&frame) {
+                        false
+                    } else {
+                        stack.push(frame);
+                        true
+                    }
+                })
+                .expect("call stack is set by `with_call_stack`; qed");
+
+                let result = f();
+
+                if pushed {
+                    super::call_stack::with(|stack| stack.pop());
+                }
+                result
+            })
+        }
+
+        /// Runs `f` with an in-memory call stack, starting an empty one unless a stack is
+        /// already set by an outer call.
+        fn with_call_stack<R>(f: impl FnOnce() -> R) -> R {
+            if super::call_stack::with(|_| ()).is_some() {
+                f()
+            } else {
+                super::call_stack::using(&mut Vec::new(), f)
+            }
+        }
+
+        /// Stores a message in the `to` inbox, and schedules its delivery for the next block
+        /// if requested.
+        pub fn enqueue_message(
+            from: T::AccountId,
+            to: T::AccountId,
+            payload: Vec<u8>,
+            delivery: Option<XvmDelivery>,
+        ) -> Result<MessageId, XvmError> {
+            if payload.len() > T::MaxMessageSize::get() as usize {
+                return Err(XvmError::MessageTooLarge);
+            }
+
+            let id = NextMessageId::<T>::get();
+            let now = frame_system::Pallet::<T>::block_number();
+
+            let mut inbox = Inboxes::<T>::get(&to);
+            inbox
+                .try_push(XvmMessage {
+                    id,
+                    from: from.clone(),
+                    sent_at: now,
+                    payload,
+                })
+                .map_err(|_| XvmError::InboxFull)?;
+
+            if let Some(delivery) = delivery {
+                let next_block = now.saturating_add(One::one());
+                let mut queue = DeliveryQueue::<T>::get(next_block);
+                queue
+                    .try_push((to.clone(), id, delivery))
+                    .map_err(|_| XvmError::DeliveryQueueFull)?;
+                DeliveryQueue::<T>::insert(next_block, queue);
+            }
+
+            Inboxes::<T>::insert(&to, inbox);
+            NextMessageId::<T>::put(id.wrapping_add(1));
+            Self::deposit_event(Event::<T>::MessageSent { id, from, to });
+
+            Ok(id)
+        }
+
+        /// Removes messages with given `ids` from the `inbox`.
+        pub fn acknowledge_messages(inbox: T::AccountId, ids: Vec<MessageId>) -> DispatchResult {
+            Inboxes::<T>::try_mutate(&inbox, |messages| -> DispatchResult {
+                for id in ids.iter() {
+                    let index = messages
+                        .iter()
+                        .position(|message| message.id == *id)
+                        .ok_or(Error::<T>::MessageNotFound)?;
+                    messages.remove(index);
+                }
+                Ok(())
+            })?;
+
+            Self::deposit_event(Event::<T>::MessagesAcknowledged { inbox, ids });
+            Ok(())
+        }
+
+        /// Delivers messages scheduled for block `now`, returns the consumed weight.
+        ///
+        /// Messages whose delivery succeeded are removed from the inbox, failed ones
+        /// stay until acknowledged.
+        pub(crate) fn deliver_messages(now: T::BlockNumber) -> Weight {
+            let deliveries = DeliveryQueue::<T>::take(now);
+            let mut weight = T::WeightInfo::deliver_messages(deliveries.len() as u32);
+
+            for (inbox, id, delivery) in deliveries {
+                let mut messages = Inboxes::<T>::get(&inbox);
+                let index = match messages.iter().position(|message| message.id == id) {
+                    Some(index) => index,
+                    // Already acknowledged
+                    None => continue,
+                };
+                let message = messages[index].clone();
+
+                let context = XvmContext {
+                    id: delivery.vm_id,
+                    max_weight: T::DeliveryWeight::get(),
+                    ..Default::default()
+                };
+                let result = Self::sync_call(context, message.from, delivery.to, message.payload);
+                weight.saturating_accrue(consumed_weight(&result));
+
+                log::trace!(
+                    target: "xvm::pallet::deliver_messages",
+                    "Delivery of message {:?} result: {:?}", id, result,
+                );
+
+                if result.is_ok() {
+                    messages.remove(index);
+                    Inboxes::<T>::insert(&inbox, messages);
+                    weight.saturating_accrue(T::DbWeight::get().writes(1));
+                }
+
+                Self::deposit_event(Event::<T>::MessageDelivered {
+                    id,
+                    result: match result {
+                        Ok(result) => Ok(result.output),
+                        Err(result) => Err(result.error),
+                    },
+                });
+            }
+
+            weight
+        }
     }
 
     #[pallet::call]
     impl<T: Config> Pallet<T> {
         #[pallet::call_index(0)]
-        #[pallet::weight(context.max_weight)]
+        #[pallet::weight(T::WeightInfo::xvm_call().saturating_add(context.max_weight))]
         pub fn xvm_call(
             origin: OriginFor<T>,
             context: XvmContext,
@@ -98,11 +568,7 @@ pub mod pallet {
         ) -> DispatchResultWithPostInfo {
             let from = ensure_signed(origin)?;
 
-            // Executing XVM call logic itself will consume some weight so that should be subtracted from the max allowed weight of XCM call
-            // TODO: fix
-            //context.max_weight = context.max_weight - PLACEHOLDER_WEIGHT;
-
-            let result = T::SyncVM::xvm_call(context, from, to, input);
+            let result = Self::sync_call(context, from, to, input);
             let consumed_weight = consumed_weight(&result);
 
             log::trace!(
@@ -117,11 +583,11 @@ pub mod pallet {
                 },
             });
 
-            Ok(Some(consumed_weight).into())
+            Ok(Some(T::WeightInfo::xvm_call().saturating_add(consumed_weight)).into())
         }
 
         #[pallet::call_index(1)]
-        #[pallet::weight(context.max_weight)]
+        #[pallet::weight(T::WeightInfo::xvm_send().saturating_add(context.max_weight))]
         pub fn xvm_send(
             origin: OriginFor<T>,
             context: XvmContext,
@@ -130,6 +596,7 @@ pub mod pallet {
         ) -> DispatchResultWithPostInfo {
             let from = ensure_signed(origin)?;
             let result = T::AsyncVM::xvm_send(context, from, to, message);
+            let consumed_weight = consumed_weight(&result);
 
             Self::deposit_event(Event::<T>::XvmSend {
                 result: match result {
@@ -138,14 +605,15 @@ pub mod pallet {
                 },
             });
 
-            Ok(().into())
+            Ok(Some(T::WeightInfo::xvm_send().saturating_add(consumed_weight)).into())
         }
 
         #[pallet::call_index(2)]
-        #[pallet::weight(context.max_weight)]
+        #[pallet::weight(T::WeightInfo::xvm_query().saturating_add(context.max_weight))]
         pub fn xvm_query(origin: OriginFor<T>, context: XvmContext) -> DispatchResultWithPostInfo {
             let inbox = ensure_signed(origin)?;
             let result = T::AsyncVM::xvm_query(context, inbox);
+            let consumed_weight = consumed_weight(&result);
 
             Self::deposit_event(Event::<T>::XvmQuery {
                 result: match result {
@@ -154,7 +622,129 @@ pub mod pallet {
                 },
             });
 
-            Ok(().into())
+            Ok(Some(T::WeightInfo::xvm_query().saturating_add(consumed_weight)).into())
+        }
+
+        /// Acknowledge messages of the origin inbox, removing them from it.
+        #[pallet::call_index(3)]
+        #[pallet::weight(T::WeightInfo::xvm_ack(ids.len() as u32))]
+        pub fn xvm_ack(origin: OriginFor<T>, ids: Vec<MessageId>) -> DispatchResult {
+            let inbox = ensure_signed(origin)?;
+            Self::acknowledge_messages(inbox, ids)
+        }
+
+        /// Enable or disable reentrancy protection of the `target` contract of a VM.
+        #[pallet::call_index(4)]
+        #[pallet::weight(T::WeightInfo::set_reentrancy_guard())]
+        pub fn set_reentrancy_guard(
+            origin: OriginFor<T>,
+            vm_id: VmId,
+            target: Vec<u8>,
+            enabled: bool,
+        ) -> DispatchResult {
+            T::ManagerOrigin::ensure_origin(origin)?;
+
+            if enabled {
+                ReentrancyGuards::<T>::insert(vm_id, &target, ());
+            } else {
+                ReentrancyGuards::<T>::remove(vm_id, &target);
+            }
+
+            Self::deposit_event(Event::<T>::ReentrancyGuardSet {
+                vm_id,
+                target,
+                enabled,
+            });
+            Ok(())
+        }
+
+        /// Instantiate a contract of a synchronous VM from `code`.
+        ///
+        /// `input` holds the constructor arguments, `salt` makes the address deterministic.
+        #[pallet::call_index(5)]
+        #[pallet::weight(T::WeightInfo::xvm_instantiate().saturating_add(context.max_weight))]
+        pub fn xvm_instantiate(
+            origin: OriginFor<T>,
+            context: XvmContext,
+            code: Vec<u8>,
+            input: Vec<u8>,
+            salt: Vec<u8>,
+        ) -> DispatchResultWithPostInfo {
+            let from = ensure_signed(origin)?;
+
+            let result = Self::sync_instantiate(context, from, code, input, salt);
+            let consumed_weight = consumed_weight(&result);
+
+            log::trace!(
+                target: "xvm::pallet::xvm_instantiate",
+                "Execution result: {:?}, consumed_weight: {:?}", result, consumed_weight,
+            );
+
+            Self::deposit_event(Event::<T>::XvmInstantiate {
+                result: match result {
+                    Ok(result) => Ok(result.output),
+                    Err(result) => Err(result.error),
+                },
+            });
+
+            Ok(Some(T::WeightInfo::xvm_instantiate().saturating_add(consumed_weight)).into())
+        }
+
+        /// Enable or disable the synchronous VM, disabled VMs can't be called nor deployed to.
+        #[pallet::call_index(6)]
+        #[pallet::weight(T::WeightInfo::set_vm_enabled())]
+        pub fn set_vm_enabled(origin: OriginFor<T>, vm_id: VmId, enabled: bool) -> DispatchResult {
+            T::ManagerOrigin::ensure_origin(origin)?;
+
+            if enabled {
+                DisabledVms::<T>::remove(vm_id);
+            } else {
+                DisabledVms::<T>::insert(vm_id, ());
+            }
+
+            Self::deposit_event(Event::<T>::VmEnabledSet { vm_id, enabled });
+            Ok(())
+        }
+
+        /// Set the filter applied to the call targets of a VM, `None` allows any target.
+        #[pallet::call_index(7)]
+        #[pallet::weight(T::WeightInfo::set_target_filter())]
+        pub fn set_target_filter(
+            origin: OriginFor<T>,
+            vm_id: VmId,
+            filter: Option<TargetFilter>,
+        ) -> DispatchResult {
+            T::ManagerOrigin::ensure_origin(origin)?;
+
+            TargetFilters::<T>::set(vm_id, filter);
+
+            Self::deposit_event(Event::<T>::TargetFilterSet { vm_id, filter });
+            Ok(())
+        }
+
+        /// Add the `target` contract of a VM to the list of its filter, or remove it.
+        #[pallet::call_index(8)]
+        #[pallet::weight(T::WeightInfo::set_target_listed())]
+        pub fn set_target_listed(
+            origin: OriginFor<T>,
+            vm_id: VmId,
+            target: Vec<u8>,
+            listed: bool,
+        ) -> DispatchResult {
+            T::ManagerOrigin::ensure_origin(origin)?;
+
+            if listed {
+                ListedTargets::<T>::insert(vm_id, &target, ());
+            } else {
+                ListedTargets::<T>::remove(vm_id, &target);
+            }
+
+            Self::deposit_event(Event::<T>::TargetListedSet {
+                vm_id,
+                target,
+                listed,
+            });
+            Ok(())
         }
     }
 }
@@ -0,0 +1,836 @@
+// This file is part of Astar.
+
+// Copyright (C) 2019-2023 Stake Technologies Pte.Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// Astar is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Astar is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Astar. If not, see <http://www.gnu.org/licenses/>.
+
+use super::*;
+use mock::*;
+
+use frame_support::{assert_noop, assert_ok};
+use parity_scale_codec::Encode;
+use sp_runtime::traits::BadOrigin;
+
+fn send(
+    from: AccountId,
+    to: AccountId,
+    message: &[u8],
+    delivery: Option<XvmDelivery>,
+) -> XvmResult {
+    <TestRuntime as Config>::AsyncVM::xvm_send(
+        XvmContext {
+            id: INBOX_VM_ID,
+            max_weight: Weight::MAX,
+            env: delivery.map(|delivery| delivery.encode()),
+            ..Default::default()
+        },
+        from,
+        to.encode(),
+        message.to_vec(),
+    )
+}
+
+fn query(inbox: AccountId) -> Vec<XvmMessage<AccountId, BlockNumber>> {
+    let result = <TestRuntime as Config>::AsyncVM::xvm_query(
+        XvmContext {
+            id: INBOX_VM_ID,
+            ..Default::default()
+        },
+        inbox,
+    )
+    .expect("query should succeed");
+    Decode::decode(&mut result.output()).expect("messages are encoded")
+}
+
+fn mock_call(to: &[u8]) -> XvmResult {
+    Xvm::xvm_bare_call(
+        XvmContext {
+            id: MOCK_VM_ID,
+            ..Default::default()
+        },
+        ALICE,
+        to.to_vec(),
+        b"ball".to_vec(),
+    )
+}
+
+#[test]
+fn send_and_query_works() {
+    ExternalityBuilder::build().execute_with(|| {
+        let result = send(ALICE, BOB, b"hello", None).expect("send should succeed");
+        assert_eq!(result.output(), 0u64.encode());
+        System::assert_last_event(mock::RuntimeEvent::Xvm(Event::MessageSent {
+            id: 0,
+            from: ALICE,
+            to: BOB,
+        }));
+
+        let result = send(BOB, BOB, b"note to self", None).expect("send should succeed");
+        assert_eq!(result.output(), 1u64.encode());
+
+        assert_eq!(
+            query(BOB),
+            vec![
+                XvmMessage {
+                    id: 0,
+                    from: ALICE,
+                    sent_at: 1,
+                    payload: b"hello".to_vec(),
+                },
+                XvmMessage {
+                    id: 1,
+                    from: BOB,
+                    sent_at: 1,
+                    payload: b"note to self".to_vec(),
+                },
+            ]
+        );
+        assert!(query(ALICE).is_empty());
+
+        // messages stay until acknowledged
+        run_to_next_block();
+        assert_eq!(query(BOB).len(), 2);
+        assert!(take_mock_vm_calls().is_empty());
+    })
+}
+
+#[test]
+fn send_limits_are_enforced() {
+    ExternalityBuilder::build().execute_with(|| {
+        assert_eq!(
+            send(ALICE, BOB, &[0u8; 65], None).unwrap_err().error(),
+            &XvmError::MessageTooLarge
+        );
+
+        for _ in 0..MaxInboxSize::get() {
+            assert_ok!(send(ALICE, BOB, b"spam", None));
+        }
+        assert_eq!(
+            send(ALICE, BOB, b"spam", None).unwrap_err().error(),
+            &XvmError::InboxFull
+        );
+
+        // undecodable recipient
+        assert_eq!(
+            <TestRuntime as Config>::AsyncVM::xvm_send(
+                XvmContext {
+                    id: INBOX_VM_ID,
+                    ..Default::default()
+                },
+                ALICE,
+                vec![1u8],
+                b"hello".to_vec(),
+            )
+            .unwrap_err()
+            .error(),
+            &XvmError::EncodingFailure
+        );
+    })
+}
+
+#[test]
+fn acknowledge_works() {
+    ExternalityBuilder::build().execute_with(|| {
+        for message in [b"one", b"two", b"six"] {
+            assert_ok!(send(ALICE, BOB, message, None));
+        }
+
+        assert_noop!(
+            Xvm::xvm_ack(RuntimeOrigin::signed(ALICE), vec![0]),
+            Error::<TestRuntime>::MessageNotFound
+        );
+        assert_noop!(
+            Xvm::xvm_ack(RuntimeOrigin::signed(BOB), vec![0, 7]),
+            Error::<TestRuntime>::MessageNotFound
+        );
+
+        assert_ok!(Xvm::xvm_ack(RuntimeOrigin::signed(BOB), vec![2, 0]));
+        System::assert_last_event(mock::RuntimeEvent::Xvm(Event::MessagesAcknowledged {
+            inbox: BOB,
+            ids: vec![2, 0],
+        }));
+        assert_eq!(
+            query(BOB).into_iter().map(|m| m.id).collect::<Vec<_>>(),
+            vec![1]
+        );
+    })
+}
+
+#[test]
+fn delivery_on_next_block_works() {
+    ExternalityBuilder::build().execute_with(|| {
+        let delivery = XvmDelivery {
+            vm_id: MOCK_VM_ID,
+            to: b"contract".to_vec(),
+        };
+        assert_ok!(send(ALICE, BOB, b"ping", Some(delivery)));
+        let failing = XvmDelivery {
+            vm_id: MOCK_VM_ID,
+            to: b"fail".to_vec(),
+        };
+        assert_ok!(send(ALICE, BOB, b"pong", Some(failing)));
+
+        // not delivered in the same block
+        assert!(take_mock_vm_calls().is_empty());
+
+        run_to_next_block();
+        assert_eq!(
+            take_mock_vm_calls(),
+            vec![
+                (ALICE, b"contract".to_vec(), b"ping".to_vec()),
+                (ALICE, b"fail".to_vec(), b"pong".to_vec()),
+            ]
+        );
+        System::assert_has_event(mock::RuntimeEvent::Xvm(Event::MessageDelivered {
+            id: 0,
+            result: Ok(b"ping".to_vec()),
+        }));
+        System::assert_has_event(mock::RuntimeEvent::Xvm(Event::MessageDelivered {
+            id: 1,
+            result: Err(XvmError::ExecutionError(b"failed".to_vec())),
+        }));
+
+        // only failed delivery stays in the inbox
+        assert_eq!(
+            query(BOB).into_iter().map(|m| m.id).collect::<Vec<_>>(),
+            vec![1]
+        );
+
+        // delivered only once
+        run_to_next_block();
+        assert!(take_mock_vm_calls().is_empty());
+    })
+}
+
+#[test]
+fn delivery_weight_is_charged_on_send() {
+    ExternalityBuilder::build().execute_with(|| {
+        let send_weight = <TestRuntime as Config>::WeightInfo::enqueue_message();
+        let delivery_weight = send_weight.saturating_add(DeliveryWeight::get());
+        let send = |max_weight, delivery: Option<XvmDelivery>| {
+            <TestRuntime as Config>::AsyncVM::xvm_send(
+                XvmContext {
+                    id: INBOX_VM_ID,
+                    max_weight,
+                    env: delivery.map(|delivery| delivery.encode()),
+                    ..Default::default()
+                },
+                ALICE,
+                BOB.encode(),
+                b"ping".to_vec(),
+            )
+        };
+        let delivery = XvmDelivery {
+            vm_id: MOCK_VM_ID,
+            to: b"contract".to_vec(),
+        };
+
+        assert_eq!(consumed_weight(&send(send_weight, None)), send_weight);
+        // budget of the send doesn't cover the delivery
+        assert_eq!(
+            send(send_weight, Some(delivery.clone()))
+                .unwrap_err()
+                .error(),
+            &XvmError::OutOfGas
+        );
+        assert_eq!(query(BOB).len(), 1);
+
+        let result = send(delivery_weight, Some(delivery.clone()));
+        assert_eq!(consumed_weight(&result), delivery_weight);
+        assert_eq!(query(BOB).len(), 2);
+
+        // extra bytes in the delivery are rejected
+        let mut env = delivery.encode();
+        env.push(0);
+        assert_eq!(
+            <TestRuntime as Config>::AsyncVM::xvm_send(
+                XvmContext {
+                    id: INBOX_VM_ID,
+                    max_weight: Weight::MAX,
+                    env: Some(env),
+                    ..Default::default()
+                },
+                ALICE,
+                BOB.encode(),
+                b"ping".to_vec(),
+            )
+            .unwrap_err()
+            .error(),
+            &XvmError::ContextConversionFailed
+        );
+    })
+}
+
+#[test]
+fn acknowledged_message_is_not_delivered() {
+    ExternalityBuilder::build().execute_with(|| {
+        let delivery = XvmDelivery {
+            vm_id: MOCK_VM_ID,
+            to: b"contract".to_vec(),
+        };
+        assert_ok!(send(ALICE, BOB, b"ping", Some(delivery.clone())));
+        assert_ok!(Xvm::xvm_ack(RuntimeOrigin::signed(BOB), vec![0]));
+
+        run_to_next_block();
+        assert!(take_mock_vm_calls().is_empty());
+
+        // delivery queue is bounded
+        for _ in 0..MaxDeliveriesPerBlock::get() {
+            assert_ok!(send(ALICE, BOB, b"ping", Some(delivery.clone())));
+        }
+        assert_eq!(
+            send(ALICE, BOB, b"ping", Some(delivery))
+                .unwrap_err()
+                .error(),
+            &XvmError::DeliveryQueueFull
+        );
+        // failed send leaves no message behind
+        assert_eq!(query(BOB).len(), MaxDeliveriesPerBlock::get() as usize);
+    })
+}
+
+#[test]
+fn ping_pong_stops_at_max_call_depth() {
+    ExternalityBuilder::build().execute_with(|| {
+        assert_eq!(
+            mock_call(b"ping").unwrap_err().error(),
+            &XvmError::CallDepthExceeded
+        );
+        // every nested call up to the limit reached the VM
+        assert_eq!(take_mock_vm_calls().len(), MaxCallDepth::get() as usize);
+
+        assert_ok!(mock_call(b"contract"));
+    })
+}
+
+#[test]
+fn reentrancy_guard_works() {
+    ExternalityBuilder::build().execute_with(|| {
+        assert_noop!(
+            Xvm::set_reentrancy_guard(
+                RuntimeOrigin::signed(ALICE),
+                MOCK_VM_ID,
+                b"ping".to_vec(),
+                true
+            ),
+            BadOrigin
+        );
+        assert_ok!(Xvm::set_reentrancy_guard(
+            RuntimeOrigin::root(),
+            MOCK_VM_ID,
+            b"ping".to_vec(),
+            true
+        ));
+        System::assert_last_event(mock::RuntimeEvent::Xvm(Event::ReentrancyGuardSet {
+            vm_id: MOCK_VM_ID,
+            target: b"ping".to_vec(),
+            enabled: true,
+        }));
+
+        // ping -> pong -> ping
+        assert_eq!(
+            mock_call(b"ping").unwrap_err().error(),
+            &XvmError::ReentrantCall
+        );
+        assert_eq!(
+            take_mock_vm_calls(),
+            vec![
+                (ALICE, b"ping".to_vec(), b"ball".to_vec()),
+                (ALICE, b"pong".to_vec(), b"ball".to_vec()),
+            ]
+        );
+
+        // pong -> ping -> pong -> ping, unprotected pong can be re-entered
+        assert_eq!(
+            mock_call(b"pong").unwrap_err().error(),
+            &XvmError::ReentrantCall
+        );
+        assert_eq!(take_mock_vm_calls().len(), 3);
+
+        assert_ok!(Xvm::set_reentrancy_guard(
+            RuntimeOrigin::root(),
+            MOCK_VM_ID,
+            b"ping".to_vec(),
+            false
+        ));
+        assert_eq!(
+            mock_call(b"ping").unwrap_err().error(),
+            &XvmError::CallDepthExceeded
+        );
+    })
+}
+
+#[test]
+fn query_call_discards_state_changes() {
+    ExternalityBuilder::build().execute_with(|| {
+        let context = XvmContext {
+            id: MOCK_VM_ID,
+            query: true,
+            ..Default::default()
+        };
+        let result = Xvm::xvm_bare_call(context, ALICE, b"send".to_vec(), b"hello".to_vec());
+        assert_eq!(result.unwrap().output(), &0u64.encode()[..]);
+        assert!(query(ALICE).is_empty());
+        assert!(System::events().is_empty());
+
+        assert_ok!(mock_call(b"send"));
+        assert_eq!(query(ALICE).len(), 1);
+    })
+}
+
+#[test]
+fn call_executed_event_carries_callee_logs() {
+    ExternalityBuilder::build().execute_with(|| {
+        assert_ok!(mock_call(b"contract"));
+        System::assert_last_event(mock::RuntimeEvent::Xvm(Event::XvmCallExecuted {
+            caller: ALICE,
+            callee: b"contract".to_vec(),
+            vm_id: MOCK_VM_ID,
+            success: true,
+            consumed_weight: MOCK_CALL_WEIGHT,
+            logs: vec![XvmLog {
+                address: b"contract".to_vec(),
+                topics: Vec::new(),
+                data: b"ball".to_vec(),
+            }],
+        }));
+
+        assert!(mock_call(b"fail").is_err());
+        System::assert_last_event(mock::RuntimeEvent::Xvm(Event::XvmCallExecuted {
+            caller: ALICE,
+            callee: b"fail".to_vec(),
+            vm_id: MOCK_VM_ID,
+            success: false,
+            consumed_weight: MOCK_CALL_WEIGHT,
+            logs: Vec::new(),
+        }));
+    })
+}
+
+#[test]
+fn instantiate_works() {
+    ExternalityBuilder::build().execute_with(|| {
+        let context = XvmContext {
+            id: MOCK_VM_ID,
+            ..Default::default()
+        };
+        assert_ok!(Xvm::xvm_instantiate(
+            RuntimeOrigin::signed(ALICE),
+            context.clone(),
+            b"code".to_vec(),
+            b"args".to_vec(),
+            b"salt".to_vec(),
+        ));
+        assert_eq!(
+            take_mock_vm_calls(),
+            vec![(ALICE, b"code".to_vec(), b"args".to_vec())]
+        );
+        System::assert_has_event(mock::RuntimeEvent::Xvm(Event::XvmContractInstantiated {
+            deployer: ALICE,
+            address: b"codesalt".to_vec(),
+            vm_id: MOCK_VM_ID,
+            consumed_weight: MOCK_CALL_WEIGHT,
+            logs: vec![XvmLog {
+                address: b"codesalt".to_vec(),
+                topics: Vec::new(),
+                data: b"args".to_vec(),
+            }],
+        }));
+        System::assert_last_event(mock::RuntimeEvent::Xvm(Event::XvmInstantiate {
+            result: Ok(b"codesalt".to_vec()),
+        }));
+
+        // failed instantiation deploys nothing
+        System::reset_events();
+        let result =
+            Xvm::xvm_bare_instantiate(context, ALICE, b"fail".to_vec(), Vec::new(), Vec::new());
+        assert_eq!(result.unwrap_err().error(), &XvmError::Reverted(Vec::new()));
+        assert!(System::events().is_empty());
+    })
+}
+
+#[test]
+fn kill_switch_blocks_vm() {
+    ExternalityBuilder::build().execute_with(|| {
+        assert_noop!(
+            Xvm::set_vm_enabled(RuntimeOrigin::signed(ALICE), MOCK_VM_ID, false),
+            BadOrigin
+        );
+        assert_ok!(Xvm::set_vm_enabled(
+            RuntimeOrigin::root(),
+            MOCK_VM_ID,
+            false
+        ));
+        System::assert_last_event(mock::RuntimeEvent::Xvm(Event::VmEnabledSet {
+            vm_id: MOCK_VM_ID,
+            enabled: false,
+        }));
+
+        assert_eq!(
+            mock_call(b"contract").unwrap_err().error(),
+            &XvmError::CallBlocked
+        );
+        let context = XvmContext {
+            id: MOCK_VM_ID,
+            ..Default::default()
+        };
+        assert_eq!(
+            Xvm::xvm_bare_instantiate(context, ALICE, b"code".to_vec(), Vec::new(), Vec::new())
+                .unwrap_err()
+                .error(),
+            &XvmError::CallBlocked
+        );
+        assert!(take_mock_vm_calls().is_empty());
+
+        assert_ok!(Xvm::set_vm_enabled(RuntimeOrigin::root(), MOCK_VM_ID, true));
+        assert_ok!(mock_call(b"contract"));
+    })
+}
+
+#[test]
+fn target_filters_work() {
+    ExternalityBuilder::build().execute_with(|| {
+        let instantiate = || {
+            let context = XvmContext {
+                id: MOCK_VM_ID,
+                ..Default::default()
+            };
+            Xvm::xvm_bare_instantiate(context, ALICE, b"code".to_vec(), Vec::new(), Vec::new())
+        };
+
+        assert_noop!(
+            Xvm::set_target_filter(
+                RuntimeOrigin::signed(ALICE),
+                MOCK_VM_ID,
+                Some(TargetFilter::Allowlist)
+            ),
+            BadOrigin
+        );
+        assert_noop!(
+            Xvm::set_target_listed(
+                RuntimeOrigin::signed(ALICE),
+                MOCK_VM_ID,
+                b"contract".to_vec(),
+                true
+            ),
+            BadOrigin
+        );
+
+        // listing has no effect without a filter
+        assert_ok!(Xvm::set_target_listed(
+            RuntimeOrigin::root(),
+            MOCK_VM_ID,
+            b"contract".to_vec(),
+            true
+        ));
+        System::assert_last_event(mock::RuntimeEvent::Xvm(Event::TargetListedSet {
+            vm_id: MOCK_VM_ID,
+            target: b"contract".to_vec(),
+            listed: true,
+        }));
+        assert_ok!(mock_call(b"contract"));
+        assert_ok!(mock_call(b"other"));
+
+        assert_ok!(Xvm::set_target_filter(
+            RuntimeOrigin::root(),
+            MOCK_VM_ID,
+            Some(TargetFilter::Allowlist)
+        ));
+        System::assert_last_event(mock::RuntimeEvent::Xvm(Event::TargetFilterSet {
+            vm_id: MOCK_VM_ID,
+            filter: Some(TargetFilter::Allowlist),
+        }));
+        assert_ok!(mock_call(b"contract"));
+        assert_eq!(
+            mock_call(b"other").unwrap_err().error(),
+            &XvmError::CallBlocked
+        );
+        // new contracts can't be listed beforehand
+        assert_eq!(instantiate().unwrap_err().error(), &XvmError::CallBlocked);
+        // the dispatchable applies the same checks
+        assert_ok!(Xvm::xvm_call(
+            RuntimeOrigin::signed(ALICE),
+            XvmContext {
+                id: MOCK_VM_ID,
+                ..Default::default()
+            },
+            b"other".to_vec(),
+            Vec::new(),
+        ));
+        System::assert_last_event(mock::RuntimeEvent::Xvm(Event::XvmCall {
+            result: Err(XvmError::CallBlocked),
+        }));
+
+        assert_ok!(Xvm::set_target_filter(
+            RuntimeOrigin::root(),
+            MOCK_VM_ID,
+            Some(TargetFilter::Denylist)
+        ));
+        assert_eq!(
+            mock_call(b"contract").unwrap_err().error(),
+            &XvmError::CallBlocked
+        );
+        assert_ok!(mock_call(b"other"));
+        assert_ok!(instantiate());
+
+        assert_ok!(Xvm::set_target_filter(
+            RuntimeOrigin::root(),
+            MOCK_VM_ID,
+            None
+        ));
+        assert_ok!(mock_call(b"contract"));
+    })
+}
+
+#[test]
+fn legacy_context_layout_decodes() {
+    let legacy = XvmContextV1 {
+        id: 1,
+        max_weight: Weight::from_ref_time(1_000),
+        env: Some(vec![1, 2, 3]),
+    };
+    assert_eq!(
+        XvmContext::decode_versioned(&legacy.encode()),
+        Ok(XvmContext {
+            id: 1,
+            max_weight: Weight::from_ref_time(1_000),
+            env: Some(vec![1, 2, 3]),
+            ..Default::default()
+        })
+    );
+
+    let context = XvmContext {
+        id: 1,
+        value: 10,
+        query: true,
+        typed_input: true,
+        ..Default::default()
+    };
+    assert_eq!(
+        XvmContext::decode_versioned(&context.encode()),
+        Ok(context.clone())
+    );
+
+    let mut trailing = context.encode();
+    trailing.push(0);
+    assert!(XvmContext::decode_versioned(&trailing).is_err());
+}
+
+#[cfg(feature = "evm")]
+mod evm {
+    use crate::{mock_evm::*, *};
+    use frame_support::assert_ok;
+    use pallet_evm::AddressMapping;
+    use pallet_unified_accounts::UnifiedAddressMapper;
+    use sp_core::{H160, H256};
+
+    /// Runtime code returning its caller.
+    const RETURN_CALLER: [u8; 9] = [0x33, 0x60, 0x00, 0x52, 0x60, 0x20, 0x60, 0x00, 0xf3];
+
+    /// Runtime code storing 1 in slot 0.
+    const STORE_ONE: [u8; 6] = [0x60, 0x01, 0x60, 0x00, 0x55, 0x00];
+
+    /// Init code reverting with 42.
+    const REVERT_42: [u8; 10] = [0x60, 0x2a, 0x60, 0x00, 0x52, 0x60, 0x20, 0x60, 0x00, 0xfd];
+
+    /// Runtime code emitting an empty log.
+    const LOG_EMPTY: [u8; 6] = [0x60, 0x00, 0x60, 0x00, 0xa0, 0x00];
+
+    const CONTRACT: H160 = H160::repeat_byte(0xCC);
+
+    const PROXY: H160 = H160::repeat_byte(0xDD);
+
+    /// Runtime code calling the XVM precompile with its call data, returning or reverting
+    /// with the precompile output.
+    fn xvm_proxy() -> Vec<u8> {
+        let mut code = vec![0x36, 0x60, 0x00, 0x60, 0x00, 0x37];
+        code.extend([0x60, 0x00, 0x60, 0x00, 0x36, 0x60, 0x00, 0x60, 0x00, 0x73]);
+        code.extend(XVM_PRECOMPILE.as_bytes());
+        code.extend([
+            0x5a, 0xf1, 0x3d, 0x60, 0x00, 0x60, 0x00, 0x3e, 0x60, 0x33, 0x57,
+        ]);
+        code.extend([0x3d, 0x60, 0x00, 0xfd, 0x5b, 0x3d, 0x60, 0x00, 0xf3]);
+        code
+    }
+
+    fn context(value: u128, query: bool) -> XvmContext {
+        XvmContext {
+            id: EVM_VM_ID,
+            max_weight: Weight::from_ref_time(1_000_000),
+            value,
+            query,
+            ..Default::default()
+        }
+    }
+
+    fn call(from: AccountId, value: u128) -> XvmResult {
+        Xvm::xvm_bare_call(context(value, false), from, CONTRACT.encode(), Vec::new())
+    }
+
+    fn query(from: AccountId, value: u128) -> XvmResult {
+        Xvm::xvm_bare_call(context(value, true), from, CONTRACT.encode(), Vec::new())
+    }
+
+    /// Calls `WASM_CONTRACT`, which calls `CONTRACT` back.
+    fn call_wasm(from: AccountId) -> XvmResult {
+        let context = XvmContext {
+            id: WASM_VM_ID,
+            max_weight: Weight::from_ref_time(1_000_000),
+            ..Default::default()
+        };
+        Xvm::xvm_bare_call(context, from, WASM_CONTRACT.encode(), CONTRACT.encode())
+    }
+
+    /// Runs `call_wasm` from `CONTRACT`, as the XVM precompile does.
+    ///
+    /// Lets the call stack be checked without entering the EVM twice, which it forbids.
+    fn ping_pong() -> XvmResult {
+        Xvm::in_origin_frame(EVM_VM_ID, CONTRACT.encode(), || {
+            call_wasm(UnifiedAccounts::into_account_id(CONTRACT))
+        })
+    }
+
+    #[test]
+    fn evm_caller_is_unified_address() {
+        ExternalityBuilder::build().execute_with(|| {
+            deploy(CONTRACT, RETURN_CALLER.to_vec());
+            let contract_account = UnifiedAccounts::into_account_id(CONTRACT);
+
+            // Unclaimed accounts call from their default address, which backs the value.
+            let default_address = UnifiedAccounts::to_h160_or_default(&BOB);
+            let result = call(BOB, 100).expect("call should succeed");
+            assert_eq!(result.output(), H256::from(default_address).as_bytes());
+            assert_eq!(Balances::free_balance(&BOB), INITIAL_BALANCE - 100);
+            assert_eq!(Balances::free_balance(&contract_account), 100);
+
+            // Claimed addresses map back to the claiming account, value is paid by it.
+            assert_ok!(UnifiedAccounts::claim_default_evm_address(
+                RuntimeOrigin::signed(ALICE)
+            ));
+            let alice_address = UnifiedAccounts::to_h160(&ALICE).expect("address is claimed");
+            assert_eq!(UnifiedAccounts::to_account_id(&alice_address), Some(ALICE));
+            let result = call(ALICE, 50).expect("call should succeed");
+            assert_eq!(result.output(), H256::from(alice_address).as_bytes());
+            assert_eq!(Balances::free_balance(&ALICE), INITIAL_BALANCE - 50);
+            assert_eq!(Balances::free_balance(&contract_account), 150);
+        });
+    }
+
+    #[test]
+    fn evm_wasm_evm_reentrancy_guard_works() {
+        ExternalityBuilder::build().execute_with(|| {
+            deploy(CONTRACT, RETURN_CALLER.to_vec());
+            let wasm_address = UnifiedAccounts::to_h160_or_default(&WASM_CONTRACT);
+
+            // unprotected contracts can be re-entered
+            let result = ping_pong().expect("re-entry should succeed");
+            assert_eq!(result.output(), H256::from(wasm_address).as_bytes());
+
+            assert_ok!(Xvm::set_reentrancy_guard(
+                RuntimeOrigin::root(),
+                EVM_VM_ID,
+                CONTRACT.encode(),
+                true
+            ));
+            assert_eq!(ping_pong().unwrap_err().error(), &XvmError::ReentrantCall);
+
+            // protected contracts can still be called when they aren't on the call stack
+            let result = call_wasm(ALICE).expect("call should succeed");
+            assert_eq!(result.output(), H256::from(wasm_address).as_bytes());
+        });
+    }
+
+    #[test]
+    fn evm_wasm_evm_nested_entry_is_forbidden() {
+        ExternalityBuilder::build().execute_with(|| {
+            deploy(CONTRACT, RETURN_CALLER.to_vec());
+            deploy(PROXY, xvm_proxy());
+
+            // the EVM can't be entered again while it executes the proxy
+            let error =
+                Xvm::xvm_bare_call(context(0, false), ALICE, PROXY.encode(), CONTRACT.encode())
+                    .unwrap_err();
+            assert_eq!(
+                error.error(),
+                &XvmError::Reverted(XvmError::ExecutionError(b"Reentrancy".to_vec()).encode())
+            );
+
+            // the EVM can be called from WASM when it isn't executing
+            let wasm_address = UnifiedAccounts::to_h160_or_default(&WASM_CONTRACT);
+            let result = call_wasm(ALICE).expect("call should succeed");
+            assert_eq!(result.output(), H256::from(wasm_address).as_bytes());
+        });
+    }
+
+    #[test]
+    fn evm_constructor_revert_is_returned() {
+        ExternalityBuilder::build().execute_with(|| {
+            for salt in [Vec::new(), vec![1u8; 32]] {
+                let error = Xvm::xvm_bare_instantiate(
+                    context(0, false),
+                    ALICE,
+                    REVERT_42.to_vec(),
+                    Vec::new(),
+                    salt,
+                )
+                .unwrap_err();
+                // the runner doesn't return the revert data of constructors
+                assert_eq!(error.error(), &XvmError::Reverted(Vec::new()));
+            }
+        });
+    }
+
+    #[test]
+    fn evm_query_is_static() {
+        ExternalityBuilder::build().execute_with(|| {
+            deploy(CONTRACT, RETURN_CALLER.to_vec());
+            let result = query(ALICE, 0).expect("query should succeed");
+            let alice_address = UnifiedAccounts::to_h160_or_default(&ALICE);
+            assert_eq!(result.output(), H256::from(alice_address).as_bytes());
+            // queries leave no trace
+            assert!(System::events().is_empty());
+
+            // static calls can't carry value
+            assert_eq!(
+                query(ALICE, 1).unwrap_err().error(),
+                &XvmError::ExecutionError(b"ValueInStaticCall".to_vec())
+            );
+            assert_eq!(Balances::free_balance(&ALICE), INITIAL_BALANCE);
+
+            // state changes of the query are discarded
+            deploy(CONTRACT, STORE_ONE.to_vec());
+            assert_ok!(query(ALICE, 0));
+            assert!(System::events().is_empty());
+            assert_eq!(
+                pallet_evm::AccountStorages::<TestRuntime>::get(CONTRACT, H256::zero()),
+                H256::zero()
+            );
+
+            assert_ok!(call(ALICE, 0));
+            assert_eq!(
+                pallet_evm::AccountStorages::<TestRuntime>::get(CONTRACT, H256::zero()),
+                H256::from_low_u64_be(1)
+            );
+
+            // logs fail the query
+            deploy(CONTRACT, LOG_EMPTY.to_vec());
+            assert_eq!(
+                query(ALICE, 0).unwrap_err().error(),
+                &XvmError::ExecutionError(b"StateChangeInStaticCall".to_vec())
+            );
+            assert!(System::events().is_empty());
+        });
+    }
+}
@@ -20,10 +20,12 @@
 
 use crate::*;
 use frame_support::traits::Currency;
+use pallet_contracts_primitives::Code;
 use parity_scale_codec::HasCompact;
 use scale_info::TypeInfo;
 use sp_runtime::traits::Get;
 use sp_runtime::traits::StaticLookup;
+use sp_runtime::DispatchError;
 use sp_std::fmt::Debug;
 pub struct WASM<I, T>(sp_std::marker::PhantomData<(I, T)>);
 
@@ -34,8 +36,10 @@ type BalanceOf<T> = <<T as pallet_contracts::Config>::Currency as Currency<
 impl<I, T> SyncVM<T::AccountId> for WASM<I, T>
 where
     I: Get<VmId>,
-    T: pallet_contracts::Config + frame_system::Config,
+    T: pallet_contracts::Config + Config,
     <BalanceOf<T> as HasCompact>::Type: Clone + Eq + PartialEq + Debug + TypeInfo + Encode,
+    <T as frame_system::Config>::RuntimeEvent: TryInto<pallet_contracts::Event<T>>,
+    T::Hash: Into<H256>,
 {
     fn id() -> VmId {
         I::get()
@@ -44,26 +48,39 @@ where
     fn xvm_call(context: XvmContext, from: T::AccountId, to: Vec<u8>, input: Vec<u8>) -> XvmResult {
         log::trace!(
             target: "xvm::WASM::xvm_call",
-            "Start WASM XVM: {:?}, {:?}, {:?}",
-            from, to, input,
+            "Start WASM XVM: {:?}, {:?}, {:?}, {:?}",
+            from, to, input, context.value,
         );
-        let gas_limit = context.max_weight;
+        let gas_limit = context
+            .max_weight
+            .saturating_sub(max_contract_logs_weight::<T>());
         log::trace!(
             target: "xvm::WASM::xvm_call",
             "WASM xvm call gas (weight) limit: {:?}", gas_limit);
         let dest = Decode::decode(&mut to.as_ref()).map_err(|_| XvmCallError {
             error: XvmError::EncodingFailure,
-            consumed_weight: PLACEHOLDER_WEIGHT,
+            consumed_weight: Weight::zero(),
+        })?;
+        let input = abi::prepare_input(&context, input, abi::TargetAbi::Ink).map_err(|error| {
+            XvmCallError {
+                error,
+                consumed_weight: Weight::zero(),
+            }
         })?;
 
-        let dest = T::Lookup::lookup(dest).map_err(|error| XvmCallError {
-            error: XvmError::ExecutionError(Into::<&str>::into(error).into()),
-            consumed_weight: PLACEHOLDER_WEIGHT,
+        let dest = T::Lookup::lookup(dest).map_err(|_| XvmCallError {
+            error: XvmError::InvalidTarget,
+            consumed_weight: Weight::zero(),
+        })?;
+        let value: BalanceOf<T> = context.value.try_into().map_err(|_| XvmCallError {
+            error: XvmError::EncodingFailure,
+            consumed_weight: Weight::zero(),
         })?;
+        let event_count = frame_system::Pallet::<T>::event_count();
         let call_result = pallet_contracts::Pallet::<T>::bare_call(
             from, // no need to check origin, we consider it signed here
             dest,
-            Default::default(),
+            value,
             gas_limit.into(),
             None,
             input,
@@ -76,18 +93,154 @@ where
             "WASM XVM call result: {:?}", call_result
         );
 
-        let consumed_weight = call_result.gas_consumed.ref_time();
+        let consumed_weight = call_result.gas_consumed;
 
         match call_result.result {
-            Ok(success) => Ok(XvmCallOk {
-                output: success.data,
+            Ok(success) if success.did_revert() => Err(XvmCallError {
+                error: XvmError::Reverted(success.data),
                 consumed_weight,
             }),
 
+            Ok(success) => {
+                let (logs, logs_weight) = contract_logs::<T>(event_count);
+                Ok(XvmCallOk {
+                    output: success.data,
+                    consumed_weight: consumed_weight.saturating_add(logs_weight),
+                    logs,
+                })
+            }
+
             Err(error) => Err(XvmCallError {
-                error: XvmError::ExecutionError(Into::<&str>::into(error).into()),
+                error: contracts_error::<T>(error),
                 consumed_weight,
             }),
         }
     }
+
+    fn xvm_instantiate(
+        context: XvmContext,
+        from: T::AccountId,
+        code: Vec<u8>,
+        input: Vec<u8>,
+        salt: Vec<u8>,
+    ) -> XvmResult {
+        log::trace!(
+            target: "xvm::WASM::xvm_instantiate",
+            "Start WASM XVM instantiate: {:?}, {:?}, {:?}, {:?}, {:?}",
+            from, code, input, salt, context.value,
+        );
+        let input = abi::prepare_input(&context, input, abi::TargetAbi::Ink).map_err(|error| {
+            XvmCallError {
+                error,
+                consumed_weight: Weight::zero(),
+            }
+        })?;
+        let value: BalanceOf<T> = context.value.try_into().map_err(|_| XvmCallError {
+            error: XvmError::EncodingFailure,
+            consumed_weight: Weight::zero(),
+        })?;
+        let event_count = frame_system::Pallet::<T>::event_count();
+        let instantiate_result = pallet_contracts::Pallet::<T>::bare_instantiate(
+            from, // no need to check origin, we consider it signed here
+            value,
+            context
+                .max_weight
+                .saturating_sub(max_contract_logs_weight::<T>()),
+            None,
+            Code::Upload(code),
+            input,
+            salt,
+            false,
+        );
+
+        log::trace!(
+            target: "xvm::WASM::xvm_instantiate",
+            "WASM XVM instantiate result: {:?}", instantiate_result
+        );
+
+        let consumed_weight = instantiate_result.gas_consumed;
+
+        match instantiate_result.result {
+            Ok(success) if success.result.did_revert() => Err(XvmCallError {
+                error: XvmError::Reverted(success.result.data),
+                consumed_weight,
+            }),
+
+            Ok(success) => {
+                let (logs, logs_weight) = contract_logs::<T>(event_count);
+                Ok(XvmCallOk {
+                    output: success.account_id.encode(),
+                    consumed_weight: consumed_weight.saturating_add(logs_weight),
+                    logs,
+                })
+            }
+
+            Err(error) => Err(XvmCallError {
+                error: contracts_error::<T>(error),
+                consumed_weight,
+            }),
+        }
+    }
+}
+
+/// Weight reserved out of the call's limit for collecting the logs of the contract.
+fn max_contract_logs_weight<T: Config>() -> Weight {
+    <T as Config>::WeightInfo::contract_logs(T::MaxScannedEvents::get())
+}
+
+/// Maps the `ContractEmitted` events deposited after the first `skip` events of the block
+/// onto XVM logs, contracts called by the callee included, along with the weight of reading them.
+///
+/// Reading them decodes all of the block's events, so no logs are collected once the block
+/// holds more than `MaxScannedEvents` events.
+fn contract_logs<T>(skip: u32) -> (Vec<XvmLog>, Weight)
+where
+    T: pallet_contracts::Config + Config,
+    <T as frame_system::Config>::RuntimeEvent: TryInto<pallet_contracts::Event<T>>,
+    T::Hash: Into<H256>,
+{
+    let events = frame_system::Pallet::<T>::event_count();
+    if events > T::MaxScannedEvents::get() {
+        log::warn!(
+            target: "xvm::WASM",
+            "Block holds {} events, contract logs aren't collected", events
+        );
+        return (Vec::new(), Weight::zero());
+    }
+
+    let logs = frame_system::Pallet::<T>::read_events_no_consensus()
+        .skip(skip as usize)
+        .filter_map(|record| {
+            let record = *record;
+            match record.event.try_into() {
+                Ok(pallet_contracts::Event::<T>::ContractEmitted { contract, data }) => {
+                    Some(XvmLog {
+                        address: contract.encode(),
+                        topics: record.topics.into_iter().map(Into::into).collect(),
+                        data,
+                    })
+                }
+                _ => None,
+            }
+        })
+        .collect();
+
+    (logs, <T as Config>::WeightInfo::contract_logs(events))
+}
+
+/// Maps `pallet-contracts` call errors onto XVM errors.
+fn contracts_error<T: pallet_contracts::Config>(error: DispatchError) -> XvmError {
+    use pallet_contracts::Error;
+
+    if error == Error::<T>::OutOfGas.into() {
+        XvmError::OutOfGas
+    } else if error == Error::<T>::ContractNotFound.into()
+        || error == Error::<T>::CodeNotFound.into()
+    {
+        XvmError::InvalidTarget
+    } else if error == Error::<T>::DecodingFailed.into() {
+        XvmError::EncodingFailure
+    } else {
+        XvmError::ExecutionError(Into::<&str>::into(error).into())
+    }
 }
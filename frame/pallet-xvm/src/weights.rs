@@ -0,0 +1,277 @@
+// This file is part of Astar.
+
+// Copyright (C) 2019-2023 Stake Technologies Pte.Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// Astar is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Astar is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Astar. If not, see <http://www.gnu.org/licenses/>.
+
+//! Weights for pallet_xvm
+//!
+//! The pallet hasn't been benchmarked on reference hardware yet. Storage accesses follow the code,
+//! execution times and proof sizes are placeholders to be replaced by the output of the `benchmark pallet`
+//! command of the collator, using `--pallet=pallet_xvm --extrinsic=*` and
+//! `./scripts/templates/weight-template.hbs`.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for pallet_xvm.
+pub trait WeightInfo {
+	fn xvm_call() -> Weight;
+	fn xvm_send() -> Weight;
+	fn xvm_query() -> Weight;
+	fn xvm_ack(n: u32, ) -> Weight;
+	fn enqueue_message() -> Weight;
+	fn query_inbox() -> Weight;
+	fn deliver_messages(n: u32, ) -> Weight;
+	fn set_reentrancy_guard() -> Weight;
+	fn xvm_instantiate() -> Weight;
+	fn set_vm_enabled() -> Weight;
+	fn set_target_filter() -> Weight;
+	fn set_target_listed() -> Weight;
+	fn contract_logs(e: u32, ) -> Weight;
+}
+
+/// Weights for pallet_xvm using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	// Storage: Xvm DisabledVms (r:1 w:0)
+	// Proof Skipped: Xvm DisabledVms (max_values: None, max_size: None, mode: Measured)
+	// Storage: Xvm TargetFilters (r:1 w:0)
+	// Proof Skipped: Xvm TargetFilters (max_values: None, max_size: None, mode: Measured)
+	// Storage: Xvm ListedTargets (r:1 w:0)
+	// Proof Skipped: Xvm ListedTargets (max_values: None, max_size: None, mode: Measured)
+	fn xvm_call() -> Weight {
+		Weight::from_ref_time(15_231_000)
+			.saturating_add(Weight::from_proof_size(6476))
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+	}
+	fn xvm_send() -> Weight {
+		Weight::from_ref_time(10_021_000)
+			.saturating_add(Weight::from_proof_size(0))
+	}
+	fn xvm_query() -> Weight {
+		Weight::from_ref_time(9_347_000)
+			.saturating_add(Weight::from_proof_size(0))
+	}
+	// Storage: Xvm Inboxes (r:1 w:1)
+	// Proof Skipped: Xvm Inboxes (max_values: None, max_size: None, mode: Measured)
+	/// The range of component `n` is `[1, 100]`.
+	fn xvm_ack(n: u32, ) -> Weight {
+		Weight::from_ref_time(14_938_117)
+			.saturating_add(Weight::from_proof_size(2705))
+			.saturating_add(Weight::from_ref_time(1_127_484).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+			.saturating_add(Weight::from_proof_size(1123).saturating_mul(n.into()))
+	}
+	// Storage: Xvm NextMessageId (r:1 w:1)
+	// Proof Skipped: Xvm NextMessageId (max_values: Some(1), max_size: None, mode: Measured)
+	// Storage: Xvm Inboxes (r:1 w:1)
+	// Proof Skipped: Xvm Inboxes (max_values: None, max_size: None, mode: Measured)
+	// Storage: Xvm DeliveryQueue (r:1 w:1)
+	// Proof Skipped: Xvm DeliveryQueue (max_values: None, max_size: None, mode: Measured)
+	fn enqueue_message() -> Weight {
+		Weight::from_ref_time(131_482_000)
+			.saturating_add(Weight::from_proof_size(121964))
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+	// Storage: Xvm Inboxes (r:1 w:0)
+	// Proof Skipped: Xvm Inboxes (max_values: None, max_size: None, mode: Measured)
+	fn query_inbox() -> Weight {
+		Weight::from_ref_time(98_735_000)
+			.saturating_add(Weight::from_proof_size(115023))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+	}
+	// Storage: Xvm DeliveryQueue (r:1 w:1)
+	// Proof Skipped: Xvm DeliveryQueue (max_values: None, max_size: None, mode: Measured)
+	// Storage: Xvm Inboxes (r:100 w:0)
+	// Proof Skipped: Xvm Inboxes (max_values: None, max_size: None, mode: Measured)
+	/// The range of component `n` is `[0, 100]`.
+	fn deliver_messages(n: u32, ) -> Weight {
+		Weight::from_ref_time(6_281_540)
+			.saturating_add(Weight::from_proof_size(1551))
+			.saturating_add(Weight::from_ref_time(7_985_212).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+			.saturating_add(Weight::from_proof_size(2593).saturating_mul(n.into()))
+	}
+	// Storage: Xvm ReentrancyGuards (r:0 w:1)
+	// Proof Skipped: Xvm ReentrancyGuards (max_values: None, max_size: None, mode: Measured)
+	fn set_reentrancy_guard() -> Weight {
+		Weight::from_ref_time(8_958_000)
+			.saturating_add(Weight::from_proof_size(0))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	// Storage: Xvm DisabledVms (r:1 w:0)
+	// Proof Skipped: Xvm DisabledVms (max_values: None, max_size: None, mode: Measured)
+	// Storage: Xvm TargetFilters (r:1 w:0)
+	// Proof Skipped: Xvm TargetFilters (max_values: None, max_size: None, mode: Measured)
+	fn xvm_instantiate() -> Weight {
+		Weight::from_ref_time(12_918_000)
+			.saturating_add(Weight::from_proof_size(2890))
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+	}
+	// Storage: Xvm DisabledVms (r:0 w:1)
+	// Proof Skipped: Xvm DisabledVms (max_values: None, max_size: None, mode: Measured)
+	fn set_vm_enabled() -> Weight {
+		Weight::from_ref_time(8_203_000)
+			.saturating_add(Weight::from_proof_size(0))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	// Storage: Xvm TargetFilters (r:0 w:1)
+	// Proof Skipped: Xvm TargetFilters (max_values: None, max_size: None, mode: Measured)
+	fn set_target_filter() -> Weight {
+		Weight::from_ref_time(8_311_000)
+			.saturating_add(Weight::from_proof_size(0))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	// Storage: Xvm ListedTargets (r:0 w:1)
+	// Proof Skipped: Xvm ListedTargets (max_values: None, max_size: None, mode: Measured)
+	fn set_target_listed() -> Weight {
+		Weight::from_ref_time(8_872_000)
+			.saturating_add(Weight::from_proof_size(0))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	// Storage: System EventCount (r:1 w:0)
+	// Proof: System EventCount (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	// Storage: System Events (r:1 w:0)
+	// Proof Skipped: System Events (max_values: Some(1), max_size: None, mode: Measured)
+	fn contract_logs(e: u32, ) -> Weight {
+		Weight::from_ref_time(3_000_000)
+			.saturating_add(Weight::from_ref_time(2_000_000).saturating_mul(e.into()))
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(Weight::from_proof_size(2048).saturating_mul(e.into()))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	// Storage: Xvm DisabledVms (r:1 w:0)
+	// Proof Skipped: Xvm DisabledVms (max_values: None, max_size: None, mode: Measured)
+	// Storage: Xvm TargetFilters (r:1 w:0)
+	// Proof Skipped: Xvm TargetFilters (max_values: None, max_size: None, mode: Measured)
+	// Storage: Xvm ListedTargets (r:1 w:0)
+	// Proof Skipped: Xvm ListedTargets (max_values: None, max_size: None, mode: Measured)
+	fn xvm_call() -> Weight {
+		Weight::from_ref_time(15_231_000)
+			.saturating_add(Weight::from_proof_size(6476))
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+	}
+	fn xvm_send() -> Weight {
+		Weight::from_ref_time(10_021_000)
+			.saturating_add(Weight::from_proof_size(0))
+	}
+	fn xvm_query() -> Weight {
+		Weight::from_ref_time(9_347_000)
+			.saturating_add(Weight::from_proof_size(0))
+	}
+	// Storage: Xvm Inboxes (r:1 w:1)
+	// Proof Skipped: Xvm Inboxes (max_values: None, max_size: None, mode: Measured)
+	/// The range of component `n` is `[1, 100]`.
+	fn xvm_ack(n: u32, ) -> Weight {
+		Weight::from_ref_time(14_938_117)
+			.saturating_add(Weight::from_proof_size(2705))
+			.saturating_add(Weight::from_ref_time(1_127_484).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+			.saturating_add(Weight::from_proof_size(1123).saturating_mul(n.into()))
+	}
+	// Storage: Xvm NextMessageId (r:1 w:1)
+	// Proof Skipped: Xvm NextMessageId (max_values: Some(1), max_size: None, mode: Measured)
+	// Storage: Xvm Inboxes (r:1 w:1)
+	// Proof Skipped: Xvm Inboxes (max_values: None, max_size: None, mode: Measured)
+	// Storage: Xvm DeliveryQueue (r:1 w:1)
+	// Proof Skipped: Xvm DeliveryQueue (max_values: None, max_size: None, mode: Measured)
+	fn enqueue_message() -> Weight {
+		Weight::from_ref_time(131_482_000)
+			.saturating_add(Weight::from_proof_size(121964))
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
+	// Storage: Xvm Inboxes (r:1 w:0)
+	// Proof Skipped: Xvm Inboxes (max_values: None, max_size: None, mode: Measured)
+	fn query_inbox() -> Weight {
+		Weight::from_ref_time(98_735_000)
+			.saturating_add(Weight::from_proof_size(115023))
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+	}
+	// Storage: Xvm DeliveryQueue (r:1 w:1)
+	// Proof Skipped: Xvm DeliveryQueue (max_values: None, max_size: None, mode: Measured)
+	// Storage: Xvm Inboxes (r:100 w:0)
+	// Proof Skipped: Xvm Inboxes (max_values: None, max_size: None, mode: Measured)
+	/// The range of component `n` is `[0, 100]`.
+	fn deliver_messages(n: u32, ) -> Weight {
+		Weight::from_ref_time(6_281_540)
+			.saturating_add(Weight::from_proof_size(1551))
+			.saturating_add(Weight::from_ref_time(7_985_212).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().reads((1_u64).saturating_mul(n.into())))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+			.saturating_add(Weight::from_proof_size(2593).saturating_mul(n.into()))
+	}
+	// Storage: Xvm ReentrancyGuards (r:0 w:1)
+	// Proof Skipped: Xvm ReentrancyGuards (max_values: None, max_size: None, mode: Measured)
+	fn set_reentrancy_guard() -> Weight {
+		Weight::from_ref_time(8_958_000)
+			.saturating_add(Weight::from_proof_size(0))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	// Storage: Xvm DisabledVms (r:1 w:0)
+	// Proof Skipped: Xvm DisabledVms (max_values: None, max_size: None, mode: Measured)
+	// Storage: Xvm TargetFilters (r:1 w:0)
+	// Proof Skipped: Xvm TargetFilters (max_values: None, max_size: None, mode: Measured)
+	fn xvm_instantiate() -> Weight {
+		Weight::from_ref_time(12_918_000)
+			.saturating_add(Weight::from_proof_size(2890))
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+	}
+	// Storage: Xvm DisabledVms (r:0 w:1)
+	// Proof Skipped: Xvm DisabledVms (max_values: None, max_size: None, mode: Measured)
+	fn set_vm_enabled() -> Weight {
+		Weight::from_ref_time(8_203_000)
+			.saturating_add(Weight::from_proof_size(0))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	// Storage: Xvm TargetFilters (r:0 w:1)
+	// Proof Skipped: Xvm TargetFilters (max_values: None, max_size: None, mode: Measured)
+	fn set_target_filter() -> Weight {
+		Weight::from_ref_time(8_311_000)
+			.saturating_add(Weight::from_proof_size(0))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	// Storage: Xvm ListedTargets (r:0 w:1)
+	// Proof Skipped: Xvm ListedTargets (max_values: None, max_size: None, mode: Measured)
+	fn set_target_listed() -> Weight {
+		Weight::from_ref_time(8_872_000)
+			.saturating_add(Weight::from_proof_size(0))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	// Storage: System EventCount (r:1 w:0)
+	// Proof: System EventCount (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	// Storage: System Events (r:1 w:0)
+	// Proof Skipped: System Events (max_values: Some(1), max_size: None, mode: Measured)
+	fn contract_logs(e: u32, ) -> Weight {
+		Weight::from_ref_time(3_000_000)
+			.saturating_add(Weight::from_ref_time(2_000_000).saturating_mul(e.into()))
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(Weight::from_proof_size(2048).saturating_mul(e.into()))
+	}
+}
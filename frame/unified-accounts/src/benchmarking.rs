@@ -0,0 +1,87 @@
+// This file is part of Astar.
+
+// Copyright (C) 2019-2023 Stake Technologies Pte.Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// Astar is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Astar is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Astar. If not, see <http://www.gnu.org/licenses/>.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+use crate::Pallet as UnifiedAccounts;
+
+use frame_benchmarking::{benchmarks, impl_benchmark_test_suite, whitelisted_caller};
+use frame_system::RawOrigin;
+
+/// EVM address of the `secret` key, along with its claim signature for `account_id`.
+fn sign_claim<T: Config>(account_id: &T::AccountId, secret: &[u8; 32]) -> (H160, EvmSignature) {
+    let secret = libsecp256k1::SecretKey::parse(secret).expect("valid secret key");
+    let public = libsecp256k1::PublicKey::from_secret_key(&secret);
+    let evm_address = H160::from_slice(&keccak_256(&public.serialize()[1..])[12..]);
+
+    let message =
+        libsecp256k1::Message::parse(&UnifiedAccounts::<T>::claim_signing_hash(account_id));
+    let (signature, recovery_id) = libsecp256k1::sign(&message, &secret);
+    let mut evm_signature = [0u8; 65];
+    evm_signature[..64].copy_from_slice(&signature.serialize());
+    evm_signature[64] = recovery_id.serialize();
+
+    (evm_address, evm_signature)
+}
+
+/// Funds the default account of `evm_address`, to be moved by its claim.
+fn fund_default_account<T: Config>(evm_address: H160) {
+    let default_account = T::DefaultEvmToNative::into_account_id(evm_address);
+    let funds = T::Currency::minimum_balance() * 100u32.into();
+    T::Currency::make_free_balance_be(&default_account, funds);
+}
+
+benchmarks! {
+
+    claim_evm_address {
+        let caller: T::AccountId = whitelisted_caller();
+        let (evm_address, signature) = sign_claim::<T>(&caller, &keccak_256(b"Alice"));
+        fund_default_account::<T>(evm_address);
+
+    }: _(RawOrigin::Signed(caller.clone()), evm_address, signature)
+    verify {
+        assert_eq!(NativeToEvm::<T>::get(&caller), Some(evm_address));
+    }
+
+    claim_default_evm_address {
+        let caller: T::AccountId = whitelisted_caller();
+        fund_default_account::<T>(T::DefaultNativeToEvm::into_h160(&caller));
+
+    }: _(RawOrigin::Signed(caller.clone()))
+    verify {
+        assert!(NativeToEvm::<T>::contains_key(&caller));
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mock;
+    use sp_io::TestExternalities;
+
+    pub fn new_test_ext() -> TestExternalities {
+        mock::ExternalityBuilder::build()
+    }
+}
+
+impl_benchmark_test_suite!(
+    UnifiedAccounts,
+    crate::benchmarking::tests::new_test_ext(),
+    crate::mock::TestRuntime
+);
@@ -0,0 +1,313 @@
+// This file is part of Astar.
+
+// Copyright (C) 2019-2023 Stake Technologies Pte.Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// Astar is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Astar is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Astar. If not, see <http://www.gnu.org/licenses/>.
+
+//! # Unified Accounts Pallet
+//!
+//! ## Overview
+//!
+//! This pallet binds native accounts and EVM addresses, so that both VMs refer to the same
+//! account of a user.
+//!
+//! A binding is established by claiming it. The owner of an EVM address proves its ownership
+//! by signing an EIP-712 `Claim(bytes substrateAddress)` message, the domain of the signature
+//! binding it to this chain. Accounts which don't want to manage an EVM key can claim their
+//! default EVM address instead. Each account and each EVM address can be claimed only once.
+//! The free balance of the default account of a claimed EVM address is moved to the claiming
+//! account, as that account is no longer reachable from the EVM.
+//!
+//! Unclaimed accounts and addresses are mapped by the configured default mappings, these
+//! mappings aren't reversible.
+//!
+//! ## Interface
+//!
+//! ### Dispatchable Function
+//!
+//! - `claim_evm_address` - binds the origin to an EVM address, proven by its signature
+//! - `claim_default_evm_address` - binds the origin to its default EVM address
+//!
+//! ### Other
+//!
+//! `UnifiedAddressMapper` interface for lookups in both directions
+//! - `to_h160` / `to_h160_or_default`
+//! - `to_account_id` / `to_account_id_or_default`
+//!
+//! The pallet implements `pallet_evm::AddressMapping`, so that it can be used as the EVM
+//! address mapping of the runtime.
+//!
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use frame_support::{
+    pallet,
+    traits::{Currency, ExistenceRequirement},
+};
+use pallet_evm::AddressMapping;
+use parity_scale_codec::Encode;
+use sp_core::{Hasher, H160, H256, U256};
+use sp_io::hashing::keccak_256;
+use sp_runtime::{traits::Zero, AccountId32};
+use sp_std::{marker::PhantomData, prelude::*};
+
+pub use pallet::*;
+
+#[cfg(any(test, feature = "runtime-benchmarks"))]
+mod benchmarking;
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+pub mod weights;
+pub use weights::WeightInfo;
+
+/// Ethereum signature of a claim, `r ‖ s ‖ v`.
+pub type EvmSignature = [u8; 65];
+
+/// EIP-712 domain type of claim signatures.
+pub const EIP712_DOMAIN_TYPE: &[u8] =
+    b"EIP712Domain(string name,string version,uint256 chainId,bytes32 salt)";
+
+/// EIP-712 type of a claim.
+pub const EIP712_CLAIM_TYPE: &[u8] = b"Claim(bytes substrateAddress)";
+
+/// EIP-712 domain name of claim signatures.
+pub const EIP712_DOMAIN_NAME: &[u8] = b"Astar EVM Claim";
+
+/// EIP-712 domain version of claim signatures.
+pub const EIP712_DOMAIN_VERSION: &[u8] = b"1";
+
+/// Lookups between native accounts and EVM addresses.
+pub trait UnifiedAddressMapper<AccountId> {
+    /// EVM address claimed by `account_id`.
+    fn to_h160(account_id: &AccountId) -> Option<H160>;
+
+    /// EVM address of `account_id`, the default one if it hasn't claimed any.
+    fn to_h160_or_default(account_id: &AccountId) -> H160;
+
+    /// Account which claimed `evm_address`.
+    fn to_account_id(evm_address: &H160) -> Option<AccountId>;
+
+    /// Account of `evm_address`, the default one if it hasn't been claimed.
+    fn to_account_id_or_default(evm_address: &H160) -> AccountId;
+}
+
+/// Default mapping of native accounts to EVM addresses.
+pub trait DefaultNativeToEvm<AccountId> {
+    /// EVM address of an account which hasn't claimed any.
+    fn into_h160(account_id: &AccountId) -> H160;
+}
+
+/// Maps an account to the first 20 bytes of its hash.
+pub struct HashedAccountMapping<H>(PhantomData<H>);
+
+impl<H: Hasher<Out = H256>> DefaultNativeToEvm<AccountId32> for HashedAccountMapping<H> {
+    fn into_h160(account_id: &AccountId32) -> H160 {
+        let account_id: &[u8] = account_id.as_ref();
+        H160::from_slice(&H::hash(account_id)[0..20])
+    }
+}
+
+#[pallet]
+pub mod pallet {
+
+    use super::*;
+    use frame_support::pallet_prelude::*;
+    use frame_system::pallet_prelude::*;
+
+    #[pallet::pallet]
+    #[pallet::without_storage_info]
+    pub struct Pallet<T>(PhantomData<T>);
+
+    #[pallet::config]
+    pub trait Config: frame_system::Config {
+        type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+        /// Mapping of unclaimed EVM addresses to native accounts.
+        type DefaultEvmToNative: AddressMapping<Self::AccountId>;
+
+        /// Mapping of native accounts to EVM addresses, when they haven't claimed any.
+        type DefaultNativeToEvm: DefaultNativeToEvm<Self::AccountId>;
+
+        /// Currency of the default accounts of claimed EVM addresses.
+        type Currency: Currency<Self::AccountId>;
+
+        /// EVM chain Id, part of the claim signature domain.
+        #[pallet::constant]
+        type ChainId: Get<u64>;
+
+        /// Weight information for extrinsics in this pallet.
+        type WeightInfo: WeightInfo;
+    }
+
+    #[pallet::error]
+    pub enum Error<T> {
+        /// Account has already claimed an EVM address.
+        AlreadyMapped,
+        /// EVM address has already been claimed.
+        EvmAddressAlreadyMapped,
+        /// Signature isn't a claim of the origin signed by the EVM address.
+        InvalidSignature,
+    }
+
+    #[pallet::event]
+    #[pallet::generate_deposit(pub(crate) fn deposit_event)]
+    pub enum Event<T: Config> {
+        /// Account claimed an EVM address.
+        AccountClaimed {
+            account_id: T::AccountId,
+            evm_address: H160,
+        },
+    }
+
+    /// Native accounts of claimed EVM addresses.
+    #[pallet::storage]
+    pub type EvmToNative<T: Config> = StorageMap<_, Blake2_128Concat, H160, T::AccountId>;
+
+    /// EVM addresses claimed by native accounts.
+    #[pallet::storage]
+    pub type NativeToEvm<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, H160>;
+
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        /// Claim `evm_address` for the origin account.
+        ///
+        /// `signature` is the EIP-712 signature of the claim by `evm_address`, see
+        /// [`Pallet::claim_signing_hash`].
+        #[pallet::call_index(0)]
+        #[pallet::weight(T::WeightInfo::claim_evm_address())]
+        pub fn claim_evm_address(
+            origin: OriginFor<T>,
+            evm_address: H160,
+            signature: EvmSignature,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            ensure!(
+                Self::recover_signer(&who, &signature) == Some(evm_address),
+                Error::<T>::InvalidSignature
+            );
+
+            Self::add_mapping(who, evm_address)
+        }
+
+        /// Claim the default EVM address of the origin account.
+        #[pallet::call_index(1)]
+        #[pallet::weight(T::WeightInfo::claim_default_evm_address())]
+        pub fn claim_default_evm_address(origin: OriginFor<T>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let evm_address = T::DefaultNativeToEvm::into_h160(&who);
+
+            Self::add_mapping(who, evm_address)
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        /// Binds `account_id` and `evm_address`, if neither of them is bound yet, moving the
+        /// free balance of the default account of `evm_address` to `account_id`.
+        fn add_mapping(account_id: T::AccountId, evm_address: H160) -> DispatchResult {
+            ensure!(
+                !NativeToEvm::<T>::contains_key(&account_id),
+                Error::<T>::AlreadyMapped
+            );
+            ensure!(
+                !EvmToNative::<T>::contains_key(evm_address),
+                Error::<T>::EvmAddressAlreadyMapped
+            );
+
+            // The default account is no longer reachable from the EVM once the address is claimed.
+            let default_account = T::DefaultEvmToNative::into_account_id(evm_address);
+            let funds = T::Currency::free_balance(&default_account);
+            if default_account != account_id && !funds.is_zero() {
+                T::Currency::transfer(
+                    &default_account,
+                    &account_id,
+                    funds,
+                    ExistenceRequirement::AllowDeath,
+                )?;
+            }
+
+            EvmToNative::<T>::insert(evm_address, &account_id);
+            NativeToEvm::<T>::insert(&account_id, evm_address);
+
+            Self::deposit_event(Event::<T>::AccountClaimed {
+                account_id,
+                evm_address,
+            });
+            Ok(())
+        }
+
+        /// EIP-712 hash signed by an EVM address to be claimed by `account_id`, i.e.
+        /// `keccak256("\x19\x01" ‖ domainSeparator ‖ hashStruct(Claim))`.
+        ///
+        /// The domain salt is the genesis hash of the chain.
+        pub fn claim_signing_hash(account_id: &T::AccountId) -> [u8; 32] {
+            let mut chain_id = [0u8; 32];
+            U256::from(T::ChainId::get()).to_big_endian(&mut chain_id);
+            let genesis_hash = frame_system::Pallet::<T>::block_hash(T::BlockNumber::zero());
+
+            let mut domain = keccak_256(EIP712_DOMAIN_TYPE).to_vec();
+            domain.extend_from_slice(&keccak_256(EIP712_DOMAIN_NAME));
+            domain.extend_from_slice(&keccak_256(EIP712_DOMAIN_VERSION));
+            domain.extend_from_slice(&chain_id);
+            domain.extend_from_slice(genesis_hash.as_ref());
+
+            let mut claim = keccak_256(EIP712_CLAIM_TYPE).to_vec();
+            claim.extend_from_slice(&keccak_256(&account_id.encode()));
+
+            let mut message = b"\x19\x01".to_vec();
+            message.extend_from_slice(&keccak_256(&domain));
+            message.extend_from_slice(&keccak_256(&claim));
+            keccak_256(&message)
+        }
+
+        /// EVM address which signed the claim of `account_id`.
+        pub fn recover_signer(account_id: &T::AccountId, signature: &EvmSignature) -> Option<H160> {
+            let hash = Self::claim_signing_hash(account_id);
+            sp_io::crypto::secp256k1_ecdsa_recover(signature, &hash)
+                .ok()
+                .map(|public| H160::from_slice(&keccak_256(&public)[12..]))
+        }
+    }
+}
+
+impl<T: Config> UnifiedAddressMapper<T::AccountId> for Pallet<T> {
+    fn to_h160(account_id: &T::AccountId) -> Option<H160> {
+        NativeToEvm::<T>::get(account_id)
+    }
+
+    fn to_h160_or_default(account_id: &T::AccountId) -> H160 {
+        Self::to_h160(account_id).unwrap_or_else(|| T::DefaultNativeToEvm::into_h160(account_id))
+    }
+
+    fn to_account_id(evm_address: &H160) -> Option<T::AccountId> {
+        EvmToNative::<T>::get(evm_address)
+    }
+
+    fn to_account_id_or_default(evm_address: &H160) -> T::AccountId {
+        Self::to_account_id(evm_address)
+            .unwrap_or_else(|| T::DefaultEvmToNative::into_account_id(*evm_address))
+    }
+}
+
+/// Claimed addresses map to their account, others to their default account.
+impl<T: Config> AddressMapping<T::AccountId> for Pallet<T> {
+    fn into_account_id(address: H160) -> T::AccountId {
+        Self::to_account_id_or_default(&address)
+    }
+}
@@ -0,0 +1,214 @@
+// This file is part of Astar.
+
+// Copyright (C) 2019-2023 Stake Technologies Pte.Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// Astar is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Astar is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Astar. If not, see <http://www.gnu.org/licenses/>.
+
+use super::{pallet::Error, pallet::Event, *};
+use frame_support::{assert_noop, assert_ok};
+use mock::*;
+use pallet_evm::HashedAddressMapping;
+use sp_runtime::traits::BlakeTwo256;
+
+/// EVM address of the `seed` key, along with its claim signature for `account_id`.
+fn sign_claim(account_id: &AccountId, seed: &[u8]) -> (H160, EvmSignature) {
+    let secret = libsecp256k1::SecretKey::parse(&keccak_256(seed)).expect("valid secret key");
+    let public = libsecp256k1::PublicKey::from_secret_key(&secret);
+    let evm_address = H160::from_slice(&keccak_256(&public.serialize()[1..])[12..]);
+
+    let message = libsecp256k1::Message::parse(&UnifiedAccounts::claim_signing_hash(account_id));
+    let (signature, recovery_id) = libsecp256k1::sign(&message, &secret);
+    let mut evm_signature = [0u8; 65];
+    evm_signature[..64].copy_from_slice(&signature.serialize());
+    // wallets use the legacy `27 + recovery id` encoding
+    evm_signature[64] = recovery_id.serialize() + 27;
+
+    (evm_address, evm_signature)
+}
+
+#[test]
+fn claim_evm_address_works() {
+    ExternalityBuilder::build().execute_with(|| {
+        let (evm_address, signature) = sign_claim(&ALICE, b"alice");
+
+        assert_ok!(UnifiedAccounts::claim_evm_address(
+            RuntimeOrigin::signed(ALICE),
+            evm_address,
+            signature
+        ));
+        System::assert_last_event(mock::RuntimeEvent::UnifiedAccounts(Event::AccountClaimed {
+            account_id: ALICE,
+            evm_address,
+        }));
+
+        assert_eq!(UnifiedAccounts::to_h160(&ALICE), Some(evm_address));
+        assert_eq!(UnifiedAccounts::to_h160_or_default(&ALICE), evm_address);
+        assert_eq!(UnifiedAccounts::to_account_id(&evm_address), Some(ALICE));
+        assert_eq!(
+            <UnifiedAccounts as AddressMapping<AccountId>>::into_account_id(evm_address),
+            ALICE
+        );
+    })
+}
+
+#[test]
+fn claim_requires_signature_of_evm_address() {
+    ExternalityBuilder::build().execute_with(|| {
+        let (alice_address, _) = sign_claim(&ALICE, b"alice");
+        let (_, bob_signature) = sign_claim(&ALICE, b"bob");
+        assert_noop!(
+            UnifiedAccounts::claim_evm_address(
+                RuntimeOrigin::signed(ALICE),
+                alice_address,
+                bob_signature
+            ),
+            Error::<TestRuntime>::InvalidSignature
+        );
+
+        // signature of a claim by another account
+        let (alice_address, signature) = sign_claim(&BOB, b"alice");
+        assert_noop!(
+            UnifiedAccounts::claim_evm_address(
+                RuntimeOrigin::signed(ALICE),
+                alice_address,
+                signature
+            ),
+            Error::<TestRuntime>::InvalidSignature
+        );
+
+        assert_noop!(
+            UnifiedAccounts::claim_evm_address(
+                RuntimeOrigin::signed(ALICE),
+                alice_address,
+                [0u8; 65]
+            ),
+            Error::<TestRuntime>::InvalidSignature
+        );
+    })
+}
+
+#[test]
+fn accounts_and_addresses_are_claimed_once() {
+    ExternalityBuilder::build().execute_with(|| {
+        let (alice_address, signature) = sign_claim(&ALICE, b"alice");
+        assert_ok!(UnifiedAccounts::claim_evm_address(
+            RuntimeOrigin::signed(ALICE),
+            alice_address,
+            signature
+        ));
+
+        let (other_address, signature) = sign_claim(&ALICE, b"other");
+        assert_noop!(
+            UnifiedAccounts::claim_evm_address(
+                RuntimeOrigin::signed(ALICE),
+                other_address,
+                signature
+            ),
+            Error::<TestRuntime>::AlreadyMapped
+        );
+        assert_noop!(
+            UnifiedAccounts::claim_default_evm_address(RuntimeOrigin::signed(ALICE)),
+            Error::<TestRuntime>::AlreadyMapped
+        );
+
+        let (_, signature) = sign_claim(&BOB, b"alice");
+        assert_noop!(
+            UnifiedAccounts::claim_evm_address(
+                RuntimeOrigin::signed(BOB),
+                alice_address,
+                signature
+            ),
+            Error::<TestRuntime>::EvmAddressAlreadyMapped
+        );
+    })
+}
+
+#[test]
+fn claim_default_evm_address_works() {
+    ExternalityBuilder::build().execute_with(|| {
+        let default_address = HashedAccountMapping::<BlakeTwo256>::into_h160(&ALICE);
+        assert_eq!(UnifiedAccounts::to_h160_or_default(&ALICE), default_address);
+        // default mappings aren't reversible
+        assert_ne!(
+            UnifiedAccounts::to_account_id_or_default(&default_address),
+            ALICE
+        );
+
+        assert_ok!(UnifiedAccounts::claim_default_evm_address(
+            RuntimeOrigin::signed(ALICE)
+        ));
+        System::assert_last_event(mock::RuntimeEvent::UnifiedAccounts(Event::AccountClaimed {
+            account_id: ALICE,
+            evm_address: default_address,
+        }));
+        assert_eq!(
+            UnifiedAccounts::to_account_id_or_default(&default_address),
+            ALICE
+        );
+    })
+}
+
+#[test]
+fn unclaimed_lookups_use_default_mappings() {
+    ExternalityBuilder::build().execute_with(|| {
+        let evm_address = H160::repeat_byte(0xAA);
+        let default_account = HashedAddressMapping::<BlakeTwo256>::into_account_id(evm_address);
+
+        assert_eq!(UnifiedAccounts::to_account_id(&evm_address), None);
+        assert_eq!(
+            UnifiedAccounts::to_account_id_or_default(&evm_address),
+            default_account
+        );
+        assert_eq!(
+            <UnifiedAccounts as AddressMapping<AccountId>>::into_account_id(evm_address),
+            default_account
+        );
+
+        assert_eq!(UnifiedAccounts::to_h160(&BOB), None);
+        assert_eq!(
+            UnifiedAccounts::to_h160_or_default(&BOB),
+            HashedAccountMapping::<BlakeTwo256>::into_h160(&BOB)
+        );
+    })
+}
+
+#[test]
+fn claim_moves_funds_of_default_account() {
+    ExternalityBuilder::build().execute_with(|| {
+        let (evm_address, signature) = sign_claim(&ALICE, b"alice");
+        let default_account = HashedAddressMapping::<BlakeTwo256>::into_account_id(evm_address);
+        Balances::make_free_balance_be(&default_account, 100);
+
+        assert_ok!(UnifiedAccounts::claim_evm_address(
+            RuntimeOrigin::signed(ALICE),
+            evm_address,
+            signature
+        ));
+        assert_eq!(Balances::free_balance(&ALICE), 100);
+        assert_eq!(Balances::free_balance(&default_account), 0);
+        assert!(!System::account_exists(&default_account));
+
+        // the same goes for default EVM addresses
+        let default_address = HashedAccountMapping::<BlakeTwo256>::into_h160(&BOB);
+        let default_account = HashedAddressMapping::<BlakeTwo256>::into_account_id(default_address);
+        Balances::make_free_balance_be(&default_account, 50);
+
+        assert_ok!(UnifiedAccounts::claim_default_evm_address(
+            RuntimeOrigin::signed(BOB)
+        ));
+        assert_eq!(Balances::free_balance(&BOB), 50);
+        assert_eq!(Balances::free_balance(&default_account), 0);
+    })
+}
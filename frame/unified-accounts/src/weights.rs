@@ -0,0 +1,98 @@
+// This file is part of Astar.
+
+// Copyright (C) 2019-2023 Stake Technologies Pte.Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// Astar is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Astar is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Astar. If not, see <http://www.gnu.org/licenses/>.
+
+//! Weights for pallet_unified_accounts
+//!
+//! The pallet hasn't been benchmarked on reference hardware yet. Storage accesses follow the code,
+//! execution times and proof sizes are placeholders to be replaced by the output of the `benchmark pallet`
+//! command of the collator, using `--pallet=pallet_unified_accounts --extrinsic=*` and
+//! `./scripts/templates/weight-template.hbs`.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for pallet_unified_accounts.
+pub trait WeightInfo {
+	fn claim_evm_address() -> Weight;
+	fn claim_default_evm_address() -> Weight;
+}
+
+/// Weights for pallet_unified_accounts using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	// Storage: System BlockHash (r:1 w:0)
+	// Proof: System BlockHash (max_values: None, max_size: Some(44), added: 2519, mode: MaxEncodedLen)
+	// Storage: UnifiedAccounts NativeToEvm (r:1 w:1)
+	// Proof Skipped: UnifiedAccounts NativeToEvm (max_values: None, max_size: None, mode: Measured)
+	// Storage: UnifiedAccounts EvmToNative (r:1 w:1)
+	// Proof Skipped: UnifiedAccounts EvmToNative (max_values: None, max_size: None, mode: Measured)
+	// Storage: System Account (r:2 w:2)
+	// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	fn claim_evm_address() -> Weight {
+		Weight::from_ref_time(72_561_000)
+			.saturating_add(Weight::from_proof_size(14315))
+			.saturating_add(T::DbWeight::get().reads(5_u64))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+	}
+	// Storage: UnifiedAccounts NativeToEvm (r:1 w:1)
+	// Proof Skipped: UnifiedAccounts NativeToEvm (max_values: None, max_size: None, mode: Measured)
+	// Storage: UnifiedAccounts EvmToNative (r:1 w:1)
+	// Proof Skipped: UnifiedAccounts EvmToNative (max_values: None, max_size: None, mode: Measured)
+	// Storage: System Account (r:2 w:2)
+	// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	fn claim_default_evm_address() -> Weight {
+		Weight::from_ref_time(25_640_000)
+			.saturating_add(Weight::from_proof_size(10686))
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	// Storage: System BlockHash (r:1 w:0)
+	// Proof: System BlockHash (max_values: None, max_size: Some(44), added: 2519, mode: MaxEncodedLen)
+	// Storage: UnifiedAccounts NativeToEvm (r:1 w:1)
+	// Proof Skipped: UnifiedAccounts NativeToEvm (max_values: None, max_size: None, mode: Measured)
+	// Storage: UnifiedAccounts EvmToNative (r:1 w:1)
+	// Proof Skipped: UnifiedAccounts EvmToNative (max_values: None, max_size: None, mode: Measured)
+	// Storage: System Account (r:2 w:2)
+	// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	fn claim_evm_address() -> Weight {
+		Weight::from_ref_time(72_561_000)
+			.saturating_add(Weight::from_proof_size(14315))
+			.saturating_add(RocksDbWeight::get().reads(5_u64))
+			.saturating_add(RocksDbWeight::get().writes(4_u64))
+	}
+	// Storage: UnifiedAccounts NativeToEvm (r:1 w:1)
+	// Proof Skipped: UnifiedAccounts NativeToEvm (max_values: None, max_size: None, mode: Measured)
+	// Storage: UnifiedAccounts EvmToNative (r:1 w:1)
+	// Proof Skipped: UnifiedAccounts EvmToNative (max_values: None, max_size: None, mode: Measured)
+	// Storage: System Account (r:2 w:2)
+	// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	fn claim_default_evm_address() -> Weight {
+		Weight::from_ref_time(25_640_000)
+			.saturating_add(Weight::from_proof_size(10686))
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(4_u64))
+	}
+}
@@ -22,10 +22,22 @@ use super::*;
 use crate::Pallet as XcAssetConfig;
 
 use frame_benchmarking::{benchmarks, impl_benchmark_test_suite};
+use frame_support::traits::Get;
 use frame_system::RawOrigin;
-use sp_std::boxed::Box;
+use sp_runtime::{FixedPointNumber, FixedU128};
+use sp_std::{boxed::Box, vec};
 use xcm::v3::MultiLocation;
 
+fn asset_metadata<T: Config>() -> XcAssetMetadataOf<T> {
+    XcAssetMetadata {
+        decimals: 12,
+        symbol: vec![b'X'; T::MaxSymbolLength::get() as usize]
+            .try_into()
+            .expect("symbol has the maximum length"),
+        reserve_chain: Some(MultiLocation::parent().into_versioned()),
+    }
+}
+
 benchmarks! {
 
     register_asset_location {
@@ -94,6 +106,59 @@ benchmarks! {
         assert!(!AssetLocationUnitsPerSecond::<T>::contains_key(&asset_location.into_versioned()));
     }
 
+    set_asset_metadata {
+        let asset_location = MultiLocation::parent();
+        let asset_id = T::AssetId::default();
+
+        XcAssetConfig::<T>::register_asset_location(RawOrigin::Root.into(), Box::new(asset_location.into_versioned()), asset_id)?;
+
+    }: _(RawOrigin::Root, asset_id, Some(Box::new(asset_metadata::<T>())))
+    verify {
+        assert_eq!(AssetMetadata::<T>::get(&asset_id), Some(asset_metadata::<T>()));
+    }
+
+    set_asset_relative_price {
+        let asset_location = MultiLocation::parent();
+        let asset_id = T::AssetId::default();
+        let relative_price = FixedU128::saturating_from_rational(1, 2);
+
+        XcAssetConfig::<T>::register_asset_location(RawOrigin::Root.into(), Box::new(asset_location.clone().into_versioned()), asset_id)?;
+        XcAssetConfig::<T>::set_asset_metadata(RawOrigin::Root.into(), asset_id, Some(Box::new(asset_metadata::<T>())))?;
+
+    }: _(RawOrigin::Root, Box::new(asset_location.clone().into_versioned()), relative_price)
+    verify {
+        assert_eq!(AssetLocationRelativePrice::<T>::get(&asset_location.into_versioned()), Some(relative_price));
+    }
+
+    set_asset_oracle_source {
+        let asset_location = MultiLocation::parent();
+        let asset_id = T::AssetId::default();
+        let oracle_source = OracleSource {
+            feed_id: 1,
+            min_units_per_second: 1,
+            max_units_per_second: u128::MAX,
+            out_of_bounds: OutOfBoundsRate::Fallback,
+            max_price_age: u64::MAX,
+        };
+
+        XcAssetConfig::<T>::register_asset_location(RawOrigin::Root.into(), Box::new(asset_location.clone().into_versioned()), asset_id)?;
+        XcAssetConfig::<T>::set_asset_metadata(RawOrigin::Root.into(), asset_id, Some(Box::new(asset_metadata::<T>())))?;
+
+    }: _(RawOrigin::Root, Box::new(asset_location.clone().into_versioned()), Some(oracle_source))
+    verify {
+        assert_eq!(AssetLocationOracleSource::<T>::get(&asset_location.into_versioned()), Some(oracle_source));
+    }
+
+    register_asset_location_with_metadata {
+        let asset_location = MultiLocation::parent();
+        let asset_id = T::AssetId::default();
+
+    }: _(RawOrigin::Root, Box::new(asset_location.clone().into_versioned()), asset_id, Box::new(asset_metadata::<T>()))
+    verify {
+        assert_eq!(AssetIdToLocation::<T>::get(&asset_id), Some(asset_location.into_versioned()));
+        assert_eq!(AssetMetadata::<T>::get(&asset_id), Some(asset_metadata::<T>()));
+    }
+
 }
 
 #[cfg(test)]
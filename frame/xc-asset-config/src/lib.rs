@@ -28,6 +28,9 @@
 //!
 //! Additionally, it stores information whether a foreign asset is supported as a payment currency for execution on local network.
 //!
+//! Assets can optionally have metadata describing their decimals, symbol and reserve chain. The execution payment rate of an asset
+//! with known decimals can be derived from its price relative to the native currency, instead of being set explicitly.
+//!
 //! ## Interface
 //!
 //! ### Dispatchable Function
@@ -37,6 +40,10 @@
 //! - `change_existing_asset_location` - changes the remote location of an existing local asset Id
 //! - `remove_payment_asset` - removes asset from the set of supported payment assets
 //! - `remove_asset` - removes all information related to this asset
+//! - `set_asset_metadata` - sets or clears the metadata of an existing asset
+//! - `set_asset_relative_price` - registers asset as payment currency, deriving its payment per second from its relative price
+//! - `set_asset_oracle_source` - sets or clears the oracle feed and rate bounds used to price a payment asset
+//! - `register_asset_location_with_metadata` - registers mapping between local asset Id and remote asset location along with the asset metadata
 //!
 //! User is encouraged to refer to specific function implementations for more comprehensive documentation.
 //!
@@ -49,6 +56,13 @@
 //! `ExecutionPaymentRate` interface for fetching `units per second` if asset is supported payment asset
 //! - `get_units_per_second`
 //!
+//! `XcAssetMetadataGetter` interface for fetching asset metadata
+//! - `get_asset_metadata`
+//!
+//! `OraclePaymentRate` interface for pricing payment assets with an oracle
+//! - `get_oracle_source`
+//! - `units_per_second_for_price`
+//!
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
@@ -75,6 +89,10 @@ pub mod pallet {
     use frame_support::{pallet_prelude::*, traits::EnsureOrigin};
     use frame_system::pallet_prelude::*;
     use parity_scale_codec::HasCompact;
+    use sp_runtime::{
+        traits::{CheckedDiv, Zero},
+        FixedPointNumber, FixedU128,
+    };
     use sp_std::boxed::Box;
     use xcm::{v3::MultiLocation, VersionedMultiLocation};
 
@@ -115,6 +133,65 @@ pub mod pallet {
         fn get_units_per_second(asset_location: MultiLocation) -> Option<u128>;
     }
 
+    /// Metadata of a cross-chain asset, with a symbol of at most `MaxSymbolLength` bytes.
+    #[derive(
+        CloneNoBound, Encode, Decode, PartialEqNoBound, EqNoBound, RuntimeDebugNoBound, TypeInfo,
+    )]
+    #[scale_info(skip_type_params(MaxSymbolLength))]
+    pub struct XcAssetMetadata<MaxSymbolLength: Get<u32>> {
+        /// Number of decimals of the asset.
+        pub decimals: u8,
+        /// Asset symbol.
+        pub symbol: BoundedVec<u8, MaxSymbolLength>,
+        /// Location of the asset reserve chain.
+        pub reserve_chain: Option<VersionedMultiLocation>,
+    }
+
+    /// Metadata of a cross-chain asset, as stored by the pallet.
+    pub type XcAssetMetadataOf<T> = XcAssetMetadata<<T as Config>::MaxSymbolLength>;
+
+    /// Used to fetch the metadata of a cross-chain asset.
+    pub trait XcAssetMetadataGetter<AssetId, MaxSymbolLength: Get<u32>> {
+        /// returns asset metadata or `None` if it isn't known.
+        fn get_asset_metadata(asset_id: AssetId) -> Option<XcAssetMetadata<MaxSymbolLength>>;
+    }
+
+    /// How an oracle based rate outside of the accepted range is handled.
+    #[derive(Clone, Copy, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub enum OutOfBoundsRate {
+        /// The rate is clamped to the nearest bound.
+        Clamp,
+        /// The rate is ignored, the configured `units per second` are used instead.
+        Fallback,
+    }
+
+    /// Oracle feed used to price a payment asset, along with the accepted rate range.
+    #[derive(Clone, Copy, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub struct OracleSource {
+        /// Identifier of the oracle price feed.
+        pub feed_id: u32,
+        /// Lowest accepted `units per second`.
+        pub min_units_per_second: u128,
+        /// Highest accepted `units per second`.
+        pub max_units_per_second: u128,
+        /// How rates outside of the accepted range are handled.
+        pub out_of_bounds: OutOfBoundsRate,
+        /// Maximum age of the oracle price in milliseconds, older prices are ignored.
+        pub max_price_age: u64,
+    }
+
+    /// Used to price cross-chain payment assets with an oracle.
+    pub trait OraclePaymentRate {
+        /// returns oracle source of the asset or `None` if it isn't priced by an oracle.
+        fn get_oracle_source(asset_location: MultiLocation) -> Option<OracleSource>;
+
+        /// returns `units per second` for the price of a whole asset token in whole native tokens.
+        fn units_per_second_for_price(
+            asset_location: MultiLocation,
+            relative_price: FixedU128,
+        ) -> Option<u128>;
+    }
+
     impl<T: Config> XcAssetLocation<T::AssetId> for Pallet<T> {
         fn get_xc_asset_location(asset_id: T::AssetId) -> Option<MultiLocation> {
             AssetIdToLocation::<T>::get(asset_id).and_then(|x| x.try_into().ok())
@@ -125,9 +202,31 @@ pub mod pallet {
         }
     }
 
+    /// Explicitly set `units per second` take precedence over rates derived from relative prices.
     impl<T: Config> ExecutionPaymentRate for Pallet<T> {
         fn get_units_per_second(asset_location: MultiLocation) -> Option<u128> {
-            AssetLocationUnitsPerSecond::<T>::get(asset_location.into_versioned())
+            let asset_location = asset_location.into_versioned();
+            AssetLocationUnitsPerSecond::<T>::get(&asset_location)
+                .or_else(|| Self::derived_units_per_second(&asset_location))
+        }
+    }
+
+    impl<T: Config> XcAssetMetadataGetter<T::AssetId, T::MaxSymbolLength> for Pallet<T> {
+        fn get_asset_metadata(asset_id: T::AssetId) -> Option<XcAssetMetadataOf<T>> {
+            AssetMetadata::<T>::get(asset_id)
+        }
+    }
+
+    impl<T: Config> OraclePaymentRate for Pallet<T> {
+        fn get_oracle_source(asset_location: MultiLocation) -> Option<OracleSource> {
+            AssetLocationOracleSource::<T>::get(asset_location.into_versioned())
+        }
+
+        fn units_per_second_for_price(
+            asset_location: MultiLocation,
+            relative_price: FixedU128,
+        ) -> Option<u128> {
+            Self::units_per_second_from_price(&asset_location.into_versioned(), relative_price)
         }
     }
 
@@ -147,6 +246,18 @@ pub mod pallet {
         /// Should most likely be root.
         type ManagerOrigin: EnsureOrigin<<Self as frame_system::Config>::RuntimeOrigin>;
 
+        /// Units of the native currency charged per second of execution.
+        #[pallet::constant]
+        type NativeUnitsPerSecond: Get<u128>;
+
+        /// Number of decimals of the native currency.
+        #[pallet::constant]
+        type NativeDecimals: Get<u8>;
+
+        /// Maximum length of an asset symbol, in bytes.
+        #[pallet::constant]
+        type MaxSymbolLength: Get<u32>;
+
         type WeightInfo: WeightInfo;
     }
 
@@ -158,6 +269,12 @@ pub mod pallet {
         AssetDoesNotExist,
         /// Failed to convert to latest versioned MultiLocation
         MultiLocationNotSupported,
+        /// Asset metadata is required to derive its payment rate.
+        AssetMetadataMissing,
+        /// Relative price must be positive.
+        InvalidRelativePrice,
+        /// Oracle source rate bounds are invalid.
+        InvalidOracleSource,
     }
 
     #[pallet::event]
@@ -188,6 +305,21 @@ pub mod pallet {
             asset_location: VersionedMultiLocation,
             asset_id: T::AssetId,
         },
+        /// Set or cleared the metadata of an asset Id
+        AssetMetadataSet {
+            asset_id: T::AssetId,
+            metadata: Option<XcAssetMetadataOf<T>>,
+        },
+        /// Changed the price of an asset relative to the native currency
+        RelativePriceChanged {
+            asset_location: VersionedMultiLocation,
+            relative_price: FixedU128,
+        },
+        /// Set or cleared the oracle source of an asset type
+        OracleSourceChanged {
+            asset_location: VersionedMultiLocation,
+            oracle_source: Option<OracleSource>,
+        },
     }
 
     /// Mapping from an asset id to asset type.
@@ -215,10 +347,32 @@ pub mod pallet {
     pub type AssetLocationUnitsPerSecond<T: Config> =
         StorageMap<_, Twox64Concat, VersionedMultiLocation, u128>;
 
+    /// Metadata of an asset id.
+    #[pallet::storage]
+    #[pallet::getter(fn asset_metadata)]
+    pub type AssetMetadata<T: Config> =
+        StorageMap<_, Twox64Concat, T::AssetId, XcAssetMetadataOf<T>>;
+
+    /// Stores the price of a whole AssetLocation token in whole native tokens.
+    /// This is used to derive the units per second of an asset without an explicit value.
+    #[pallet::storage]
+    #[pallet::getter(fn asset_location_relative_price)]
+    pub type AssetLocationRelativePrice<T: Config> =
+        StorageMap<_, Twox64Concat, VersionedMultiLocation, FixedU128>;
+
+    /// Stores the oracle source used to price an AssetLocation for execution payment.
+    #[pallet::storage]
+    #[pallet::getter(fn asset_location_oracle_source)]
+    pub type AssetLocationOracleSource<T: Config> =
+        StorageMap<_, Twox64Concat, VersionedMultiLocation, OracleSource>;
+
     #[pallet::call]
     impl<T: Config> Pallet<T> {
         /// Register new asset location to asset Id mapping.
         ///
+        /// Asset metadata can be set afterwards with `set_asset_metadata`, or registered along
+        /// with the asset through `register_asset_location_with_metadata`.
+        ///
         /// This makes the asset eligible for XCM interaction.
         #[pallet::call_index(0)]
         #[pallet::weight(T::WeightInfo::register_asset_location())]
@@ -229,26 +383,7 @@ pub mod pallet {
         ) -> DispatchResult {
             T::ManagerOrigin::ensure_origin(origin)?;
 
-            // Ensure such an assetId does not exist
-            ensure!(
-                !AssetIdToLocation::<T>::contains_key(&asset_id),
-                Error::<T>::AssetAlreadyRegistered
-            );
-
-            let v3_asset_loc = MultiLocation::try_from(*asset_location)
-                .map_err(|_| Error::<T>::MultiLocationNotSupported)?;
-            let asset_location = VersionedMultiLocation::V3(v3_asset_loc);
-
-            AssetIdToLocation::<T>::insert(&asset_id, asset_location.clone());
-            AssetLocationToId::<T>::insert(&asset_location, asset_id);
-
-            T::XcAssetChanged::xc_asset_registered(asset_id);
-
-            Self::deposit_event(Event::AssetRegistered {
-                asset_location,
-                asset_id,
-            });
-            Ok(())
+            Self::do_register_asset_location(*asset_location, asset_id)
         }
 
         /// Change the amount of units we are charging per execution second
@@ -310,6 +445,16 @@ pub mod pallet {
                 AssetLocationUnitsPerSecond::<T>::insert(&new_asset_location, units);
             }
 
+            // Change AssetLocationRelativePrice
+            if let Some(price) = AssetLocationRelativePrice::<T>::take(&previous_asset_location) {
+                AssetLocationRelativePrice::<T>::insert(&new_asset_location, price);
+            }
+
+            // Change AssetLocationOracleSource
+            if let Some(source) = AssetLocationOracleSource::<T>::take(&previous_asset_location) {
+                AssetLocationOracleSource::<T>::insert(&new_asset_location, source);
+            }
+
             Self::deposit_event(Event::AssetLocationChanged {
                 previous_asset_location,
                 asset_id,
@@ -334,6 +479,8 @@ pub mod pallet {
             let asset_location = VersionedMultiLocation::V3(v3_asset_loc);
 
             AssetLocationUnitsPerSecond::<T>::remove(&asset_location);
+            AssetLocationRelativePrice::<T>::remove(&asset_location);
+            AssetLocationOracleSource::<T>::remove(&asset_location);
 
             Self::deposit_event(Event::SupportedAssetRemoved { asset_location });
             Ok(())
@@ -354,6 +501,9 @@ pub mod pallet {
             AssetIdToLocation::<T>::remove(&asset_id);
             AssetLocationToId::<T>::remove(&asset_location);
             AssetLocationUnitsPerSecond::<T>::remove(&asset_location);
+            AssetLocationRelativePrice::<T>::remove(&asset_location);
+            AssetLocationOracleSource::<T>::remove(&asset_location);
+            AssetMetadata::<T>::remove(&asset_id);
             T::XcAssetChanged::xc_asset_unregistered(asset_id);
 
             Self::deposit_event(Event::AssetRemoved {
@@ -362,5 +512,207 @@ pub mod pallet {
             });
             Ok(())
         }
+
+        /// Set the metadata of an existing asset Id, or clear it.
+        ///
+        /// Without metadata, the payment rate of the asset can't be derived from its relative price.
+        #[pallet::call_index(5)]
+        #[pallet::weight(T::WeightInfo::set_asset_metadata())]
+        pub fn set_asset_metadata(
+            origin: OriginFor<T>,
+            #[pallet::compact] asset_id: T::AssetId,
+            metadata: Option<Box<XcAssetMetadataOf<T>>>,
+        ) -> DispatchResult {
+            T::ManagerOrigin::ensure_origin(origin)?;
+
+            ensure!(
+                AssetIdToLocation::<T>::contains_key(&asset_id),
+                Error::<T>::AssetDoesNotExist
+            );
+            let metadata = metadata
+                .map(|metadata| Self::latest_metadata(*metadata))
+                .transpose()?;
+
+            AssetMetadata::<T>::set(&asset_id, metadata.clone());
+
+            Self::deposit_event(Event::AssetMetadataSet { asset_id, metadata });
+            Ok(())
+        }
+
+        /// Change the price of a whole AssetLocation token in whole native tokens.
+        ///
+        /// Unless `units per second` are set explicitly, they are derived from this price, the
+        /// native execution price and the decimals of both currencies.
+        #[pallet::call_index(6)]
+        #[pallet::weight(T::WeightInfo::set_asset_relative_price())]
+        pub fn set_asset_relative_price(
+            origin: OriginFor<T>,
+            asset_location: Box<VersionedMultiLocation>,
+            relative_price: FixedU128,
+        ) -> DispatchResult {
+            T::ManagerOrigin::ensure_origin(origin)?;
+
+            ensure!(!relative_price.is_zero(), Error::<T>::InvalidRelativePrice);
+
+            let v3_asset_loc = MultiLocation::try_from(*asset_location)
+                .map_err(|_| Error::<T>::MultiLocationNotSupported)?;
+            let asset_location = VersionedMultiLocation::V3(v3_asset_loc);
+
+            let asset_id = AssetLocationToId::<T>::get(&asset_location)
+                .ok_or(Error::<T>::AssetDoesNotExist)?;
+            ensure!(
+                AssetMetadata::<T>::contains_key(&asset_id),
+                Error::<T>::AssetMetadataMissing
+            );
+
+            AssetLocationRelativePrice::<T>::insert(&asset_location, relative_price);
+
+            Self::deposit_event(Event::RelativePriceChanged {
+                asset_location,
+                relative_price,
+            });
+            Ok(())
+        }
+
+        /// Set the oracle source used to price an AssetLocation for execution payment, or clear it.
+        ///
+        /// Oracle based rates outside of the source bounds are handled as the source specifies.
+        /// The asset must have metadata, since its decimals are needed to convert the oracle
+        /// price into `units per second`.
+        #[pallet::call_index(7)]
+        #[pallet::weight(T::WeightInfo::set_asset_oracle_source())]
+        pub fn set_asset_oracle_source(
+            origin: OriginFor<T>,
+            asset_location: Box<VersionedMultiLocation>,
+            oracle_source: Option<OracleSource>,
+        ) -> DispatchResult {
+            T::ManagerOrigin::ensure_origin(origin)?;
+
+            let v3_asset_loc = MultiLocation::try_from(*asset_location)
+                .map_err(|_| Error::<T>::MultiLocationNotSupported)?;
+            let asset_location = VersionedMultiLocation::V3(v3_asset_loc);
+
+            let asset_id = AssetLocationToId::<T>::get(&asset_location)
+                .ok_or(Error::<T>::AssetDoesNotExist)?;
+
+            if let Some(source) = &oracle_source {
+                ensure!(
+                    !source.max_units_per_second.is_zero()
+                        && source.min_units_per_second <= source.max_units_per_second,
+                    Error::<T>::InvalidOracleSource
+                );
+                ensure!(
+                    AssetMetadata::<T>::contains_key(&asset_id),
+                    Error::<T>::AssetMetadataMissing
+                );
+            }
+
+            AssetLocationOracleSource::<T>::set(&asset_location, oracle_source);
+
+            Self::deposit_event(Event::OracleSourceChanged {
+                asset_location,
+                oracle_source,
+            });
+            Ok(())
+        }
+
+        /// Register new asset location to asset Id mapping, along with the asset metadata.
+        ///
+        /// This makes the asset eligible for XCM interaction, and its payment rate can be
+        /// derived from a relative price right away.
+        #[pallet::call_index(8)]
+        #[pallet::weight(T::WeightInfo::register_asset_location_with_metadata())]
+        pub fn register_asset_location_with_metadata(
+            origin: OriginFor<T>,
+            asset_location: Box<VersionedMultiLocation>,
+            #[pallet::compact] asset_id: T::AssetId,
+            metadata: Box<XcAssetMetadataOf<T>>,
+        ) -> DispatchResult {
+            T::ManagerOrigin::ensure_origin(origin)?;
+
+            let metadata = Self::latest_metadata(*metadata)?;
+            Self::do_register_asset_location(*asset_location, asset_id)?;
+
+            AssetMetadata::<T>::insert(&asset_id, metadata.clone());
+
+            Self::deposit_event(Event::AssetMetadataSet {
+                asset_id,
+                metadata: Some(metadata),
+            });
+            Ok(())
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        /// Register new asset location to asset Id mapping.
+        fn do_register_asset_location(
+            asset_location: VersionedMultiLocation,
+            asset_id: T::AssetId,
+        ) -> DispatchResult {
+            // Ensure such an assetId does not exist
+            ensure!(
+                !AssetIdToLocation::<T>::contains_key(&asset_id),
+                Error::<T>::AssetAlreadyRegistered
+            );
+
+            let v3_asset_loc = MultiLocation::try_from(asset_location)
+                .map_err(|_| Error::<T>::MultiLocationNotSupported)?;
+            let asset_location = VersionedMultiLocation::V3(v3_asset_loc);
+
+            AssetIdToLocation::<T>::insert(&asset_id, asset_location.clone());
+            AssetLocationToId::<T>::insert(&asset_location, asset_id);
+
+            T::XcAssetChanged::xc_asset_registered(asset_id);
+
+            Self::deposit_event(Event::AssetRegistered {
+                asset_location,
+                asset_id,
+            });
+            Ok(())
+        }
+
+        /// Metadata with the reserve chain location converted to the latest version.
+        fn latest_metadata(
+            metadata: XcAssetMetadataOf<T>,
+        ) -> Result<XcAssetMetadataOf<T>, Error<T>> {
+            let reserve_chain = metadata
+                .reserve_chain
+                .map(|location| {
+                    MultiLocation::try_from(location)
+                        .map(VersionedMultiLocation::V3)
+                        .map_err(|_| Error::<T>::MultiLocationNotSupported)
+                })
+                .transpose()?;
+
+            Ok(XcAssetMetadata {
+                reserve_chain,
+                ..metadata
+            })
+        }
+
+        /// Units per second derived from the stored relative price of the asset.
+        fn derived_units_per_second(asset_location: &VersionedMultiLocation) -> Option<u128> {
+            let relative_price = AssetLocationRelativePrice::<T>::get(asset_location)?;
+            Self::units_per_second_from_price(asset_location, relative_price)
+        }
+
+        /// Units per second for the relative price of the asset, i.e. the native execution
+        /// price converted to whole native tokens, then to whole asset tokens and finally
+        /// to asset units.
+        fn units_per_second_from_price(
+            asset_location: &VersionedMultiLocation,
+            relative_price: FixedU128,
+        ) -> Option<u128> {
+            let asset_id = AssetLocationToId::<T>::get(asset_location)?;
+            let metadata = AssetMetadata::<T>::get(asset_id)?;
+
+            let native_unit = 10_u128.checked_pow(T::NativeDecimals::get().into())?;
+            let asset_unit = 10_u128.checked_pow(metadata.decimals.into())?;
+
+            FixedU128::checked_from_rational(T::NativeUnitsPerSecond::get(), native_unit)?
+                .checked_div(&relative_price)?
+                .checked_mul_int(asset_unit)
+                .filter(|units| !units.is_zero())
+        }
     }
 }
@@ -101,11 +101,20 @@ impl pallet_balances::Config for Test {
 
 type AssetId = u128;
 
+parameter_types! {
+    pub const NativeUnitsPerSecond: u128 = 2_000_000_000_000_000_000;
+    pub const NativeDecimals: u8 = 18;
+    pub const MaxSymbolLength: u32 = 16;
+}
+
 impl pallet_xc_asset_config::Config for Test {
     type RuntimeEvent = RuntimeEvent;
     type AssetId = AssetId;
     type XcAssetChanged = ();
     type ManagerOrigin = frame_system::EnsureRoot<AccountId>;
+    type NativeUnitsPerSecond = NativeUnitsPerSecond;
+    type NativeDecimals = NativeDecimals;
+    type MaxSymbolLength = MaxSymbolLength;
     type WeightInfo = ();
 }
 
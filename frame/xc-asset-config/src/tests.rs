@@ -17,9 +17,10 @@
 // along with Astar. If not, see <http://www.gnu.org/licenses/>.
 
 use super::{pallet::Error, pallet::Event, *};
-use frame_support::{assert_noop, assert_ok, WeakBoundedVec};
+use frame_support::{assert_noop, assert_ok, traits::Get, WeakBoundedVec};
 use mock::*;
-use sp_runtime::traits::BadOrigin;
+use parity_scale_codec::{Decode, Encode};
+use sp_runtime::{traits::BadOrigin, FixedPointNumber, FixedU128};
 use xcm::latest::prelude::*;
 
 use xcm::{v3::MultiLocation, VersionedMultiLocation};
@@ -69,6 +70,29 @@ fn only_root_as_origin() {
             XcAssetConfig::remove_asset(RuntimeOrigin::signed(1), asset_id,),
             BadOrigin
         );
+
+        assert_noop!(
+            XcAssetConfig::set_asset_metadata(RuntimeOrigin::signed(1), asset_id, None),
+            BadOrigin
+        );
+
+        assert_noop!(
+            XcAssetConfig::set_asset_relative_price(
+                RuntimeOrigin::signed(1),
+                Box::new(asset_location.clone()),
+                FixedU128::from(2),
+            ),
+            BadOrigin
+        );
+
+        assert_noop!(
+            XcAssetConfig::set_asset_oracle_source(
+                RuntimeOrigin::signed(1),
+                Box::new(asset_location.clone()),
+                None,
+            ),
+            BadOrigin
+        );
     })
 }
 
@@ -449,3 +473,371 @@ fn incompatible_versioned_multilocations_are_not_ok() {
         );
     })
 }
+
+fn asset_metadata(decimals: u8) -> XcAssetMetadataOf<Test> {
+    XcAssetMetadata {
+        decimals,
+        symbol: b"xcUSD".to_vec().try_into().unwrap(),
+        reserve_chain: Some(
+            MultiLocation::new(1, Junctions::X1(Junction::Parachain(1000))).into_versioned(),
+        ),
+    }
+}
+
+#[test]
+fn asset_symbol_length_is_bounded() {
+    let max_length = <Test as Config>::MaxSymbolLength::get() as usize;
+    let encoded_metadata =
+        |symbol: Vec<u8>| (6_u8, symbol, Some(MultiLocation::parent().into_versioned())).encode();
+
+    let metadata =
+        XcAssetMetadataOf::<Test>::decode(&mut &encoded_metadata(vec![b'X'; max_length])[..])
+            .expect("symbol fits");
+    assert_eq!(metadata.symbol.len(), max_length);
+
+    assert!(XcAssetMetadataOf::<Test>::decode(
+        &mut &encoded_metadata(vec![b'X'; max_length + 1])[..]
+    )
+    .is_err());
+}
+
+#[test]
+fn set_asset_metadata_is_ok() {
+    ExternalityBuilder::build().execute_with(|| {
+        let asset_location = MultiLocation::parent();
+        let asset_id = 17;
+
+        // Metadata can only be set for registered assets
+        assert_noop!(
+            XcAssetConfig::set_asset_metadata(
+                RuntimeOrigin::root(),
+                asset_id,
+                Some(Box::new(asset_metadata(12)))
+            ),
+            Error::<Test>::AssetDoesNotExist
+        );
+
+        assert_ok!(XcAssetConfig::register_asset_location(
+            RuntimeOrigin::root(),
+            Box::new(asset_location.clone().into_versioned()),
+            asset_id
+        ));
+        assert!(!AssetMetadata::<Test>::contains_key(asset_id));
+        assert_eq!(XcAssetConfig::get_asset_metadata(asset_id), None);
+
+        // Reserve chain location is stored in the latest version
+        let legacy_metadata = XcAssetMetadata {
+            reserve_chain: Some(VersionedMultiLocation::V2(xcm::v2::MultiLocation::parent())),
+            ..asset_metadata(12)
+        };
+        let expected_metadata = XcAssetMetadata {
+            reserve_chain: Some(MultiLocation::parent().into_versioned()),
+            ..asset_metadata(12)
+        };
+        assert_ok!(XcAssetConfig::set_asset_metadata(
+            RuntimeOrigin::root(),
+            asset_id,
+            Some(Box::new(legacy_metadata))
+        ));
+        System::assert_last_event(mock::RuntimeEvent::XcAssetConfig(Event::AssetMetadataSet {
+            asset_id,
+            metadata: Some(expected_metadata.clone()),
+        }));
+        assert_eq!(
+            AssetMetadata::<Test>::get(asset_id),
+            Some(expected_metadata)
+        );
+
+        // Clear metadata
+        assert_ok!(XcAssetConfig::set_asset_metadata(
+            RuntimeOrigin::root(),
+            asset_id,
+            None
+        ));
+        assert!(!AssetMetadata::<Test>::contains_key(asset_id));
+    })
+}
+
+#[test]
+fn register_asset_location_with_metadata_is_ok() {
+    ExternalityBuilder::build().execute_with(|| {
+        let asset_location = MultiLocation::parent();
+        let asset_id = 17;
+        let legacy_metadata = XcAssetMetadata {
+            reserve_chain: Some(VersionedMultiLocation::V2(xcm::v2::MultiLocation::parent())),
+            ..asset_metadata(12)
+        };
+        let expected_metadata = XcAssetMetadata {
+            reserve_chain: Some(MultiLocation::parent().into_versioned()),
+            ..asset_metadata(12)
+        };
+
+        assert_noop!(
+            XcAssetConfig::register_asset_location_with_metadata(
+                RuntimeOrigin::signed(1),
+                Box::new(asset_location.clone().into_versioned()),
+                asset_id,
+                Box::new(asset_metadata(12))
+            ),
+            BadOrigin
+        );
+
+        assert_ok!(XcAssetConfig::register_asset_location_with_metadata(
+            RuntimeOrigin::root(),
+            Box::new(asset_location.clone().into_versioned()),
+            asset_id,
+            Box::new(legacy_metadata)
+        ));
+        System::assert_has_event(mock::RuntimeEvent::XcAssetConfig(Event::AssetRegistered {
+            asset_location: asset_location.clone().into_versioned(),
+            asset_id,
+        }));
+        System::assert_last_event(mock::RuntimeEvent::XcAssetConfig(Event::AssetMetadataSet {
+            asset_id,
+            metadata: Some(expected_metadata.clone()),
+        }));
+
+        assert_eq!(
+            AssetIdToLocation::<Test>::get(asset_id),
+            Some(asset_location.clone().into_versioned())
+        );
+        assert_eq!(
+            XcAssetConfig::get_asset_metadata(asset_id),
+            Some(expected_metadata)
+        );
+
+        // Registered assets can't be registered again, nor have their metadata replaced this way
+        assert_noop!(
+            XcAssetConfig::register_asset_location_with_metadata(
+                RuntimeOrigin::root(),
+                Box::new(asset_location.into_versioned()),
+                asset_id,
+                Box::new(asset_metadata(6))
+            ),
+            Error::<Test>::AssetAlreadyRegistered
+        );
+    })
+}
+
+#[test]
+fn units_per_second_derived_from_relative_price() {
+    ExternalityBuilder::build().execute_with(|| {
+        let asset_location = MultiLocation::new(1, Junctions::X1(Junction::Parachain(1000)));
+        let asset_id = 17;
+        let relative_price = FixedU128::saturating_from_rational(1, 2);
+
+        assert_ok!(XcAssetConfig::register_asset_location(
+            RuntimeOrigin::root(),
+            Box::new(asset_location.clone().into_versioned()),
+            asset_id
+        ));
+
+        // Price can't be set without known decimals, or with zero value
+        assert_noop!(
+            XcAssetConfig::set_asset_relative_price(
+                RuntimeOrigin::root(),
+                Box::new(asset_location.clone().into_versioned()),
+                relative_price,
+            ),
+            Error::<Test>::AssetMetadataMissing
+        );
+        assert_ok!(XcAssetConfig::set_asset_metadata(
+            RuntimeOrigin::root(),
+            asset_id,
+            Some(Box::new(asset_metadata(6)))
+        ));
+        assert_noop!(
+            XcAssetConfig::set_asset_relative_price(
+                RuntimeOrigin::root(),
+                Box::new(asset_location.clone().into_versioned()),
+                FixedU128::from(0),
+            ),
+            Error::<Test>::InvalidRelativePrice
+        );
+
+        assert_ok!(XcAssetConfig::set_asset_relative_price(
+            RuntimeOrigin::root(),
+            Box::new(asset_location.clone().into_versioned()),
+            relative_price,
+        ));
+        System::assert_last_event(mock::RuntimeEvent::XcAssetConfig(
+            Event::RelativePriceChanged {
+                asset_location: asset_location.clone().into_versioned(),
+                relative_price,
+            },
+        ));
+
+        // 2 native tokens per second, worth 4 whole asset tokens with 6 decimals
+        assert_eq!(
+            XcAssetConfig::get_units_per_second(asset_location.clone()),
+            Some(4_000_000)
+        );
+
+        // Explicit rate takes precedence
+        assert_ok!(XcAssetConfig::set_asset_units_per_second(
+            RuntimeOrigin::root(),
+            Box::new(asset_location.clone().into_versioned()),
+            123
+        ));
+        assert_eq!(
+            XcAssetConfig::get_units_per_second(asset_location.clone()),
+            Some(123)
+        );
+
+        // Price follows the asset location
+        let new_asset_location = MultiLocation::parent();
+        assert_ok!(XcAssetConfig::change_existing_asset_location(
+            RuntimeOrigin::root(),
+            Box::new(new_asset_location.clone().into_versioned()),
+            asset_id
+        ));
+        assert!(!AssetLocationRelativePrice::<Test>::contains_key(
+            asset_location.into_versioned()
+        ));
+        assert_eq!(
+            AssetLocationRelativePrice::<Test>::get(new_asset_location.clone().into_versioned()),
+            Some(relative_price)
+        );
+
+        // Removing payment asset removes the price as well
+        assert_ok!(XcAssetConfig::remove_payment_asset(
+            RuntimeOrigin::root(),
+            Box::new(new_asset_location.clone().into_versioned()),
+        ));
+        assert!(XcAssetConfig::get_units_per_second(new_asset_location.clone()).is_none());
+    })
+}
+
+#[test]
+fn remove_asset_removes_metadata_and_price() {
+    ExternalityBuilder::build().execute_with(|| {
+        let asset_location = MultiLocation::parent();
+        let asset_id = 17;
+
+        assert_ok!(XcAssetConfig::register_asset_location(
+            RuntimeOrigin::root(),
+            Box::new(asset_location.clone().into_versioned()),
+            asset_id
+        ));
+        assert_ok!(XcAssetConfig::set_asset_metadata(
+            RuntimeOrigin::root(),
+            asset_id,
+            Some(Box::new(asset_metadata(12)))
+        ));
+        assert_ok!(XcAssetConfig::set_asset_relative_price(
+            RuntimeOrigin::root(),
+            Box::new(asset_location.clone().into_versioned()),
+            FixedU128::from(3),
+        ));
+
+        assert_ok!(XcAssetConfig::remove_asset(RuntimeOrigin::root(), asset_id));
+        assert!(!AssetMetadata::<Test>::contains_key(asset_id));
+        assert!(!AssetLocationRelativePrice::<Test>::contains_key(
+            asset_location.into_versioned()
+        ));
+    })
+}
+
+#[test]
+fn set_asset_oracle_source_is_ok() {
+    ExternalityBuilder::build().execute_with(|| {
+        let asset_location = MultiLocation::new(1, Junctions::X1(Junction::Parachain(1000)));
+        let asset_id = 17;
+        let oracle_source = OracleSource {
+            feed_id: 3,
+            min_units_per_second: 1_000,
+            max_units_per_second: 1_000_000,
+            out_of_bounds: OutOfBoundsRate::Fallback,
+            max_price_age: 60_000,
+        };
+
+        // Asset must be registered first
+        assert_noop!(
+            XcAssetConfig::set_asset_oracle_source(
+                RuntimeOrigin::root(),
+                Box::new(asset_location.clone().into_versioned()),
+                Some(oracle_source),
+            ),
+            Error::<Test>::AssetDoesNotExist
+        );
+
+        assert_ok!(XcAssetConfig::register_asset_location(
+            RuntimeOrigin::root(),
+            Box::new(asset_location.clone().into_versioned()),
+            asset_id
+        ));
+
+        // Decimals are needed to convert the oracle price
+        assert_noop!(
+            XcAssetConfig::set_asset_oracle_source(
+                RuntimeOrigin::root(),
+                Box::new(asset_location.clone().into_versioned()),
+                Some(oracle_source),
+            ),
+            Error::<Test>::AssetMetadataMissing
+        );
+        assert_ok!(XcAssetConfig::set_asset_metadata(
+            RuntimeOrigin::root(),
+            asset_id,
+            Some(Box::new(asset_metadata(6)))
+        ));
+
+        // Bounds must be consistent
+        assert_noop!(
+            XcAssetConfig::set_asset_oracle_source(
+                RuntimeOrigin::root(),
+                Box::new(asset_location.clone().into_versioned()),
+                Some(OracleSource {
+                    min_units_per_second: 1_000_001,
+                    ..oracle_source
+                }),
+            ),
+            Error::<Test>::InvalidOracleSource
+        );
+
+        assert_ok!(XcAssetConfig::set_asset_oracle_source(
+            RuntimeOrigin::root(),
+            Box::new(asset_location.clone().into_versioned()),
+            Some(oracle_source),
+        ));
+        System::assert_last_event(mock::RuntimeEvent::XcAssetConfig(
+            Event::OracleSourceChanged {
+                asset_location: asset_location.clone().into_versioned(),
+                oracle_source: Some(oracle_source),
+            },
+        ));
+        assert_eq!(
+            XcAssetConfig::get_oracle_source(asset_location.clone()),
+            Some(oracle_source)
+        );
+
+        // Oracle price is converted the same way as the relative price
+        assert_eq!(
+            XcAssetConfig::units_per_second_for_price(
+                asset_location.clone(),
+                FixedU128::saturating_from_rational(1, 2)
+            ),
+            Some(4_000_000)
+        );
+
+        // Oracle source follows the asset location
+        let new_asset_location = MultiLocation::parent();
+        assert_ok!(XcAssetConfig::change_existing_asset_location(
+            RuntimeOrigin::root(),
+            Box::new(new_asset_location.clone().into_versioned()),
+            asset_id
+        ));
+        assert!(XcAssetConfig::get_oracle_source(asset_location).is_none());
+        assert_eq!(
+            XcAssetConfig::get_oracle_source(new_asset_location.clone()),
+            Some(oracle_source)
+        );
+
+        // Removing payment asset clears the oracle source
+        assert_ok!(XcAssetConfig::remove_payment_asset(
+            RuntimeOrigin::root(),
+            Box::new(new_asset_location.clone().into_versioned()),
+        ));
+        assert!(XcAssetConfig::get_oracle_source(new_asset_location).is_none());
+    })
+}
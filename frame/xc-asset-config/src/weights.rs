@@ -52,6 +52,10 @@ pub trait WeightInfo {
 	fn change_existing_asset_location() -> Weight;
 	fn remove_payment_asset() -> Weight;
 	fn remove_asset() -> Weight;
+	fn set_asset_metadata() -> Weight;
+	fn set_asset_relative_price() -> Weight;
+	fn set_asset_oracle_source() -> Weight;
+	fn register_asset_location_with_metadata() -> Weight;
 }
 
 /// Weights for pallet_xc_asset_config using the Substrate node and recommended hardware.
@@ -85,22 +89,34 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 	// Proof Skipped: XcAssetConfig AssetIdToLocation (max_values: None, max_size: None, mode: Measured)
 	// Storage: XcAssetConfig AssetLocationUnitsPerSecond (r:1 w:2)
 	// Proof Skipped: XcAssetConfig AssetLocationUnitsPerSecond (max_values: None, max_size: None, mode: Measured)
+	// Storage: XcAssetConfig AssetLocationRelativePrice (r:1 w:2)
+	// Proof Skipped: XcAssetConfig AssetLocationRelativePrice (max_values: None, max_size: None, mode: Measured)
+	// Storage: XcAssetConfig AssetLocationOracleSource (r:1 w:2)
+	// Proof Skipped: XcAssetConfig AssetLocationOracleSource (max_values: None, max_size: None, mode: Measured)
 	// Storage: XcAssetConfig AssetLocationToId (r:0 w:2)
 	// Proof Skipped: XcAssetConfig AssetLocationToId (max_values: None, max_size: None, mode: Measured)
+	/// Benchmarked before relative prices and oracle sources, their storage accesses are added on top.
 	fn change_existing_asset_location() -> Weight {
 		// Minimum execution time: 22_357 nanoseconds.
 		Weight::from_ref_time(22_572_000)
 			.saturating_add(Weight::from_proof_size(5373))
 			.saturating_add(T::DbWeight::get().reads(2_u64))
 			.saturating_add(T::DbWeight::get().writes(5_u64))
+			.saturating_add(T::DbWeight::get().reads_writes(2_u64, 4_u64))
 	}
 	// Storage: XcAssetConfig AssetLocationUnitsPerSecond (r:0 w:1)
 	// Proof Skipped: XcAssetConfig AssetLocationUnitsPerSecond (max_values: None, max_size: None, mode: Measured)
+	// Storage: XcAssetConfig AssetLocationRelativePrice (r:0 w:1)
+	// Proof Skipped: XcAssetConfig AssetLocationRelativePrice (max_values: None, max_size: None, mode: Measured)
+	// Storage: XcAssetConfig AssetLocationOracleSource (r:0 w:1)
+	// Proof Skipped: XcAssetConfig AssetLocationOracleSource (max_values: None, max_size: None, mode: Measured)
+	/// Benchmarked before relative prices and oracle sources, their storage accesses are added on top.
 	fn remove_payment_asset() -> Weight {
 		// Minimum execution time: 9_707 nanoseconds.
 		Weight::from_ref_time(10_005_000)
 			.saturating_add(Weight::from_proof_size(0))
 			.saturating_add(T::DbWeight::get().writes(1_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
 	}
 	// Storage: XcAssetConfig AssetIdToLocation (r:1 w:1)
 	// Proof Skipped: XcAssetConfig AssetIdToLocation (max_values: None, max_size: None, mode: Measured)
@@ -110,12 +126,63 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 	// Proof Skipped: XcAssetConfig AssetLocationUnitsPerSecond (max_values: None, max_size: None, mode: Measured)
 	// Storage: XcAssetConfig AssetLocationToId (r:0 w:1)
 	// Proof Skipped: XcAssetConfig AssetLocationToId (max_values: None, max_size: None, mode: Measured)
+	// Storage: XcAssetConfig AssetLocationRelativePrice (r:0 w:1)
+	// Proof Skipped: XcAssetConfig AssetLocationRelativePrice (max_values: None, max_size: None, mode: Measured)
+	// Storage: XcAssetConfig AssetLocationOracleSource (r:0 w:1)
+	// Proof Skipped: XcAssetConfig AssetLocationOracleSource (max_values: None, max_size: None, mode: Measured)
+	// Storage: XcAssetConfig AssetMetadata (r:0 w:1)
+	// Proof Skipped: XcAssetConfig AssetMetadata (max_values: None, max_size: None, mode: Measured)
+	/// Benchmarked before asset metadata, relative prices and oracle sources, their storage accesses are added on top.
 	fn remove_asset() -> Weight {
 		// Minimum execution time: 18_645 nanoseconds.
 		Weight::from_ref_time(18_878_000)
 			.saturating_add(Weight::from_proof_size(2987))
 			.saturating_add(T::DbWeight::get().reads(1_u64))
 			.saturating_add(T::DbWeight::get().writes(4_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+	// Storage: XcAssetConfig AssetIdToLocation (r:1 w:0)
+	// Proof Skipped: XcAssetConfig AssetIdToLocation (max_values: None, max_size: None, mode: Measured)
+	// Storage: XcAssetConfig AssetMetadata (r:0 w:1)
+	// Proof Skipped: XcAssetConfig AssetMetadata (max_values: None, max_size: None, mode: Measured)
+	/// Not benchmarked yet, derived from the `set_asset_units_per_second` results, with as many storage accesses.
+	fn set_asset_metadata() -> Weight {
+		Self::set_asset_units_per_second()
+	}
+	// Storage: XcAssetConfig AssetLocationToId (r:1 w:0)
+	// Proof Skipped: XcAssetConfig AssetLocationToId (max_values: None, max_size: None, mode: Measured)
+	// Storage: XcAssetConfig AssetMetadata (r:1 w:0)
+	// Proof Skipped: XcAssetConfig AssetMetadata (max_values: None, max_size: None, mode: Measured)
+	// Storage: XcAssetConfig AssetLocationRelativePrice (r:0 w:1)
+	// Proof Skipped: XcAssetConfig AssetLocationRelativePrice (max_values: None, max_size: None, mode: Measured)
+	/// Not benchmarked yet, derived from the `set_asset_units_per_second` results and the metadata read.
+	fn set_asset_relative_price() -> Weight {
+		Self::set_asset_units_per_second()
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+	}
+	// Storage: XcAssetConfig AssetLocationToId (r:1 w:0)
+	// Proof Skipped: XcAssetConfig AssetLocationToId (max_values: None, max_size: None, mode: Measured)
+	// Storage: XcAssetConfig AssetMetadata (r:1 w:0)
+	// Proof Skipped: XcAssetConfig AssetMetadata (max_values: None, max_size: None, mode: Measured)
+	// Storage: XcAssetConfig AssetLocationOracleSource (r:0 w:1)
+	// Proof Skipped: XcAssetConfig AssetLocationOracleSource (max_values: None, max_size: None, mode: Measured)
+	/// Not benchmarked yet, derived from the `set_asset_units_per_second` results and the metadata read.
+	fn set_asset_oracle_source() -> Weight {
+		Self::set_asset_units_per_second()
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+	}
+	// Storage: XcAssetConfig AssetIdToLocation (r:1 w:1)
+	// Proof Skipped: XcAssetConfig AssetIdToLocation (max_values: None, max_size: None, mode: Measured)
+	// Storage: EVM AccountCodes (r:0 w:1)
+	// Proof Skipped: EVM AccountCodes (max_values: None, max_size: None, mode: Measured)
+	// Storage: XcAssetConfig AssetLocationToId (r:0 w:1)
+	// Proof Skipped: XcAssetConfig AssetLocationToId (max_values: None, max_size: None, mode: Measured)
+	// Storage: XcAssetConfig AssetMetadata (r:0 w:1)
+	// Proof Skipped: XcAssetConfig AssetMetadata (max_values: None, max_size: None, mode: Measured)
+	/// Not benchmarked yet, derived from the `register_asset_location` results and the metadata write.
+	fn register_asset_location_with_metadata() -> Weight {
+		Self::register_asset_location()
+			.saturating_add(T::DbWeight::get().writes(1_u64))
 	}
 }
 
@@ -149,22 +216,34 @@ impl WeightInfo for () {
 	// Proof Skipped: XcAssetConfig AssetIdToLocation (max_values: None, max_size: None, mode: Measured)
 	// Storage: XcAssetConfig AssetLocationUnitsPerSecond (r:1 w:2)
 	// Proof Skipped: XcAssetConfig AssetLocationUnitsPerSecond (max_values: None, max_size: None, mode: Measured)
+	// Storage: XcAssetConfig AssetLocationRelativePrice (r:1 w:2)
+	// Proof Skipped: XcAssetConfig AssetLocationRelativePrice (max_values: None, max_size: None, mode: Measured)
+	// Storage: XcAssetConfig AssetLocationOracleSource (r:1 w:2)
+	// Proof Skipped: XcAssetConfig AssetLocationOracleSource (max_values: None, max_size: None, mode: Measured)
 	// Storage: XcAssetConfig AssetLocationToId (r:0 w:2)
 	// Proof Skipped: XcAssetConfig AssetLocationToId (max_values: None, max_size: None, mode: Measured)
+	/// Benchmarked before relative prices and oracle sources, their storage accesses are added on top.
 	fn change_existing_asset_location() -> Weight {
 		// Minimum execution time: 22_357 nanoseconds.
 		Weight::from_ref_time(22_572_000)
 			.saturating_add(Weight::from_proof_size(5373))
 			.saturating_add(RocksDbWeight::get().reads(2_u64))
 			.saturating_add(RocksDbWeight::get().writes(5_u64))
+			.saturating_add(RocksDbWeight::get().reads_writes(2_u64, 4_u64))
 	}
 	// Storage: XcAssetConfig AssetLocationUnitsPerSecond (r:0 w:1)
 	// Proof Skipped: XcAssetConfig AssetLocationUnitsPerSecond (max_values: None, max_size: None, mode: Measured)
+	// Storage: XcAssetConfig AssetLocationRelativePrice (r:0 w:1)
+	// Proof Skipped: XcAssetConfig AssetLocationRelativePrice (max_values: None, max_size: None, mode: Measured)
+	// Storage: XcAssetConfig AssetLocationOracleSource (r:0 w:1)
+	// Proof Skipped: XcAssetConfig AssetLocationOracleSource (max_values: None, max_size: None, mode: Measured)
+	/// Benchmarked before relative prices and oracle sources, their storage accesses are added on top.
 	fn remove_payment_asset() -> Weight {
 		// Minimum execution time: 9_707 nanoseconds.
 		Weight::from_ref_time(10_005_000)
 			.saturating_add(Weight::from_proof_size(0))
 			.saturating_add(RocksDbWeight::get().writes(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
 	}
 	// Storage: XcAssetConfig AssetIdToLocation (r:1 w:1)
 	// Proof Skipped: XcAssetConfig AssetIdToLocation (max_values: None, max_size: None, mode: Measured)
@@ -174,11 +253,62 @@ impl WeightInfo for () {
 	// Proof Skipped: XcAssetConfig AssetLocationUnitsPerSecond (max_values: None, max_size: None, mode: Measured)
 	// Storage: XcAssetConfig AssetLocationToId (r:0 w:1)
 	// Proof Skipped: XcAssetConfig AssetLocationToId (max_values: None, max_size: None, mode: Measured)
+	// Storage: XcAssetConfig AssetLocationRelativePrice (r:0 w:1)
+	// Proof Skipped: XcAssetConfig AssetLocationRelativePrice (max_values: None, max_size: None, mode: Measured)
+	// Storage: XcAssetConfig AssetLocationOracleSource (r:0 w:1)
+	// Proof Skipped: XcAssetConfig AssetLocationOracleSource (max_values: None, max_size: None, mode: Measured)
+	// Storage: XcAssetConfig AssetMetadata (r:0 w:1)
+	// Proof Skipped: XcAssetConfig AssetMetadata (max_values: None, max_size: None, mode: Measured)
+	/// Benchmarked before asset metadata, relative prices and oracle sources, their storage accesses are added on top.
 	fn remove_asset() -> Weight {
 		// Minimum execution time: 18_645 nanoseconds.
 		Weight::from_ref_time(18_878_000)
 			.saturating_add(Weight::from_proof_size(2987))
 			.saturating_add(RocksDbWeight::get().reads(1_u64))
 			.saturating_add(RocksDbWeight::get().writes(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
+	// Storage: XcAssetConfig AssetIdToLocation (r:1 w:0)
+	// Proof Skipped: XcAssetConfig AssetIdToLocation (max_values: None, max_size: None, mode: Measured)
+	// Storage: XcAssetConfig AssetMetadata (r:0 w:1)
+	// Proof Skipped: XcAssetConfig AssetMetadata (max_values: None, max_size: None, mode: Measured)
+	/// Not benchmarked yet, derived from the `set_asset_units_per_second` results, with as many storage accesses.
+	fn set_asset_metadata() -> Weight {
+		Self::set_asset_units_per_second()
+	}
+	// Storage: XcAssetConfig AssetLocationToId (r:1 w:0)
+	// Proof Skipped: XcAssetConfig AssetLocationToId (max_values: None, max_size: None, mode: Measured)
+	// Storage: XcAssetConfig AssetMetadata (r:1 w:0)
+	// Proof Skipped: XcAssetConfig AssetMetadata (max_values: None, max_size: None, mode: Measured)
+	// Storage: XcAssetConfig AssetLocationRelativePrice (r:0 w:1)
+	// Proof Skipped: XcAssetConfig AssetLocationRelativePrice (max_values: None, max_size: None, mode: Measured)
+	/// Not benchmarked yet, derived from the `set_asset_units_per_second` results and the metadata read.
+	fn set_asset_relative_price() -> Weight {
+		Self::set_asset_units_per_second()
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+	}
+	// Storage: XcAssetConfig AssetLocationToId (r:1 w:0)
+	// Proof Skipped: XcAssetConfig AssetLocationToId (max_values: None, max_size: None, mode: Measured)
+	// Storage: XcAssetConfig AssetMetadata (r:1 w:0)
+	// Proof Skipped: XcAssetConfig AssetMetadata (max_values: None, max_size: None, mode: Measured)
+	// Storage: XcAssetConfig AssetLocationOracleSource (r:0 w:1)
+	// Proof Skipped: XcAssetConfig AssetLocationOracleSource (max_values: None, max_size: None, mode: Measured)
+	/// Not benchmarked yet, derived from the `set_asset_units_per_second` results and the metadata read.
+	fn set_asset_oracle_source() -> Weight {
+		Self::set_asset_units_per_second()
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+	}
+	// Storage: XcAssetConfig AssetIdToLocation (r:1 w:1)
+	// Proof Skipped: XcAssetConfig AssetIdToLocation (max_values: None, max_size: None, mode: Measured)
+	// Storage: EVM AccountCodes (r:0 w:1)
+	// Proof Skipped: EVM AccountCodes (max_values: None, max_size: None, mode: Measured)
+	// Storage: XcAssetConfig AssetLocationToId (r:0 w:1)
+	// Proof Skipped: XcAssetConfig AssetLocationToId (max_values: None, max_size: None, mode: Measured)
+	// Storage: XcAssetConfig AssetMetadata (r:0 w:1)
+	// Proof Skipped: XcAssetConfig AssetMetadata (max_values: None, max_size: None, mode: Measured)
+	/// Not benchmarked yet, derived from the `register_asset_location` results and the metadata write.
+	fn register_asset_location_with_metadata() -> Weight {
+		Self::register_asset_location()
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
 	}
 }
\ No newline at end of file
@@ -0,0 +1,58 @@
+pragma solidity ^0.8.0;
+
+/**
+ * @title Custom signatures interface.
+ *
+ * Relays Substrate calls signed by users with custom signatures (e.g. Ethereum wallets).
+ * Payload `version` is `1` for `(magic, nonce, call)` payloads and `2` for payloads bound
+ * to the chain, `expires_at` of version `2` payloads is the last valid block or `0` for
+ * immortal payloads.
+ */
+interface CustomSignatures {
+    /**
+     * @dev Verify signature of a signed call.
+     * @param signer - Substrate account ID of the signer
+     * @param call - SCALE encoded runtime call
+     * @param signature - signature bytes
+     * @param nonce - signer account nonce the payload was signed with
+     * @param version - signed payload version
+     * @param expires_at - last block the payload is valid in (version 2 only)
+     * @return A boolean confirming whether the signature is valid for the call.
+     */
+    function verify(
+        bytes32 signer,
+        bytes calldata call,
+        bytes calldata signature,
+        uint256 nonce,
+        uint8 version,
+        uint64 expires_at
+    ) external view returns (bool);
+
+    /**
+     * @dev Dispatch a signed call on behalf of the signer, the signer pays the call fee.
+     * @return A boolean confirming whether the call was submitted.
+     */
+    function submit(
+        bytes32 signer,
+        bytes calldata call,
+        bytes calldata signature,
+        uint256 nonce,
+        uint8 version,
+        uint64 expires_at
+    ) external returns (bool);
+
+    /**
+     * @dev Dispatch a signed call on behalf of the signer, the caller pays the call fee
+     * from the allowance it gave to the signer.
+     * The signer signs a sponsored call payload, which includes the caller's sponsoring account.
+     * @return A boolean confirming whether the call was submitted.
+     */
+    function submit_sponsored(
+        bytes32 signer,
+        bytes calldata call,
+        bytes calldata signature,
+        uint256 nonce,
+        uint8 version,
+        uint64 expires_at
+    ) external returns (bool);
+}
@@ -0,0 +1,301 @@
+// This file is part of Astar.
+
+// Copyright (C) 2019-2023 Stake Technologies Pte.Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// Astar is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Astar is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Astar. If not, see <http://www.gnu.org/licenses/>.
+
+//! Custom signatures interface, relays signed Substrate calls from EVM.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![cfg_attr(test, feature(assert_matches))]
+
+use fp_evm::{PrecompileHandle, PrecompileOutput};
+use frame_support::dispatch::{Dispatchable, GetDispatchInfo, PostDispatchInfo};
+use pallet_custom_signatures::{
+    ethereum::{Eip712Signature, EthereumSignature},
+    multi::MultiSchemeSignature,
+    secp256r1::{Secp256r1Signature, WebAuthnSignature},
+    PayloadVersion,
+};
+use pallet_evm::Precompile;
+use pallet_unified_accounts::UnifiedAddressMapper;
+use parity_scale_codec::{DecodeLimit, Encode};
+use sp_core::{H256, U256};
+use sp_std::marker::PhantomData;
+use sp_std::prelude::*;
+
+use precompile_utils::{
+    revert, succeed, Bytes, EvmDataWriter, EvmResult, FunctionModifier, PrecompileHandleExt,
+    RuntimeHelper,
+};
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+/// Maximum nesting depth of a decoded call.
+const CALL_DECODE_DEPTH_LIMIT: u32 = 8;
+
+/// Cost of a secp256k1 public key recovery, same as the `ecrecover` precompile.
+const ECRECOVER_GAS_COST: u64 = 3_000;
+
+/// Cost of a P-256 signature verification, same as the `P256VERIFY` precompile (RIP-7212).
+const P256_VERIFY_GAS_COST: u64 = 3_450;
+
+/// Cost of hashing a word of the signed message with Keccak-256, same as `SHA3`.
+const KECCAK_GAS_COST_PER_WORD: u64 = 6;
+
+/// Cost of hashing a word of the signed message with SHA-256, same as the `sha256` precompile.
+const SHA256_GAS_COST_PER_WORD: u64 = 12;
+
+/// Number of 32 byte words needed to hold `len` bytes.
+fn words(len: usize) -> u64 {
+    (len as u64).saturating_add(31) / 32
+}
+
+/// Gas cost of a signature verification, depends on the signature scheme and the size of the
+/// hashed data.
+pub trait VerifyGasCost {
+    /// Cost of verifying the signature of a `message_len` bytes long message.
+    fn verify_gas_cost(&self, message_len: usize) -> u64;
+}
+
+impl VerifyGasCost for EthereumSignature {
+    fn verify_gas_cost(&self, message_len: usize) -> u64 {
+        ECRECOVER_GAS_COST
+            .saturating_add(KECCAK_GAS_COST_PER_WORD.saturating_mul(words(message_len)))
+    }
+}
+
+impl VerifyGasCost for Eip712Signature {
+    fn verify_gas_cost(&self, message_len: usize) -> u64 {
+        ECRECOVER_GAS_COST
+            .saturating_add(KECCAK_GAS_COST_PER_WORD.saturating_mul(words(message_len)))
+    }
+}
+
+impl VerifyGasCost for Secp256r1Signature {
+    fn verify_gas_cost(&self, message_len: usize) -> u64 {
+        P256_VERIFY_GAS_COST
+            .saturating_add(SHA256_GAS_COST_PER_WORD.saturating_mul(words(message_len)))
+    }
+}
+
+/// Besides the message, the client data and the authenticator data are hashed too.
+impl VerifyGasCost for WebAuthnSignature {
+    fn verify_gas_cost(&self, message_len: usize) -> u64 {
+        let hashed_words = words(message_len)
+            .saturating_add(words(self.client_data_json.len()))
+            .saturating_add(words(self.authenticator_data.len().saturating_add(32)));
+        P256_VERIFY_GAS_COST.saturating_add(SHA256_GAS_COST_PER_WORD.saturating_mul(hashed_words))
+    }
+}
+
+impl VerifyGasCost for MultiSchemeSignature {
+    fn verify_gas_cost(&self, message_len: usize) -> u64 {
+        match self {
+            Self::Ethereum(signature) => signature.verify_gas_cost(message_len),
+            Self::Eip712(signature) => signature.verify_gas_cost(message_len),
+            Self::Secp256r1(signature) => signature.verify_gas_cost(message_len),
+            Self::WebAuthn(signature) => signature.verify_gas_cost(message_len),
+        }
+    }
+}
+
+#[precompile_utils::generate_function_selector]
+#[derive(Debug, PartialEq)]
+pub enum Action {
+    Verify = "verify(bytes32,bytes,bytes,uint256,uint8,uint64)",
+    Submit = "submit(bytes32,bytes,bytes,uint256,uint8,uint64)",
+    SubmitSponsored = "submit_sponsored(bytes32,bytes,bytes,uint256,uint8,uint64)",
+}
+
+/// Signed call as passed to the precompile.
+struct SignedCall<R: pallet_custom_signatures::Config> {
+    signer: R::AccountId,
+    call: Box<<R as pallet_custom_signatures::Config>::RuntimeCall>,
+    signature: Vec<u8>,
+    nonce: R::Index,
+    version: PayloadVersion<R::BlockNumber>,
+}
+
+/// A precompile to wrap custom signatures verification and dispatch.
+///
+/// The EVM caller is mapped to the native account sponsoring calls by `M`.
+pub struct CustomSignaturesPrecompile<R, M>(PhantomData<(R, M)>);
+
+impl<R, M> Precompile for CustomSignaturesPrecompile<R, M>
+where
+    R: pallet_evm::Config + pallet_custom_signatures::Config,
+    M: UnifiedAddressMapper<R::AccountId>,
+    <R as frame_system::Config>::RuntimeCall: From<pallet_custom_signatures::Call<R>>
+        + Dispatchable<PostInfo = PostDispatchInfo>
+        + GetDispatchInfo,
+    <<R as frame_system::Config>::RuntimeCall as Dispatchable>::RuntimeOrigin:
+        From<Option<R::AccountId>>,
+    R::AccountId: From<[u8; 32]>,
+    <R as pallet_custom_signatures::Config>::Signature: VerifyGasCost,
+{
+    fn execute(handle: &mut impl PrecompileHandle) -> EvmResult<PrecompileOutput> {
+        log::trace!(target: "custom-signatures-precompile", "In custom signatures precompile");
+
+        let selector = handle.read_selector()?;
+
+        handle.check_function_modifier(match selector {
+            Action::Verify => FunctionModifier::View,
+            _ => FunctionModifier::NonPayable,
+        })?;
+
+        match selector {
+            // Views
+            Action::Verify => Self::verify(handle),
+            // Dispatchables
+            Action::Submit => Self::submit(handle),
+            Action::SubmitSponsored => Self::submit_sponsored(handle),
+        }
+    }
+}
+
+impl<R, M> CustomSignaturesPrecompile<R, M>
+where
+    R: pallet_evm::Config + pallet_custom_signatures::Config,
+    M: UnifiedAddressMapper<R::AccountId>,
+    <R as frame_system::Config>::RuntimeCall: From<pallet_custom_signatures::Call<R>>
+        + Dispatchable<PostInfo = PostDispatchInfo>
+        + GetDispatchInfo,
+    <<R as frame_system::Config>::RuntimeCall as Dispatchable>::RuntimeOrigin:
+        From<Option<R::AccountId>>,
+    R::AccountId: From<[u8; 32]>,
+    <R as pallet_custom_signatures::Config>::Signature: VerifyGasCost,
+{
+    /// Checks the signature of a signed call, returns `false` if it can't be decoded.
+    ///
+    /// The cost depends on the signature scheme and the length of the call.
+    fn verify(handle: &mut impl PrecompileHandle) -> EvmResult<PrecompileOutput> {
+        handle.record_cost(RuntimeHelper::<R>::db_read_gas_cost())?;
+        let signed = Self::read_signed_call(handle)?;
+
+        let signature =
+            match <R as pallet_custom_signatures::Config>::Signature::try_from(signed.signature) {
+                Ok(signature) => signature,
+                // Return `false` if signature encoding is wrong
+                Err(_) => return Ok(succeed(EvmDataWriter::new().write(false).build())),
+            };
+        handle.record_cost(signature.verify_gas_cost(signed.call.encoded_size()))?;
+
+        let is_valid = pallet_custom_signatures::Pallet::<R>::valid_signature(
+            &signed.call,
+            &signed.signer,
+            &signature,
+            &signed.nonce,
+            &signed.version,
+        );
+
+        log::trace!(
+            target: "custom-signatures-precompile",
+            "Verified signature of {:?} is {:?}",
+            signed.signer, is_valid,
+        );
+
+        Ok(succeed(EvmDataWriter::new().write(is_valid).build()))
+    }
+
+    /// Dispatches a signed call, the fee is paid by the signer.
+    fn submit(handle: &mut impl PrecompileHandle) -> EvmResult<PrecompileOutput> {
+        let signed = Self::read_signed_call(handle)?;
+        log::trace!(target: "custom-signatures-precompile", "submit for {:?}", signed.signer);
+
+        let call = pallet_custom_signatures::Call::<R>::call {
+            call: signed.call,
+            signer: signed.signer,
+            signature: signed.signature,
+            nonce: signed.nonce,
+            version: signed.version,
+        };
+
+        RuntimeHelper::<R>::try_dispatch(handle, None.into(), call)?;
+
+        Ok(succeed(EvmDataWriter::new().write(true).build()))
+    }
+
+    /// Dispatches a signed call, the fee is paid by the caller from its sponsor allowance.
+    fn submit_sponsored(handle: &mut impl PrecompileHandle) -> EvmResult<PrecompileOutput> {
+        let signed = Self::read_signed_call(handle)?;
+        log::trace!(
+            target: "custom-signatures-precompile",
+            "submit_sponsored for {:?}", signed.signer,
+        );
+
+        let origin = M::to_account_id_or_default(&handle.context().caller);
+        let call = pallet_custom_signatures::Call::<R>::sponsored_call {
+            call: signed.call,
+            signer: signed.signer,
+            signature: signed.signature,
+            nonce: signed.nonce,
+            version: signed.version,
+        };
+
+        RuntimeHelper::<R>::try_dispatch(handle, Some(origin).into(), call)?;
+
+        Ok(succeed(EvmDataWriter::new().write(true).build()))
+    }
+
+    /// Parses `(signer, call, signature, nonce, version, expires_at)` arguments.
+    fn read_signed_call(handle: &mut impl PrecompileHandle) -> EvmResult<SignedCall<R>> {
+        let mut input = handle.read_input()?;
+        input.expect_arguments(6)?;
+
+        let signer: [u8; 32] = input.read::<H256>()?.into();
+        let call: Vec<u8> = input.read::<Bytes>()?.into();
+        let signature: Vec<u8> = input.read::<Bytes>()?.into();
+        let nonce: U256 = input.read()?;
+        let version: u8 = input.read()?;
+        let expires_at: u64 = input.read()?;
+
+        let call = <R as pallet_custom_signatures::Config>::RuntimeCall::decode_with_depth_limit(
+            CALL_DECODE_DEPTH_LIMIT,
+            &mut &call[..],
+        )
+        .map_err(|_| revert("Failed to decode call"))?;
+
+        let nonce = u128::try_from(nonce)
+            .ok()
+            .and_then(|nonce| R::Index::try_from(nonce).ok())
+            .ok_or_else(|| revert("Nonce overflow"))?;
+
+        let version = match version {
+            1 => PayloadVersion::V1,
+            2 => PayloadVersion::V2 {
+                expires_at: match expires_at {
+                    0 => None,
+                    at => Some(
+                        R::BlockNumber::try_from(at)
+                            .map_err(|_| revert("Expiry block overflow"))?,
+                    ),
+                },
+            },
+            _ => return Err(revert("Unknown payload version")),
+        };
+
+        Ok(SignedCall {
+            signer: signer.into(),
+            call: Box::new(call),
+            signature,
+            nonce,
+            version,
+        })
+    }
+}
@@ -0,0 +1,252 @@
+// This file is part of Astar.
+
+// Copyright (C) 2019-2023 Stake Technologies Pte.Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// Astar is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Astar is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Astar. If not, see <http://www.gnu.org/licenses/>.
+
+//! Testing utilities.
+
+use super::*;
+
+use frame_support::{
+    construct_runtime, parameter_types,
+    traits::{ConstU128, Everything},
+    weights::{ConstantMultiplier, Weight},
+};
+use pallet_custom_signatures::ethereum::EthereumSignature;
+use pallet_evm::{
+    AddressMapping, EnsureAddressNever, EnsureAddressRoot, HashedAddressMapping, PrecompileResult,
+    PrecompileSet,
+};
+use pallet_unified_accounts::HashedAccountMapping;
+use sp_core::{H160, H256};
+use sp_runtime::{
+    testing::Header,
+    traits::{BlakeTwo256, IdentifyAccount, IdentityLookup, Verify},
+    transaction_validity::TransactionPriority,
+    AccountId32, MultiSignature,
+};
+
+pub type AccountId = AccountId32;
+pub type Balance = u128;
+pub type BlockNumber = u64;
+pub type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Runtime>;
+pub type Block = frame_system::mocking::MockBlock<Runtime>;
+
+pub const PRECOMPILE_ADDRESS: H160 = H160::repeat_byte(0xBB);
+pub const RELAYER: H160 = H160::repeat_byte(0xAA);
+
+/// Secret key of the account signing calls.
+pub const ECDSA_SEED: [u8; 32] =
+    hex_literal::hex!["7e9c7ad85df5cdc88659f53e06fb2eb9bab3ebc59083a3190eaf2c730332529c"];
+
+/// Magic number of the signed payload.
+pub const MAGIC_NUMBER: u16 = 0xff50;
+
+/// Initial balance of the signer and relayer accounts.
+pub const INITIAL_BALANCE: Balance = 1_000_000_000_000_000;
+
+/// Account signing calls.
+pub fn signer() -> AccountId {
+    let pair = <sp_core::ecdsa::Pair as sp_core::Pair>::from_seed(&ECDSA_SEED);
+    <MultiSignature as Verify>::Signer::from(pair.public()).into_account()
+}
+
+/// Substrate account of the relayer contract, its address isn't claimed.
+pub fn relayer() -> AccountId {
+    HashedAddressMapping::<BlakeTwo256>::into_account_id(RELAYER)
+}
+
+parameter_types! {
+    pub const BlockHashCount: u64 = 250;
+    pub const SS58Prefix: u8 = 42;
+}
+
+impl frame_system::Config for Runtime {
+    type BaseCallFilter = Everything;
+    type DbWeight = ();
+    type RuntimeOrigin = RuntimeOrigin;
+    type Index = u64;
+    type BlockNumber = BlockNumber;
+    type RuntimeCall = RuntimeCall;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = AccountId;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type Header = Header;
+    type RuntimeEvent = RuntimeEvent;
+    type BlockHashCount = BlockHashCount;
+    type Version = ();
+    type PalletInfo = PalletInfo;
+    type AccountData = pallet_balances::AccountData<Balance>;
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type BlockWeights = ();
+    type BlockLength = ();
+    type SS58Prefix = SS58Prefix;
+    type OnSetCode = ();
+    type MaxConsumers = frame_support::traits::ConstU32<16>;
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct TestPrecompileSet<R>(PhantomData<R>);
+
+impl<R> PrecompileSet for TestPrecompileSet<R>
+where
+    R: pallet_evm::Config + pallet_unified_accounts::Config,
+    CustomSignaturesPrecompile<R, pallet_unified_accounts::Pallet<R>>: Precompile,
+{
+    fn execute(&self, handle: &mut impl PrecompileHandle) -> Option<PrecompileResult> {
+        match handle.code_address() {
+            a if a == PRECOMPILE_ADDRESS => Some(CustomSignaturesPrecompile::<
+                R,
+                pallet_unified_accounts::Pallet<R>,
+            >::execute(handle)),
+            _ => None,
+        }
+    }
+
+    fn is_precompile(&self, address: H160) -> bool {
+        address == PRECOMPILE_ADDRESS
+    }
+}
+
+parameter_types! {
+    pub const MinimumPeriod: u64 = 5;
+}
+
+impl pallet_timestamp::Config for Runtime {
+    type Moment = u64;
+    type OnTimestampSet = ();
+    type MinimumPeriod = MinimumPeriod;
+    type WeightInfo = ();
+}
+
+parameter_types! {
+    pub const ExistentialDeposit: u128 = 1;
+}
+
+impl pallet_balances::Config for Runtime {
+    type MaxReserves = ();
+    type ReserveIdentifier = ();
+    type MaxLocks = ();
+    type Balance = Balance;
+    type RuntimeEvent = RuntimeEvent;
+    type DustRemoval = ();
+    type ExistentialDeposit = ExistentialDeposit;
+    type AccountStore = System;
+    type WeightInfo = ();
+}
+
+parameter_types! {
+    pub const PrecompilesValue: TestPrecompileSet<Runtime> =
+        TestPrecompileSet(PhantomData);
+    pub const WeightPerGas: Weight = Weight::from_ref_time(1);
+}
+
+impl pallet_evm::Config for Runtime {
+    type FeeCalculator = ();
+    type GasWeightMapping = pallet_evm::FixedGasWeightMapping<Self>;
+    type WeightPerGas = WeightPerGas;
+    type CallOrigin = EnsureAddressRoot<AccountId>;
+    type WithdrawOrigin = EnsureAddressNever<AccountId>;
+    type AddressMapping = UnifiedAccounts;
+    type Currency = Balances;
+    type RuntimeEvent = RuntimeEvent;
+    type Runner = pallet_evm::runner::stack::Runner<Self>;
+    type PrecompilesType = TestPrecompileSet<Self>;
+    type PrecompilesValue = PrecompilesValue;
+    type ChainId = ();
+    type OnChargeTransaction = ();
+    type BlockGasLimit = ();
+    type BlockHashMapping = pallet_evm::SubstrateBlockHashMapping<Self>;
+    type OnCreate = ();
+    type FindAuthor = ();
+    type WeightInfo = ();
+}
+
+parameter_types! {
+    pub const CallFee: Balance = 42;
+    pub const CallMagicNumber: u16 = MAGIC_NUMBER;
+    pub const ChainId: u64 = 592;
+    pub const MaxBatchCalls: u32 = 4;
+    pub const WebAuthnRpId: &'static [u8] = b"astar.network";
+    pub const WebAuthnOrigin: &'static [u8] = b"https://astar.network";
+    pub const Priority: TransactionPriority = TransactionPriority::MAX;
+}
+
+impl pallet_custom_signatures::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type RuntimeCall = RuntimeCall;
+    type Signature = EthereumSignature;
+    type Signer = <MultiSignature as Verify>::Signer;
+    type CallMagicNumber = CallMagicNumber;
+    type ChainId = ChainId;
+    type MaxBatchCalls = MaxBatchCalls;
+    type WebAuthnRpId = WebAuthnRpId;
+    type WebAuthnOrigin = WebAuthnOrigin;
+    type Currency = Balances;
+    type CallFee = CallFee;
+    type WeightToFee = ConstantMultiplier<Balance, ConstU128<1>>;
+    type OnChargeTransaction = ();
+    type UnsignedPriority = Priority;
+}
+
+impl pallet_unified_accounts::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type DefaultEvmToNative = HashedAddressMapping<BlakeTwo256>;
+    type DefaultNativeToEvm = HashedAccountMapping<BlakeTwo256>;
+    type Currency = Balances;
+    type ChainId = ChainId;
+    type WeightInfo = ();
+}
+
+// Configure a mock runtime to test the pallet.
+construct_runtime!(
+    pub enum Runtime where
+        Block = Block,
+        NodeBlock = Block,
+        UncheckedExtrinsic = UncheckedExtrinsic,
+    {
+        System: frame_system,
+        Balances: pallet_balances,
+        Evm: pallet_evm,
+        Timestamp: pallet_timestamp,
+        CustomSignatures: pallet_custom_signatures,
+        UnifiedAccounts: pallet_unified_accounts,
+    }
+);
+
+#[derive(Default)]
+pub(crate) struct ExtBuilder;
+
+impl ExtBuilder {
+    pub(crate) fn build(self) -> sp_io::TestExternalities {
+        let mut t = frame_system::GenesisConfig::default()
+            .build_storage::<Runtime>()
+            .expect("Frame system builds valid default genesis config");
+
+        pallet_balances::GenesisConfig::<Runtime> {
+            balances: vec![(signer(), INITIAL_BALANCE), (relayer(), INITIAL_BALANCE)],
+        }
+        .assimilate_storage(&mut t)
+        .expect("Pallet balances storage can be assimilated");
+
+        let mut ext = sp_io::TestExternalities::new(t);
+        ext.execute_with(|| System::set_block_number(1));
+        ext
+    }
+}
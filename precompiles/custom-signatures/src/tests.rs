@@ -0,0 +1,301 @@
+// This file is part of Astar.
+
+// Copyright (C) 2019-2023 Stake Technologies Pte.Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// Astar is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Astar is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Astar. If not, see <http://www.gnu.org/licenses/>.
+
+use crate::mock::*;
+use crate::*;
+
+use frame_support::traits::Currency;
+use pallet_custom_signatures::SPONSORED_PAYLOAD_TAG;
+use parity_scale_codec::Encode;
+use precompile_utils::testing::*;
+use sp_io::hashing::keccak_256;
+use sp_runtime::AccountId32;
+
+fn precompiles() -> TestPrecompileSet<Runtime> {
+    PrecompilesValue::get()
+}
+
+/// Simple `eth_sign` implementation, should be equal to exported by RPC
+fn eth_sign(seed: &[u8; 32], data: &[u8]) -> Vec<u8> {
+    let call_msg = pallet_custom_signatures::ethereum::signable_message(data);
+    let ecdsa_msg = libsecp256k1::Message::parse(&keccak_256(&call_msg));
+    let secret = libsecp256k1::SecretKey::parse(seed).expect("valid seed");
+    let (signature, recovery_id) = libsecp256k1::sign(&ecdsa_msg, &secret);
+    let mut out = Vec::new();
+    out.extend_from_slice(&signature.serialize()[..]);
+    // Fix recovery ID: Ethereum uses 27/28 notation
+    out.push(recovery_id.serialize() + 27);
+    out
+}
+
+fn transfer(value: Balance) -> RuntimeCall {
+    pallet_balances::Call::<Runtime>::transfer {
+        dest: AccountId32::new([1u8; 32]),
+        value,
+    }
+    .into()
+}
+
+fn input(action: Action, call: &RuntimeCall, signature: &[u8], nonce: u64) -> Vec<u8> {
+    let signer: [u8; 32] = signer().into();
+    EvmDataWriter::new_with_selector(action)
+        .write(H256::from(signer))
+        .write(Bytes::from(&call.encode()[..]))
+        .write(Bytes::from(signature))
+        .write(U256::from(nonce))
+        .write(1u8)
+        .write(0u64)
+        .build()
+}
+
+#[test]
+fn verify_works() {
+    ExtBuilder::default().build().execute_with(|| {
+        let call = transfer(1_000);
+        let signature = eth_sign(&ECDSA_SEED, &(MAGIC_NUMBER, 0u64, call.clone()).encode());
+
+        precompiles()
+            .prepare_test(
+                RELAYER,
+                PRECOMPILE_ADDRESS,
+                input(Action::Verify, &call, &signature, 0),
+            )
+            .expect_cost(
+                RuntimeHelper::<Runtime>::db_read_gas_cost()
+                    + 3_000
+                    + 6 * ((call.encoded_size() as u64 + 31) / 32),
+            )
+            .expect_no_logs()
+            .execute_returns(EvmDataWriter::new().write(true).build());
+
+        // nonce is part of the signed payload
+        precompiles()
+            .prepare_test(
+                RELAYER,
+                PRECOMPILE_ADDRESS,
+                input(Action::Verify, &call, &signature, 1),
+            )
+            .execute_returns(EvmDataWriter::new().write(false).build());
+
+        // wrong signature length
+        precompiles()
+            .prepare_test(
+                RELAYER,
+                PRECOMPILE_ADDRESS,
+                input(Action::Verify, &call, &signature[..64], 0),
+            )
+            .execute_returns(EvmDataWriter::new().write(false).build());
+    });
+}
+
+#[test]
+fn verify_gas_cost_depends_on_scheme_and_length() {
+    use pallet_custom_signatures::secp256r1::{
+        Secp256r1Public, Secp256r1Signature, WebAuthnSignature,
+    };
+
+    let ethereum = pallet_custom_signatures::ethereum::EthereumSignature([0u8; 65]);
+    assert_eq!(ethereum.verify_gas_cost(0), 3_000);
+    assert_eq!(ethereum.verify_gas_cost(1), 3_006);
+    assert_eq!(ethereum.verify_gas_cost(64), 3_012);
+    assert_eq!(ethereum.verify_gas_cost(65), 3_018);
+
+    let secp256r1 = Secp256r1Signature {
+        public: Secp256r1Public([2u8; 33]),
+        signature: [0u8; 64],
+    };
+    assert_eq!(secp256r1.verify_gas_cost(64), 3_450 + 2 * 12);
+
+    // client and authenticator data are hashed as well
+    let webauthn = WebAuthnSignature {
+        public: Secp256r1Public([2u8; 33]),
+        authenticator_data: vec![0u8; 37],
+        client_data_json: vec![0u8; 100],
+        signature: [0u8; 64],
+    };
+    assert_eq!(webauthn.verify_gas_cost(64), 3_450 + (2 + 4 + 3) * 12);
+    assert_eq!(
+        pallet_custom_signatures::multi::MultiSchemeSignature::WebAuthn(webauthn)
+            .verify_gas_cost(64),
+        3_450 + (2 + 4 + 3) * 12
+    );
+}
+
+#[test]
+fn submit_works() {
+    ExtBuilder::default().build().execute_with(|| {
+        let call = transfer(1_000);
+        let signature = eth_sign(&ECDSA_SEED, &(MAGIC_NUMBER, 0u64, call.clone()).encode());
+
+        precompiles()
+            .prepare_test(
+                RELAYER,
+                PRECOMPILE_ADDRESS,
+                input(Action::Submit, &call, &signature, 0),
+            )
+            .execute_returns(EvmDataWriter::new().write(true).build());
+
+        assert_eq!(System::account_nonce(signer()), 1);
+        assert_eq!(Balances::free_balance(AccountId32::new([1u8; 32])), 1_000);
+        // relayer doesn't pay the call fee
+        assert_eq!(Balances::free_balance(relayer()), INITIAL_BALANCE);
+        assert!(Balances::free_balance(signer()) < INITIAL_BALANCE - 1_000);
+
+        // signature can't be replayed
+        precompiles()
+            .prepare_test(
+                RELAYER,
+                PRECOMPILE_ADDRESS,
+                input(Action::Submit, &call, &signature, 0),
+            )
+            .execute_reverts(|output| output.starts_with(b"Dispatched call failed"));
+    });
+}
+
+#[test]
+fn submit_sponsored_works() {
+    ExtBuilder::default().build().execute_with(|| {
+        let call = transfer(1_000);
+        let payload = (
+            MAGIC_NUMBER,
+            SPONSORED_PAYLOAD_TAG,
+            0u64,
+            relayer(),
+            call.clone(),
+        );
+        let signature = eth_sign(&ECDSA_SEED, &payload.encode());
+
+        // relayer didn't give any allowance yet
+        precompiles()
+            .prepare_test(
+                RELAYER,
+                PRECOMPILE_ADDRESS,
+                input(Action::SubmitSponsored, &call, &signature, 0),
+            )
+            .execute_reverts(|output| output.starts_with(b"Dispatched call failed"));
+
+        assert!(CustomSignatures::set_sponsor_allowance(
+            RuntimeOrigin::signed(relayer()),
+            signer(),
+            INITIAL_BALANCE / 2,
+        )
+        .is_ok());
+
+        precompiles()
+            .prepare_test(
+                RELAYER,
+                PRECOMPILE_ADDRESS,
+                input(Action::SubmitSponsored, &call, &signature, 0),
+            )
+            .execute_returns(EvmDataWriter::new().write(true).build());
+
+        assert_eq!(System::account_nonce(signer()), 1);
+        assert_eq!(Balances::free_balance(AccountId32::new([1u8; 32])), 1_000);
+        // fee paid by the relayer
+        assert_eq!(Balances::free_balance(signer()), INITIAL_BALANCE - 1_000);
+        let fee = INITIAL_BALANCE - Balances::free_balance(relayer());
+        assert!(fee > CallFee::get());
+        assert_eq!(
+            CustomSignatures::sponsor_allowance(relayer(), signer()),
+            INITIAL_BALANCE / 2 - fee
+        );
+    });
+}
+
+#[test]
+fn submit_sponsored_by_claimed_address_works() {
+    ExtBuilder::default().build().execute_with(|| {
+        let call = transfer(1_000);
+
+        // relayer address is claimed by the sponsor
+        let sponsor = AccountId32::new([2u8; 32]);
+        let payload = (
+            MAGIC_NUMBER,
+            SPONSORED_PAYLOAD_TAG,
+            0u64,
+            sponsor.clone(),
+            call.clone(),
+        );
+        let signature = eth_sign(&ECDSA_SEED, &payload.encode());
+        let _ = Balances::deposit_creating(&sponsor, INITIAL_BALANCE);
+        pallet_unified_accounts::EvmToNative::<Runtime>::insert(RELAYER, sponsor.clone());
+        pallet_unified_accounts::NativeToEvm::<Runtime>::insert(sponsor.clone(), RELAYER);
+        assert!(CustomSignatures::set_sponsor_allowance(
+            RuntimeOrigin::signed(sponsor.clone()),
+            signer(),
+            INITIAL_BALANCE / 2,
+        )
+        .is_ok());
+
+        precompiles()
+            .prepare_test(
+                RELAYER,
+                PRECOMPILE_ADDRESS,
+                input(Action::SubmitSponsored, &call, &signature, 0),
+            )
+            .execute_returns(EvmDataWriter::new().write(true).build());
+
+        // fee paid by the sponsor, not by the default account of the relayer address
+        assert_eq!(Balances::free_balance(relayer()), INITIAL_BALANCE);
+        let fee = INITIAL_BALANCE - Balances::free_balance(&sponsor);
+        assert!(fee > CallFee::get());
+        assert_eq!(
+            CustomSignatures::sponsor_allowance(&sponsor, signer()),
+            INITIAL_BALANCE / 2 - fee
+        );
+    });
+}
+
+#[test]
+fn bad_input_reverts() {
+    ExtBuilder::default().build().execute_with(|| {
+        let signer: [u8; 32] = signer().into();
+
+        // undecodable call
+        precompiles()
+            .prepare_test(
+                RELAYER,
+                PRECOMPILE_ADDRESS,
+                EvmDataWriter::new_with_selector(Action::Verify)
+                    .write(H256::from(signer))
+                    .write(Bytes::from(&[0xff, 0xff][..]))
+                    .write(Bytes::from(&[0u8; 65][..]))
+                    .write(U256::zero())
+                    .write(1u8)
+                    .write(0u64)
+                    .build(),
+            )
+            .execute_reverts(|output| output == b"Failed to decode call");
+
+        // unknown payload version
+        precompiles()
+            .prepare_test(
+                RELAYER,
+                PRECOMPILE_ADDRESS,
+                EvmDataWriter::new_with_selector(Action::Verify)
+                    .write(H256::from(signer))
+                    .write(Bytes::from(&transfer(1).encode()[..]))
+                    .write(Bytes::from(&[0u8; 65][..]))
+                    .write(U256::zero())
+                    .write(3u8)
+                    .write(0u64)
+                    .build(),
+            )
+            .execute_reverts(|output| output == b"Unknown payload version");
+    });
+}
@@ -4,17 +4,60 @@ pragma solidity ^0.8.0;
  * @title XVM interface.
  */
 interface XVM {
+    /**
+     * @dev Raised when the XVM call fails.
+     * @param code - error code: 1 VM not recognized, 2 encoding failure,
+     *               3 context conversion failed, 4 out of gas, 5 execution error,
+     *               6 reverted, 7 invalid target, 11 call depth exceeded,
+     *               12 reentrant call, 13 call blocked
+     * @param data - callee revert data, or the VM exit reason
+     */
+    error XvmError(uint8 code, bytes data);
+
     /**
      * @dev Execute external VM call
-     * @param context - execution context
+     * @param context - SCALE-encoded execution context, including the native value to transfer,
+     *                  its max weight is replaced by the gas left to the call
      * @param to - call recepient
      * @param input - SCALE-encoded call arguments
-     * @return success - operation outcome
-     * @return data - output data if successful, error data on error
+     * @return success - operation outcome, failures revert with `XvmError`
+     * @return data - call output data
      */
     function xvm_call(
         bytes calldata context,
         bytes calldata to,
         bytes calldata input
     ) external returns (bool success, bytes memory data);
+
+    /**
+     * @dev Execute external VM call in read-only mode, state changes are discarded
+     * @param context - SCALE-encoded execution context, its max weight is replaced by the gas
+     *                  left to the call
+     * @param to - call recepient
+     * @param input - SCALE-encoded call arguments
+     * @return success - operation outcome, failures revert with `XvmError`
+     * @return data - call output data
+     */
+    function xvm_query_call(
+        bytes calldata context,
+        bytes calldata to,
+        bytes calldata input
+    ) external view returns (bool success, bytes memory data);
+
+    /**
+     * @dev Instantiate external VM contract
+     * @param context - SCALE-encoded execution context, including the native value to transfer,
+     *                  its max weight is replaced by the gas left to the call
+     * @param code - contract code
+     * @param input - SCALE-encoded constructor arguments
+     * @param salt - salt making the contract address deterministic
+     * @return success - operation outcome, failures revert with `XvmError`
+     * @return data - encoded address of the new contract
+     */
+    function xvm_instantiate(
+        bytes calldata context,
+        bytes calldata code,
+        bytes calldata input,
+        bytes calldata salt
+    ) external returns (bool success, bytes memory data);
 }
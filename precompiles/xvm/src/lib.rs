@@ -19,17 +19,17 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 #![cfg_attr(test, feature(assert_matches))]
 
-use fp_evm::{PrecompileHandle, PrecompileOutput};
+use fp_evm::{PrecompileFailure, PrecompileHandle, PrecompileOutput};
 use frame_support::dispatch::{Dispatchable, GetDispatchInfo, PostDispatchInfo};
-use pallet_evm::{AddressMapping, Precompile};
-use pallet_xvm::XvmContext;
-use parity_scale_codec::Decode;
-use sp_runtime::codec::Encode;
+use pallet_evm::{GasWeightMapping, Precompile};
+use pallet_unified_accounts::UnifiedAddressMapper;
+use pallet_xvm::{WeightInfo, XvmContext};
 use sp_std::marker::PhantomData;
 use sp_std::prelude::*;
 
 use precompile_utils::{
-    revert, succeed, Bytes, EvmDataWriter, EvmResult, FunctionModifier, PrecompileHandleExt,
+    keccak256, revert, succeed, Bytes, EvmDataWriter, EvmResult, FunctionModifier,
+    PrecompileHandleExt,
 };
 
 #[cfg(test)]
@@ -41,14 +41,41 @@ mod tests;
 #[derive(Debug, PartialEq)]
 pub enum Action {
     XvmCall = "xvm_call(bytes,bytes,bytes)",
+    XvmQueryCall = "xvm_query_call(bytes,bytes,bytes)",
+    XvmInstantiate = "xvm_instantiate(bytes,bytes,bytes,bytes)",
+}
+
+/// Hash of the `XvmError(uint8,bytes)` custom error, its first 4 bytes are the selector.
+pub const XVM_ERROR_SELECTOR: [u8; 32] = keccak256!("XvmError(uint8,bytes)");
+
+/// Reverts with the ABI-encoded `XvmError(code, data)` custom error.
+///
+/// `data` is the callee revert data, or the VM exit reason.
+fn revert_with_error(error: &pallet_xvm::XvmError) -> PrecompileFailure {
+    let selector = u32::from_be_bytes([
+        XVM_ERROR_SELECTOR[0],
+        XVM_ERROR_SELECTOR[1],
+        XVM_ERROR_SELECTOR[2],
+        XVM_ERROR_SELECTOR[3],
+    ]);
+
+    revert(
+        EvmDataWriter::new_with_selector(selector)
+            .write(error.code())
+            .write(Bytes(error.data().to_vec()))
+            .build(),
+    )
 }
 
 /// A precompile that expose XVM related functions.
-pub struct XvmPrecompile<T>(PhantomData<T>);
+///
+/// The EVM caller is mapped to the native account making the XVM call by `M`.
+pub struct XvmPrecompile<T, M>(PhantomData<(T, M)>);
 
-impl<R> Precompile for XvmPrecompile<R>
+impl<R, M> Precompile for XvmPrecompile<R, M>
 where
     R: pallet_evm::Config + pallet_xvm::Config,
+    M: UnifiedAddressMapper<R::AccountId>,
     <<R as frame_system::Config>::RuntimeCall as Dispatchable>::RuntimeOrigin:
         From<Option<R::AccountId>>,
     <R as frame_system::Config>::RuntimeCall:
@@ -59,44 +86,63 @@ where
 
         let selector = handle.read_selector()?;
 
-        handle.check_function_modifier(FunctionModifier::NonPayable)?;
+        handle.check_function_modifier(match selector {
+            Action::XvmQueryCall => FunctionModifier::View,
+            _ => FunctionModifier::NonPayable,
+        })?;
 
         match selector {
             // Dispatchables
-            Action::XvmCall => Self::xvm_call(handle),
+            Action::XvmCall => Self::xvm_call(handle, false),
+            Action::XvmInstantiate => Self::xvm_instantiate(handle),
+            // Views
+            Action::XvmQueryCall => Self::xvm_call(handle, true),
         }
     }
 }
 
-impl<R> XvmPrecompile<R>
+impl<R, M> XvmPrecompile<R, M>
 where
     R: pallet_evm::Config + pallet_xvm::Config,
+    M: UnifiedAddressMapper<R::AccountId>,
     <<R as frame_system::Config>::RuntimeCall as Dispatchable>::RuntimeOrigin:
         From<Option<R::AccountId>>,
     <R as frame_system::Config>::RuntimeCall:
         From<pallet_xvm::Call<R>> + Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo,
 {
-    fn xvm_call(handle: &mut impl PrecompileHandle) -> EvmResult<PrecompileOutput> {
+    fn xvm_call(handle: &mut impl PrecompileHandle, query: bool) -> EvmResult<PrecompileOutput> {
         let mut input = handle.read_input()?;
         input.expect_arguments(4)?;
 
-        // Read arguments and check it
-        // TODO: This approach probably needs to be revised - does contract call need to specify gas/weight? Usually it is implicit.
+        // XVM dispatch overhead is charged upfront, the remaining gas is the call budget.
+        handle.record_cost(R::GasWeightMapping::weight_to_gas(
+            <R as pallet_xvm::Config>::WeightInfo::xvm_call(),
+        ))?;
+
+        // Contexts of the legacy layout, without value, are accepted too.
         let context_raw = input.read::<Bytes>()?;
-        let context: XvmContext = Decode::decode(&mut context_raw.0.as_ref())
+        let mut context = XvmContext::decode_versioned(&context_raw.0)
             .map_err(|_| revert("can not decode XVM context"))?;
-
-        // Fetch the remaining gas (weight) available for execution
-        // TODO: rework
-        //let remaining_gas = handle.remaining_gas();
-        //let remaining_weight = R::GasWeightMapping::gas_to_weight(remaining_gas);
-        //context.max_weight = remaining_weight;
+        context.max_weight = R::GasWeightMapping::gas_to_weight(handle.remaining_gas(), true);
+        context.query = query;
 
         let call_to = input.read::<Bytes>()?.0;
         let call_input = input.read::<Bytes>()?.0;
 
-        let from = R::AddressMapping::into_account_id(handle.context().caller);
-        match &pallet_xvm::Pallet::<R>::xvm_bare_call(context, from, call_to, call_input) {
+        let caller = handle.context().caller;
+        let from = M::to_account_id_or_default(&caller);
+        let call_result = pallet_xvm::Pallet::<R>::in_origin_frame(
+            pallet_xvm::FRONTIER_VM_ID,
+            caller.as_bytes().to_vec(),
+            || pallet_xvm::Pallet::<R>::xvm_bare_call(context, from, call_to, call_input),
+        );
+
+        // Only the weight actually consumed by the call is charged.
+        handle.record_cost(R::GasWeightMapping::weight_to_gas(
+            pallet_xvm::consumed_weight(&call_result),
+        ))?;
+
+        match &call_result {
             Ok(success) => {
                 log::trace!(
                     target: "xvm-precompile::xvm_call",
@@ -117,16 +163,74 @@ where
                     "failure: {:?}", failure
                 );
 
-                let mut error_buffer = Vec::new();
-                failure.error().encode_to(&mut error_buffer);
+                Err(revert_with_error(failure.error()))
+            }
+        }
+    }
+
+    fn xvm_instantiate(handle: &mut impl PrecompileHandle) -> EvmResult<PrecompileOutput> {
+        let mut input = handle.read_input()?;
+        input.expect_arguments(4)?;
+
+        // XVM dispatch overhead is charged upfront, the remaining gas is the deployment budget.
+        handle.record_cost(R::GasWeightMapping::weight_to_gas(
+            <R as pallet_xvm::Config>::WeightInfo::xvm_instantiate(),
+        ))?;
+
+        let context_raw = input.read::<Bytes>()?;
+        let mut context = XvmContext::decode_versioned(&context_raw.0)
+            .map_err(|_| revert("can not decode XVM context"))?;
+        context.max_weight = R::GasWeightMapping::gas_to_weight(handle.remaining_gas(), true);
+
+        let code = input.read::<Bytes>()?.0;
+        let constructor_input = input.read::<Bytes>()?.0;
+        let salt = input.read::<Bytes>()?.0;
+
+        let caller = handle.context().caller;
+        let from = M::to_account_id_or_default(&caller);
+        let instantiate_result = pallet_xvm::Pallet::<R>::in_origin_frame(
+            pallet_xvm::FRONTIER_VM_ID,
+            caller.as_bytes().to_vec(),
+            || {
+                pallet_xvm::Pallet::<R>::xvm_bare_instantiate(
+                    context,
+                    from,
+                    code,
+                    constructor_input,
+                    salt,
+                )
+            },
+        );
+
+        // Only the weight actually consumed by the deployment is charged.
+        handle.record_cost(R::GasWeightMapping::weight_to_gas(
+            pallet_xvm::consumed_weight(&instantiate_result),
+        ))?;
+
+        match &instantiate_result {
+            Ok(success) => {
+                log::trace!(
+                    target: "xvm-precompile::xvm_instantiate",
+                    "success: {:?}", success
+                );
 
+                // Output is the encoded address of the new contract.
                 Ok(succeed(
                     EvmDataWriter::new()
-                        .write(false)
-                        .write(Bytes(error_buffer))
+                        .write(true)
+                        .write(Bytes(success.output().to_vec()))
                         .build(),
                 ))
             }
+
+            Err(failure) => {
+                log::trace!(
+                    target: "xvm-precompile::xvm_instantiate",
+                    "failure: {:?}", failure
+                );
+
+                Err(revert_with_error(failure.error()))
+            }
         }
     }
 }
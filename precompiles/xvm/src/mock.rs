@@ -83,6 +83,30 @@ impl AddressMapping<TestAccount> for TestAccount {
     }
 }
 
+impl UnifiedAddressMapper<TestAccount> for TestAccount {
+    fn to_h160(account_id: &TestAccount) -> Option<H160> {
+        match account_id {
+            TestAccount::Bogus => None,
+            account => Some(account.clone().into()),
+        }
+    }
+
+    fn to_h160_or_default(account_id: &TestAccount) -> H160 {
+        account_id.clone().into()
+    }
+
+    fn to_account_id(evm_address: &H160) -> Option<TestAccount> {
+        match TestAccount::into_account_id(*evm_address) {
+            TestAccount::Bogus => None,
+            account => Some(account),
+        }
+    }
+
+    fn to_account_id_or_default(evm_address: &H160) -> TestAccount {
+        TestAccount::into_account_id(*evm_address)
+    }
+}
+
 impl From<H160> for TestAccount {
     fn from(x: H160) -> TestAccount {
         TestAccount::into_account_id(x)
@@ -150,11 +174,11 @@ pub struct TestPrecompileSet<R>(PhantomData<R>);
 impl<R> PrecompileSet for TestPrecompileSet<R>
 where
     R: pallet_evm::Config + pallet_xvm::Config,
-    XvmPrecompile<R>: Precompile,
+    XvmPrecompile<R, TestAccount>: Precompile,
 {
     fn execute(&self, handle: &mut impl PrecompileHandle) -> Option<PrecompileResult> {
         match handle.code_address() {
-            a if a == PRECOMPILE_ADDRESS => Some(XvmPrecompile::<R>::execute(handle)),
+            a if a == PRECOMPILE_ADDRESS => Some(XvmPrecompile::<R, TestAccount>::execute(handle)),
             _ => None,
         }
     }
@@ -218,10 +242,27 @@ impl pallet_evm::Config for Runtime {
     type WeightInfo = ();
 }
 
+parameter_types! {
+    pub const MaxInboxSize: u32 = 16;
+    pub const MaxMessageSize: u32 = 1024;
+    pub const MaxDeliveriesPerBlock: u32 = 16;
+    pub DeliveryWeight: Weight = Weight::from_ref_time(1_000_000_000);
+}
+
 impl pallet_xvm::Config for Runtime {
     type RuntimeEvent = RuntimeEvent;
     type SyncVM = ();
     type AsyncVM = ();
+    type MaxInboxSize = MaxInboxSize;
+    type MaxMessageSize = MaxMessageSize;
+    type MaxDeliveriesPerBlock = MaxDeliveriesPerBlock;
+    type DeliveryWeight = DeliveryWeight;
+    type MaxCallDepth = frame_support::traits::ConstU32<4>;
+    type MaxScannedEvents = frame_support::traits::ConstU32<64>;
+    type ManagerOrigin = frame_system::EnsureRoot<AccountId>;
+    type WeightInfo = ();
+    #[cfg(feature = "runtime-benchmarks")]
+    type BenchmarkVmId = ();
 }
 
 // Configure a mock runtime to test the pallet.
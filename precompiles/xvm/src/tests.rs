@@ -19,6 +19,7 @@
 use crate::mock::*;
 use crate::*;
 
+use pallet_xvm::XvmError;
 use parity_scale_codec::Encode;
 use precompile_utils::testing::*;
 use precompile_utils::EvmDataWriter;
@@ -73,11 +74,105 @@ fn correct_arguments_works() {
                     .build(),
             )
             .expect_no_logs()
-            .execute_returns(
-                EvmDataWriter::new()
-                    .write(false) // the XVM call should succeed but the internal should fail
-                    .write(vec![0u8])
+            .execute_reverts(|output| {
+                // no VM is configured, so the call fails with `VmNotRecognized`
+                output
+                    == EvmDataWriter::new_with_selector(u32::from_be_bytes([
+                        XVM_ERROR_SELECTOR[0],
+                        XVM_ERROR_SELECTOR[1],
+                        XVM_ERROR_SELECTOR[2],
+                        XVM_ERROR_SELECTOR[3],
+                    ]))
+                    .write(XvmError::VmNotRecognized.code())
+                    .write(Bytes(Vec::new()))
+                    .build()
+            });
+    })
+}
+
+#[test]
+fn legacy_context_is_accepted() {
+    // context as encoded by contracts deployed before value was added
+    let context = pallet_xvm::XvmContextV1 {
+        id: 0x0F,
+        ..Default::default()
+    };
+    ExtBuilder::default().build().execute_with(|| {
+        precompiles()
+            .prepare_test(
+                TestAccount::Alice,
+                PRECOMPILE_ADDRESS,
+                EvmDataWriter::new_with_selector(Action::XvmCall)
+                    .write(Bytes(context.encode()))
+                    .write(Bytes(b"".to_vec()))
+                    .write(Bytes(b"".to_vec()))
+                    .write(Bytes(b"".to_vec()))
+                    .build(),
+            )
+            .expect_no_logs()
+            .execute_reverts(|output| {
+                output
+                    == EvmDataWriter::new_with_selector(u32::from_be_bytes([
+                        XVM_ERROR_SELECTOR[0],
+                        XVM_ERROR_SELECTOR[1],
+                        XVM_ERROR_SELECTOR[2],
+                        XVM_ERROR_SELECTOR[3],
+                    ]))
+                    .write(XvmError::VmNotRecognized.code())
+                    .write(Bytes(Vec::new()))
+                    .build()
+            });
+    })
+}
+
+#[test]
+fn query_call_is_not_payable() {
+    let context: XvmContext = Default::default();
+    ExtBuilder::default().build().execute_with(|| {
+        precompiles()
+            .prepare_test(
+                TestAccount::Alice,
+                PRECOMPILE_ADDRESS,
+                EvmDataWriter::new_with_selector(Action::XvmQueryCall)
+                    .write(Bytes(context.encode()))
+                    .write(Bytes(b"".to_vec()))
+                    .write(Bytes(b"".to_vec()))
+                    .write(Bytes(b"".to_vec()))
                     .build(),
-            );
+            )
+            .with_value(1)
+            .expect_no_logs()
+            .execute_reverts(|output| output == b"function is not payable");
+    })
+}
+
+#[test]
+fn instantiate_with_unknown_vm_reverts() {
+    let context: XvmContext = Default::default();
+    ExtBuilder::default().build().execute_with(|| {
+        precompiles()
+            .prepare_test(
+                TestAccount::Alice,
+                PRECOMPILE_ADDRESS,
+                EvmDataWriter::new_with_selector(Action::XvmInstantiate)
+                    .write(Bytes(context.encode()))
+                    .write(Bytes(b"".to_vec()))
+                    .write(Bytes(b"".to_vec()))
+                    .write(Bytes(b"".to_vec()))
+                    .build(),
+            )
+            .expect_no_logs()
+            .execute_reverts(|output| {
+                output
+                    == EvmDataWriter::new_with_selector(u32::from_be_bytes([
+                        XVM_ERROR_SELECTOR[0],
+                        XVM_ERROR_SELECTOR[1],
+                        XVM_ERROR_SELECTOR[2],
+                        XVM_ERROR_SELECTOR[3],
+                    ]))
+                    .write(XvmError::VmNotRecognized.code())
+                    .write(Bytes(Vec::new()))
+                    .build()
+            });
     })
 }
@@ -24,6 +24,7 @@
 //!
 //! - `AssetLocationIdConverter` - conversion between local asset Id and cross-chain asset multilocation
 //! - `FixedRateOfForeignAsset` - weight trader for execution payment in foreign asset
+//! - `OracleRateOfForeignAsset` - weight trader for execution payment in foreign asset, priced by an oracle
 //! - `ReserveAssetFilter` - used to check whether asset/origin are a valid reserve location
 //! - `XcmFungibleFeeHandler` - used to handle XCM fee execution fees
 //!
@@ -34,10 +35,13 @@
 
 use frame_support::{
     ensure,
-    traits::{tokens::fungibles, Contains, ContainsPair, Get},
+    traits::{tokens::fungibles, Contains, ContainsPair, Get, UnixTime},
     weights::constants::WEIGHT_REF_TIME_PER_SECOND,
 };
-use sp_runtime::traits::{Bounded, Zero};
+use sp_runtime::{
+    traits::{Bounded, SaturatedConversion, Zero},
+    FixedU128,
+};
 use sp_std::{borrow::Borrow, marker::PhantomData, vec::Vec};
 
 // Polkadot imports
@@ -45,7 +49,9 @@ use xcm::latest::{prelude::*, Weight};
 use xcm_builder::TakeRevenue;
 use xcm_executor::traits::{MatchesFungibles, ShouldExecute, WeightTrader};
 
-use pallet_xc_asset_config::{ExecutionPaymentRate, XcAssetLocation};
+use pallet_xc_asset_config::{
+    ExecutionPaymentRate, OraclePaymentRate, OutOfBoundsRate, XcAssetLocation,
+};
 
 #[cfg(test)]
 mod tests;
@@ -80,17 +86,54 @@ where
     }
 }
 
+/// Weight bought with a single payment asset at a single rate, as tracked by `FixedRateOfForeignAsset`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+struct PaymentAssetLedger {
+    /// Asset Id (as MultiLocation) of the payment asset
+    asset_location: MultiLocation,
+    /// Units per second used for payment
+    units_per_second: u128,
+    /// Weight bought with this asset
+    weight: Weight,
+    /// Consumed amount of this asset
+    consumed: u128,
+}
+
+impl PaymentAssetLedger {
+    /// Refunds as much of `weight` as was bought with this entry, returning the refunded weight
+    /// and the matching amount of the asset.
+    fn refund(&mut self, weight: Weight) -> (Weight, u128) {
+        let refunded = weight.min(self.weight);
+        let amount = self
+            .units_per_second
+            .saturating_mul(refunded.ref_time() as u128)
+            / (WEIGHT_REF_TIME_PER_SECOND as u128);
+
+        self.weight = self.weight.saturating_sub(refunded);
+        self.consumed = self.consumed.saturating_sub(amount);
+
+        (refunded, amount)
+    }
+}
+
 /// Used as weight trader for foreign assets.
 ///
 /// In case foreigin asset is supported as payment asset, XCM execution time
 /// on-chain can be paid by the foreign asset, using the configured rate.
+///
+/// Multiple `BuyExecution` instructions can use different payment assets, or the same asset at different rates,
+/// each tracked in its own ledger entry. Since a refund can only return a single asset, weight is refunded in the
+/// most recently used entry which covers it. When no entry covers the whole refund, the most recently used one is
+/// refunded as much as possible and the remainder is kept pending, to be added to the next refund.
+/// Weight still pending when the trader is dropped is deducted from the most recently used entries,
+/// so the assets paid for it aren't taken as revenue.
 pub struct FixedRateOfForeignAsset<T: ExecutionPaymentRate, R: TakeRevenue> {
     /// Total used weight
     weight: Weight,
-    /// Total consumed assets
-    consumed: u128,
-    /// Asset Id (as MultiLocation) and units per second for payment
-    asset_location_and_units_per_second: Option<(MultiLocation, u128)>,
+    /// Per payment asset and rate ledger, ordered from the least to the most recently used entry
+    ledger: Vec<PaymentAssetLedger>,
+    /// Weight which couldn't be refunded yet
+    pending_refund: Weight,
     _pd: PhantomData<(T, R)>,
 }
 
@@ -98,8 +141,8 @@ impl<T: ExecutionPaymentRate, R: TakeRevenue> WeightTrader for FixedRateOfForeig
     fn new() -> Self {
         Self {
             weight: Weight::zero(),
-            consumed: 0,
-            asset_location_and_units_per_second: None,
+            ledger: Vec::new(),
+            pending_refund: Weight::zero(),
             _pd: PhantomData,
         }
     }
@@ -139,20 +182,22 @@ impl<T: ExecutionPaymentRate, R: TakeRevenue> WeightTrader for FixedRateOfForeig
 
                     self.weight = self.weight.saturating_add(weight);
 
-                    // If there are multiple calls to `BuyExecution` but with different assets, we need to be able to handle that.
-                    // Current primitive implementation will just keep total track of consumed asset for the FIRST consumed asset.
-                    // Others will just be ignored when refund is concerned.
-                    if let Some((old_asset_location, _)) =
-                        self.asset_location_and_units_per_second.clone()
-                    {
-                        if old_asset_location == asset_location {
-                            self.consumed = self.consumed.saturating_add(amount);
-                        }
-                    } else {
-                        self.consumed = self.consumed.saturating_add(amount);
-                        self.asset_location_and_units_per_second =
-                            Some((asset_location, units_per_second));
-                    }
+                    // Entry becomes the most recently used one, so it's moved to the end of the ledger.
+                    let mut entry = match self.ledger.iter().position(|entry| {
+                        entry.asset_location == asset_location
+                            && entry.units_per_second == units_per_second
+                    }) {
+                        Some(index) => self.ledger.remove(index),
+                        None => PaymentAssetLedger {
+                            asset_location,
+                            units_per_second,
+                            weight: Weight::zero(),
+                            consumed: 0,
+                        },
+                    };
+                    entry.weight = entry.weight.saturating_add(weight);
+                    entry.consumed = entry.consumed.saturating_add(amount);
+                    self.ledger.push(entry);
 
                     Ok(unused)
                 } else {
@@ -166,21 +211,24 @@ impl<T: ExecutionPaymentRate, R: TakeRevenue> WeightTrader for FixedRateOfForeig
     fn refund_weight(&mut self, weight: Weight) -> Option<MultiAsset> {
         log::trace!(target: "xcm::weight", "FixedRateOfForeignAsset::refund_weight weight: {:?}", weight);
 
-        if let Some((asset_location, units_per_second)) =
-            self.asset_location_and_units_per_second.clone()
-        {
-            let weight = weight.min(self.weight);
-            let amount = units_per_second.saturating_mul(weight.ref_time() as u128)
-                / (WEIGHT_REF_TIME_PER_SECOND as u128);
-
-            self.weight = self.weight.saturating_sub(weight);
-            self.consumed = self.consumed.saturating_sub(amount);
-
-            if amount > 0 {
-                Some((asset_location, amount).into())
-            } else {
-                None
-            }
+        let weight = weight.saturating_add(self.pending_refund).min(self.weight);
+        let index = self
+            .ledger
+            .iter()
+            .rposition(|entry| entry.weight.all_gte(weight))
+            .or_else(|| {
+                self.ledger
+                    .iter()
+                    .rposition(|entry| !entry.weight.is_zero())
+            })?;
+        let entry = &mut self.ledger[index];
+
+        let (refunded, amount) = entry.refund(weight);
+        self.pending_refund = weight.saturating_sub(refunded);
+        self.weight = self.weight.saturating_sub(refunded);
+
+        if amount > 0 {
+            Some((entry.asset_location, amount).into())
         } else {
             None
         }
@@ -189,14 +237,77 @@ impl<T: ExecutionPaymentRate, R: TakeRevenue> WeightTrader for FixedRateOfForeig
 
 impl<T: ExecutionPaymentRate, R: TakeRevenue> Drop for FixedRateOfForeignAsset<T, R> {
     fn drop(&mut self) {
-        if let Some((asset_location, _)) = self.asset_location_and_units_per_second.clone() {
-            if self.consumed > 0 {
-                R::take_revenue((asset_location, self.consumed).into());
+        let mut pending_refund = self.pending_refund;
+        for entry in self.ledger.iter_mut().rev() {
+            if pending_refund.is_zero() {
+                break;
+            }
+            let (refunded, _) = entry.refund(pending_refund);
+            pending_refund = pending_refund.saturating_sub(refunded);
+        }
+
+        for entry in self.ledger.iter() {
+            if entry.consumed > 0 {
+                R::take_revenue((entry.asset_location, entry.consumed).into());
             }
         }
     }
 }
 
+/// Provides prices of cross-chain assets relative to the native currency.
+pub trait PriceProvider {
+    /// returns price of a whole asset token in whole native tokens, as reported by the oracle feed,
+    /// along with the unix time in milliseconds of its last update.
+    fn get_price(feed_id: u32) -> Option<(FixedU128, u64)>;
+}
+
+/// Execution payment rate derived from an oracle price.
+///
+/// For assets with an oracle source, `units per second` are computed from the price reported by `P`.
+/// Rates outside of the source bounds are clamped or ignored, as the source specifies. If the asset
+/// has no oracle source, the price isn't available, is older than the source allows according to `N`,
+/// or its rate is ignored, the rate configured in `T` is used instead.
+pub struct OracleExecutionPaymentRate<T, P, N>(PhantomData<(T, P, N)>);
+impl<T: ExecutionPaymentRate + OraclePaymentRate, P: PriceProvider, N: UnixTime>
+    ExecutionPaymentRate for OracleExecutionPaymentRate<T, P, N>
+{
+    fn get_units_per_second(asset_location: MultiLocation) -> Option<u128> {
+        T::get_oracle_source(asset_location.clone())
+            .and_then(|source| {
+                let (price, updated_at) = P::get_price(source.feed_id)?;
+                let now = N::now().as_millis().saturated_into::<u64>();
+                if now.saturating_sub(updated_at) > source.max_price_age {
+                    log::trace!(
+                        target: "xcm::weight",
+                        "OracleExecutionPaymentRate price of feed {:?} is stale, updated at {:?}",
+                        source.feed_id, updated_at,
+                    );
+                    return None;
+                }
+
+                let units_per_second =
+                    T::units_per_second_for_price(asset_location.clone(), price)?;
+                match source.out_of_bounds {
+                    OutOfBoundsRate::Clamp => Some(
+                        units_per_second
+                            .clamp(source.min_units_per_second, source.max_units_per_second),
+                    ),
+                    OutOfBoundsRate::Fallback => (source.min_units_per_second
+                        ..=source.max_units_per_second)
+                        .contains(&units_per_second)
+                        .then_some(units_per_second),
+                }
+            })
+            .or_else(|| T::get_units_per_second(asset_location))
+    }
+}
+
+/// Used as weight trader for foreign assets priced by an oracle.
+///
+/// The rate is evaluated when weight is bought, and then kept for refunds during the same execution.
+pub type OracleRateOfForeignAsset<T, P, N, R> =
+    FixedRateOfForeignAsset<OracleExecutionPaymentRate<T, P, N>, R>;
+
 /// Used to determine whether the cross-chain asset is coming from a trusted reserve or not
 ///
 /// Basically, we trust any cross-chain asset from any location to act as a reserve since
@@ -17,11 +17,14 @@
 // along with Astar. If not, see <http://www.gnu.org/licenses/>.
 
 use super::*;
+use core::time::Duration;
 use frame_support::{
     assert_ok,
     traits::{Everything, Nothing},
 };
-use sp_runtime::traits::Zero;
+use pallet_xc_asset_config::OracleSource;
+use sp_runtime::{traits::Zero, FixedPointNumber};
+use sp_std::cell::RefCell;
 use xcm_executor::traits::Convert;
 
 type AssetId = u128;
@@ -73,6 +76,114 @@ impl ExecutionPaymentRate for ExecutionPayment {
     }
 }
 
+/// Oracle configuration used for testing `OracleRateOfForeignAsset`
+const ORACLE_SOURCE: OracleSource = OracleSource {
+    feed_id: 1,
+    min_units_per_second: 1_000_000,
+    max_units_per_second: 10_000_000,
+    out_of_bounds: OutOfBoundsRate::Clamp,
+    max_price_age: 60_000,
+};
+impl OraclePaymentRate for ExecutionPayment {
+    fn get_oracle_source(asset_location: MultiLocation) -> Option<OracleSource> {
+        match asset_location {
+            a if a == PARACHAIN => Some(ORACLE_SOURCE),
+            a if a == GENERAL_INDEX => Some(OracleSource {
+                feed_id: 2,
+                ..ORACLE_SOURCE
+            }),
+            _ => None,
+        }
+    }
+
+    // One whole native token per second, asset has 6 decimals
+    fn units_per_second_for_price(
+        _asset_location: MultiLocation,
+        relative_price: FixedU128,
+    ) -> Option<u128> {
+        relative_price.reciprocal()?.checked_mul_int(1_000_000)
+    }
+}
+
+/// Helper struct used for testing oracle rates outside of the source bounds being ignored
+struct FallbackExecutionPayment;
+impl ExecutionPaymentRate for FallbackExecutionPayment {
+    fn get_units_per_second(asset_location: MultiLocation) -> Option<u128> {
+        ExecutionPayment::get_units_per_second(asset_location)
+    }
+}
+impl OraclePaymentRate for FallbackExecutionPayment {
+    fn get_oracle_source(asset_location: MultiLocation) -> Option<OracleSource> {
+        ExecutionPayment::get_oracle_source(asset_location).map(|source| OracleSource {
+            out_of_bounds: OutOfBoundsRate::Fallback,
+            ..source
+        })
+    }
+
+    fn units_per_second_for_price(
+        asset_location: MultiLocation,
+        relative_price: FixedU128,
+    ) -> Option<u128> {
+        ExecutionPayment::units_per_second_for_price(asset_location, relative_price)
+    }
+}
+
+/// Current unix time in milliseconds, as reported by `MockTime`
+const NOW: u64 = 1_000_000_000;
+
+/// Helper struct used for testing oracle price age
+struct MockTime;
+impl UnixTime for MockTime {
+    fn now() -> Duration {
+        Duration::from_millis(NOW)
+    }
+}
+
+/// Helper struct used for testing `OracleRateOfForeignAsset`
+struct MockOracle;
+impl PriceProvider for MockOracle {
+    fn get_price(feed_id: u32) -> Option<(FixedU128, u64)> {
+        match feed_id {
+            1 => Some((FixedU128::saturating_from_rational(1, 4), NOW)),
+            _ => None,
+        }
+    }
+}
+
+thread_local! {
+    static REVENUE: RefCell<Vec<MultiAsset>> = RefCell::new(Vec::new());
+    static UNITS_PER_SECOND: RefCell<u128> = RefCell::new(1_000_000);
+}
+
+/// Helper struct used for testing rate changes during an execution
+struct VariableExecutionPayment;
+impl ExecutionPaymentRate for VariableExecutionPayment {
+    fn get_units_per_second(_asset_location: MultiLocation) -> Option<u128> {
+        Some(UNITS_PER_SECOND.with(|rate| *rate.borrow()))
+    }
+}
+
+/// Helper struct used for testing revenue taken by `FixedRateOfForeignAsset`
+struct MockRevenue;
+impl TakeRevenue for MockRevenue {
+    fn take_revenue(revenue: MultiAsset) {
+        REVENUE.with(|r| r.borrow_mut().push(revenue));
+    }
+}
+
+/// Amount of the payment asset consumed by the trader, at any rate
+fn consumed<T: ExecutionPaymentRate, R: TakeRevenue>(
+    trader: &FixedRateOfForeignAsset<T, R>,
+    asset_location: MultiLocation,
+) -> u128 {
+    trader
+        .ledger
+        .iter()
+        .filter(|entry| entry.asset_location == asset_location)
+        .map(|entry| entry.consumed)
+        .sum()
+}
+
 /// Execution fee for the specified weight, using provided `units_per_second`
 fn execution_fee(weight: Weight, units_per_second: u128) -> u128 {
     units_per_second * (weight.ref_time() as u128) / (WEIGHT_REF_TIME_PER_SECOND as u128)
@@ -152,10 +263,13 @@ fn fixed_rate_of_foreign_asset_buy_is_ok() {
             &MultiAsset::from((PARENT, total_payment - expected_execution_fee)).into()
         ));
 
-        assert_eq!(fixed_rate_trader.consumed, expected_execution_fee);
+        assert_eq!(consumed(&fixed_rate_trader, PARENT), expected_execution_fee);
         assert_eq!(fixed_rate_trader.weight, weight);
         assert_eq!(
-            fixed_rate_trader.asset_location_and_units_per_second,
+            fixed_rate_trader
+                .ledger
+                .last()
+                .map(|entry| (entry.asset_location, entry.units_per_second)),
             Some((
                 PARENT,
                 ExecutionPayment::get_units_per_second(PARENT).unwrap()
@@ -166,7 +280,10 @@ fn fixed_rate_of_foreign_asset_buy_is_ok() {
     }
 
     // 2. Buy more weight, using the same trader and asset type. Verify it works as expected.
-    let (old_weight, old_consumed) = (fixed_rate_trader.weight, fixed_rate_trader.consumed);
+    let (old_weight, old_consumed) = (
+        fixed_rate_trader.weight,
+        consumed(&fixed_rate_trader, PARENT),
+    );
 
     let weight: Weight = Weight::from_ref_time(3_500_000_000);
     let expected_execution_fee = execution_fee(
@@ -184,12 +301,15 @@ fn fixed_rate_of_foreign_asset_buy_is_ok() {
         ));
 
         assert_eq!(
-            fixed_rate_trader.consumed,
+            consumed(&fixed_rate_trader, PARENT),
             expected_execution_fee + old_consumed
         );
         assert_eq!(fixed_rate_trader.weight, weight + old_weight);
         assert_eq!(
-            fixed_rate_trader.asset_location_and_units_per_second,
+            fixed_rate_trader
+                .ledger
+                .last()
+                .map(|entry| (entry.asset_location, entry.units_per_second)),
             Some((
                 PARENT,
                 ExecutionPayment::get_units_per_second(PARENT).unwrap()
@@ -200,7 +320,10 @@ fn fixed_rate_of_foreign_asset_buy_is_ok() {
     }
 
     // 3. Buy even more weight, but use a different type of asset now while reusing the old trader instance.
-    let (old_weight, old_consumed) = (fixed_rate_trader.weight, fixed_rate_trader.consumed);
+    let (old_weight, old_consumed) = (
+        fixed_rate_trader.weight,
+        consumed(&fixed_rate_trader, PARENT),
+    );
 
     // Note that the concrete asset type differs now from previous buys
     let total_payment = 20_000;
@@ -225,14 +348,21 @@ fn fixed_rate_of_foreign_asset_buy_is_ok() {
         ));
 
         assert_eq!(fixed_rate_trader.weight, weight + old_weight);
-        // We don't expect this to change since trader already contains data about previous asset type.
-        // Current rule is not to update in this case.
-        assert_eq!(fixed_rate_trader.consumed, old_consumed);
+        // Previous asset is tracked separately, so its consumed amount doesn't change.
+        assert_eq!(consumed(&fixed_rate_trader, PARENT), old_consumed);
+        assert_eq!(
+            consumed(&fixed_rate_trader, PARACHAIN),
+            expected_execution_fee
+        );
+        assert_eq!(fixed_rate_trader.ledger.len(), 2);
         assert_eq!(
-            fixed_rate_trader.asset_location_and_units_per_second,
+            fixed_rate_trader
+                .ledger
+                .last()
+                .map(|entry| (entry.asset_location, entry.units_per_second)),
             Some((
-                PARENT,
-                ExecutionPayment::get_units_per_second(PARENT).unwrap()
+                PARACHAIN,
+                ExecutionPayment::get_units_per_second(PARACHAIN).unwrap()
             ))
         );
     } else {
@@ -300,26 +430,409 @@ fn fixed_rate_of_foreign_asset_refund_is_ok() {
     // Refund quarter and expect it to pass
     let weight_to_refund = weight / 4;
     let assets_to_refund = expected_execution_fee / 4;
-    let (old_weight, old_consumed) = (fixed_rate_trader.weight, fixed_rate_trader.consumed);
+    let (old_weight, old_consumed) = (
+        fixed_rate_trader.weight,
+        consumed(&fixed_rate_trader, PARENT),
+    );
 
     let result = fixed_rate_trader.refund_weight(weight_to_refund);
     if let Some(asset_location) = result {
         assert_eq!(asset_location, (PARENT, assets_to_refund).into());
 
         assert_eq!(fixed_rate_trader.weight, old_weight - weight_to_refund);
-        assert_eq!(fixed_rate_trader.consumed, old_consumed - assets_to_refund);
+        assert_eq!(
+            consumed(&fixed_rate_trader, PARENT),
+            old_consumed - assets_to_refund
+        );
     }
 
     // Refund more than remains and expect it to pass (saturated)
-    let assets_to_refund = fixed_rate_trader.consumed;
+    let assets_to_refund = consumed(&fixed_rate_trader, PARENT);
 
     let result = fixed_rate_trader.refund_weight(weight + Weight::from_ref_time(10000));
     if let Some(asset_location) = result {
         assert_eq!(asset_location, (PARENT, assets_to_refund).into());
 
         assert!(fixed_rate_trader.weight.is_zero());
-        assert!(fixed_rate_trader.consumed.is_zero());
+        assert!(consumed(&fixed_rate_trader, PARENT).is_zero());
+    }
+}
+
+#[test]
+fn fixed_rate_of_foreign_asset_multiple_assets_refund_is_ok() {
+    let mut fixed_rate_trader = FixedRateOfForeignAsset::<ExecutionPayment, ()>::new();
+
+    let total_payment = 100_000;
+    let parent_weight = Weight::from_ref_time(2_000_000_000);
+    let parachain_weight = Weight::from_ref_time(1_000_000_000);
+    let parent_fee = execution_fee(
+        parent_weight,
+        ExecutionPayment::get_units_per_second(PARENT).unwrap(),
+    );
+    let parachain_fee = execution_fee(
+        parachain_weight,
+        ExecutionPayment::get_units_per_second(PARACHAIN).unwrap(),
+    );
+
+    // Buy weight with two different assets
+    assert_ok!(fixed_rate_trader.buy_weight(
+        parent_weight,
+        MultiAsset::from((PARENT, total_payment)).into()
+    ));
+    assert_ok!(fixed_rate_trader.buy_weight(
+        parachain_weight,
+        MultiAsset::from((PARACHAIN, total_payment)).into()
+    ));
+    assert_eq!(fixed_rate_trader.weight, parent_weight + parachain_weight);
+
+    // Refund is done in the most recently used asset
+    assert_eq!(
+        fixed_rate_trader.refund_weight(parachain_weight / 2),
+        Some((PARACHAIN, parachain_fee / 2).into())
+    );
+    assert_eq!(consumed(&fixed_rate_trader, PARACHAIN), parachain_fee / 2);
+
+    // Unless the weight bought with it doesn't cover the refund
+    assert_eq!(
+        fixed_rate_trader.refund_weight(parent_weight / 2),
+        Some((PARENT, parent_fee / 2).into())
+    );
+    assert_eq!(consumed(&fixed_rate_trader, PARENT), parent_fee / 2);
+    assert_eq!(consumed(&fixed_rate_trader, PARACHAIN), parachain_fee / 2);
+    assert_eq!(
+        fixed_rate_trader.weight,
+        parent_weight / 2 + parachain_weight / 2
+    );
+
+    // Buying with the first asset again makes it the most recently used one
+    assert_ok!(fixed_rate_trader.buy_weight(
+        parachain_weight,
+        MultiAsset::from((PARACHAIN, total_payment)).into()
+    ));
+    assert_ok!(fixed_rate_trader.buy_weight(
+        parent_weight,
+        MultiAsset::from((PARENT, total_payment)).into()
+    ));
+    assert_eq!(
+        fixed_rate_trader.refund_weight(parent_weight / 4),
+        Some((PARENT, parent_fee / 4).into())
+    );
+    assert_eq!(
+        consumed(&fixed_rate_trader, PARACHAIN),
+        parachain_fee / 2 + parachain_fee
+    );
+}
+
+#[test]
+fn fixed_rate_of_foreign_asset_refund_exceeding_last_asset_is_ok() {
+    let mut fixed_rate_trader = FixedRateOfForeignAsset::<ExecutionPayment, ()>::new();
+
+    let total_payment = 100_000;
+    let weight = Weight::from_ref_time(1_000_000_000);
+    let parent_fee = execution_fee(
+        weight,
+        ExecutionPayment::get_units_per_second(PARENT).unwrap(),
+    );
+    let parachain_fee = execution_fee(
+        weight,
+        ExecutionPayment::get_units_per_second(PARACHAIN).unwrap(),
+    );
+
+    assert_ok!(
+        fixed_rate_trader.buy_weight(weight, MultiAsset::from((PARENT, total_payment)).into())
+    );
+    assert_ok!(
+        fixed_rate_trader.buy_weight(weight, MultiAsset::from((PARACHAIN, total_payment)).into())
+    );
+
+    // No asset covers the refund, the most recently used one is fully refunded
+    assert_eq!(
+        fixed_rate_trader.refund_weight(weight + weight / 2),
+        Some((PARACHAIN, parachain_fee).into())
+    );
+    assert!(consumed(&fixed_rate_trader, PARACHAIN).is_zero());
+    assert_eq!(fixed_rate_trader.pending_refund, weight / 2);
+
+    // The remainder is refunded together with the next refund
+    assert_eq!(
+        fixed_rate_trader.refund_weight(weight / 2),
+        Some((PARENT, parent_fee).into())
+    );
+    assert!(consumed(&fixed_rate_trader, PARENT).is_zero());
+    assert!(fixed_rate_trader.pending_refund.is_zero());
+    assert!(fixed_rate_trader.weight.is_zero());
+}
+
+#[test]
+fn fixed_rate_of_foreign_asset_pending_refund_is_not_revenue() {
+    REVENUE.with(|r| r.borrow_mut().clear());
+
+    let total_payment = 100_000;
+    let weight = Weight::from_ref_time(1_000_000_000);
+    let parent_units_per_second = ExecutionPayment::get_units_per_second(PARENT).unwrap();
+    let parent_fee = execution_fee(weight, parent_units_per_second);
+    let parachain_fee = execution_fee(
+        weight,
+        ExecutionPayment::get_units_per_second(PARACHAIN).unwrap(),
+    );
+
+    {
+        let mut fixed_rate_trader = FixedRateOfForeignAsset::<ExecutionPayment, MockRevenue>::new();
+        assert_ok!(
+            fixed_rate_trader.buy_weight(weight, MultiAsset::from((PARENT, total_payment)).into())
+        );
+        assert_ok!(fixed_rate_trader
+            .buy_weight(weight, MultiAsset::from((PARACHAIN, total_payment)).into()));
+
+        // Refund exceeding the last entry is only partially returned
+        assert_eq!(
+            fixed_rate_trader.refund_weight(weight + weight / 2),
+            Some((PARACHAIN, parachain_fee).into())
+        );
+        assert_eq!(fixed_rate_trader.pending_refund, weight / 2);
+    }
+
+    // Pending weight is deducted from the revenue, so the whole refund is accounted for
+    REVENUE.with(|r| {
+        assert_eq!(
+            *r.borrow(),
+            vec![MultiAsset::from((
+                PARENT,
+                parent_fee - execution_fee(weight / 2, parent_units_per_second)
+            ))]
+        )
+    });
+}
+
+#[test]
+fn fixed_rate_of_foreign_asset_refund_is_bounded_by_bought_weight() {
+    let mut fixed_rate_trader = FixedRateOfForeignAsset::<ExecutionPayment, ()>::new();
+
+    let total_payment = 100_000;
+    let weight = Weight::from_ref_time(1_000_000_000);
+    let parent_fee = execution_fee(
+        weight,
+        ExecutionPayment::get_units_per_second(PARENT).unwrap(),
+    );
+
+    assert_ok!(
+        fixed_rate_trader.buy_weight(weight, MultiAsset::from((PARENT, total_payment)).into())
+    );
+
+    // Weight exceeding the bought one isn't kept pending
+    assert_eq!(
+        fixed_rate_trader.refund_weight(weight + weight),
+        Some((PARENT, parent_fee).into())
+    );
+    assert!(fixed_rate_trader.pending_refund.is_zero());
+    assert!(fixed_rate_trader.weight.is_zero());
+    assert_eq!(fixed_rate_trader.refund_weight(weight), None);
+}
+
+#[test]
+fn fixed_rate_of_foreign_asset_refund_uses_buying_rate() {
+    let mut fixed_rate_trader = FixedRateOfForeignAsset::<VariableExecutionPayment, ()>::new();
+
+    let total_payment = 100_000;
+    let weight = Weight::from_ref_time(1_000_000_000);
+
+    UNITS_PER_SECOND.with(|rate| *rate.borrow_mut() = 1_000_000);
+    assert_ok!(
+        fixed_rate_trader.buy_weight(weight, MultiAsset::from((PARENT, total_payment)).into())
+    );
+    UNITS_PER_SECOND.with(|rate| *rate.borrow_mut() = 3_000_000);
+    assert_ok!(
+        fixed_rate_trader.buy_weight(weight, MultiAsset::from((PARENT, total_payment)).into())
+    );
+    assert_eq!(
+        consumed(&fixed_rate_trader, PARENT),
+        execution_fee(weight, 1_000_000) + execution_fee(weight, 3_000_000)
+    );
+
+    // Each purchase is refunded at its own rate, the most recent one first
+    assert_eq!(
+        fixed_rate_trader.refund_weight(weight),
+        Some((PARENT, execution_fee(weight, 3_000_000)).into())
+    );
+    assert_eq!(
+        fixed_rate_trader.refund_weight(weight),
+        Some((PARENT, execution_fee(weight, 1_000_000)).into())
+    );
+    assert!(consumed(&fixed_rate_trader, PARENT).is_zero());
+}
+
+#[test]
+fn fixed_rate_of_foreign_asset_revenue_per_asset() {
+    REVENUE.with(|r| r.borrow_mut().clear());
+
+    let total_payment = 100_000;
+    let weight = Weight::from_ref_time(1_000_000_000);
+    let parent_fee = execution_fee(
+        weight,
+        ExecutionPayment::get_units_per_second(PARENT).unwrap(),
+    );
+    let parachain_fee = execution_fee(
+        weight,
+        ExecutionPayment::get_units_per_second(PARACHAIN).unwrap(),
+    );
+
+    {
+        let mut fixed_rate_trader = FixedRateOfForeignAsset::<ExecutionPayment, MockRevenue>::new();
+        assert_ok!(
+            fixed_rate_trader.buy_weight(weight, MultiAsset::from((PARENT, total_payment)).into())
+        );
+        assert_ok!(fixed_rate_trader
+            .buy_weight(weight, MultiAsset::from((PARACHAIN, total_payment)).into()));
+        assert_ok!(fixed_rate_trader.buy_weight(
+            weight,
+            MultiAsset::from((GENERAL_INDEX, total_payment)).into()
+        ));
+
+        // Fully refunded asset doesn't produce any revenue
+        assert!(fixed_rate_trader.refund_weight(weight).is_some());
     }
+
+    // Revenue is taken separately for each consumed asset
+    REVENUE.with(|r| {
+        assert_eq!(
+            *r.borrow(),
+            vec![
+                MultiAsset::from((PARENT, parent_fee)),
+                MultiAsset::from((PARACHAIN, parachain_fee)),
+            ]
+        )
+    });
+}
+
+#[test]
+fn oracle_execution_payment_rate_is_ok() {
+    type OracleRate = OracleExecutionPaymentRate<ExecutionPayment, MockOracle, MockTime>;
+
+    // Asset without oracle source uses the stored rate
+    assert_eq!(OracleRate::get_units_per_second(PARENT), Some(1_000_000));
+
+    // Asset priced by the oracle
+    assert_eq!(OracleRate::get_units_per_second(PARACHAIN), Some(4_000_000));
+
+    // Price isn't available so the stored rate is used
+    assert_eq!(
+        OracleRate::get_units_per_second(GENERAL_INDEX),
+        Some(3_000_000)
+    );
+
+    // Unsupported asset
+    assert!(OracleRate::get_units_per_second(MultiLocation::here()).is_none());
+}
+
+#[test]
+fn oracle_execution_payment_rate_is_bounded() {
+    type OracleRate<P> = OracleExecutionPaymentRate<ExecutionPayment, P, MockTime>;
+    type FallbackRate<P> = OracleExecutionPaymentRate<FallbackExecutionPayment, P, MockTime>;
+
+    struct BoundedOracle;
+    impl PriceProvider for BoundedOracle {
+        fn get_price(_feed_id: u32) -> Option<(FixedU128, u64)> {
+            // Too cheap, rate above upper bound
+            Some((FixedU128::saturating_from_rational(1, 100), NOW))
+        }
+    }
+    assert_eq!(
+        OracleRate::<BoundedOracle>::get_units_per_second(PARACHAIN),
+        Some(ORACLE_SOURCE.max_units_per_second)
+    );
+
+    struct ExpensiveOracle;
+    impl PriceProvider for ExpensiveOracle {
+        fn get_price(_feed_id: u32) -> Option<(FixedU128, u64)> {
+            // Too expensive, rate below lower bound
+            Some((FixedU128::from(100), NOW))
+        }
+    }
+    assert_eq!(
+        OracleRate::<ExpensiveOracle>::get_units_per_second(PARACHAIN),
+        Some(ORACLE_SOURCE.min_units_per_second)
+    );
+
+    // Sources falling back use the stored rate instead
+    assert_eq!(
+        FallbackRate::<BoundedOracle>::get_units_per_second(PARACHAIN),
+        Some(2_000_000)
+    );
+    assert_eq!(
+        FallbackRate::<ExpensiveOracle>::get_units_per_second(PARACHAIN),
+        Some(2_000_000)
+    );
+    assert_eq!(
+        FallbackRate::<MockOracle>::get_units_per_second(PARACHAIN),
+        Some(4_000_000)
+    );
+}
+
+#[test]
+fn oracle_execution_payment_rate_ignores_stale_price() {
+    type OracleRate<P> = OracleExecutionPaymentRate<ExecutionPayment, P, MockTime>;
+
+    struct OldOracle;
+    impl PriceProvider for OldOracle {
+        fn get_price(_feed_id: u32) -> Option<(FixedU128, u64)> {
+            Some((
+                FixedU128::saturating_from_rational(1, 4),
+                NOW - ORACLE_SOURCE.max_price_age,
+            ))
+        }
+    }
+    assert_eq!(
+        OracleRate::<OldOracle>::get_units_per_second(PARACHAIN),
+        Some(4_000_000)
+    );
+
+    struct StaleOracle;
+    impl PriceProvider for StaleOracle {
+        fn get_price(_feed_id: u32) -> Option<(FixedU128, u64)> {
+            Some((
+                FixedU128::saturating_from_rational(1, 4),
+                NOW - ORACLE_SOURCE.max_price_age - 1,
+            ))
+        }
+    }
+    assert_eq!(
+        OracleRate::<StaleOracle>::get_units_per_second(PARACHAIN),
+        Some(2_000_000)
+    );
+}
+
+#[test]
+fn oracle_rate_of_foreign_asset_buy_and_refund_is_ok() {
+    let mut oracle_rate_trader =
+        OracleRateOfForeignAsset::<ExecutionPayment, MockOracle, MockTime, ()>::new();
+
+    let total_payment = 100_000;
+    let payment_multi_asset = MultiAsset {
+        id: xcm::latest::AssetId::Concrete(PARACHAIN),
+        fun: Fungibility::Fungible(total_payment),
+    };
+    let weight: Weight = Weight::from_ref_time(1_000_000_000);
+
+    // Fee is computed using the oracle rate, not the stored one
+    let expected_execution_fee = execution_fee(weight, 4_000_000);
+    assert!(expected_execution_fee > 0); // sanity check
+
+    let unused = oracle_rate_trader
+        .buy_weight(weight, payment_multi_asset.into())
+        .expect("Should have been `Ok` wrapped Assets!");
+    assert_ok!(unused.ensure_contains(
+        &MultiAsset::from((PARACHAIN, total_payment - expected_execution_fee)).into()
+    ));
+    assert_eq!(
+        consumed(&oracle_rate_trader, PARACHAIN),
+        expected_execution_fee
+    );
+
+    // Refund uses the same rate
+    assert_eq!(
+        oracle_rate_trader.refund_weight(weight / 2),
+        Some((PARACHAIN, expected_execution_fee / 2).into())
+    );
 }
 
 #[test]